@@ -1,23 +1,95 @@
-use budget_lib::Budget;
-use clap::{crate_authors, crate_version, value_t, App, AppSettings, ArgMatches};
+use crate::budgets::BudgetDirectory;
+use crate::config::Config;
+use crate::dates;
+use crate::names::{self, NameError};
+use crate::sd::BudgetFile;
+use budget_lib::{Budget, CalendarMonth, CurrencyCode, Money};
+use chrono::NaiveDate;
+use clap::{crate_version, value_t, App, AppSettings, Arg, ArgMatches};
+mod account;
+mod assign;
 mod category;
+mod config;
+mod currency;
+mod decrypt;
+mod encrypt;
+mod forecast;
+mod list_budgets;
+mod new;
+mod payee;
+mod quick;
+mod rate;
+mod report;
+mod rules;
+mod schedule;
+mod shell;
+mod summary;
+mod tag;
 mod transaction;
 mod transfer;
+mod tui;
+mod use_budget;
+use decimal::d128;
 use std::str::FromStr;
 
-pub fn run(budget: &mut Budget) {
-    match get_app().get_matches().subcommand() {
-        ("transaction", Some(submatches)) => transaction::run(budget, submatches),
-        ("category", Some(submatches)) => category::run(budget, submatches),
-        ("list", Some(submatches)) => transaction::list::run(budget, submatches),
-        ("transfer", Some(submatches)) => transfer::run(budget, submatches),
+/// Run the subcommands which don't operate on a single budget, such as managing the
+/// budget directory or the configuration.
+///
+/// Returns false if the subcommand operates on a single budget, and should be run with `run`.
+pub fn run_without_budget(
+    dir: &BudgetDirectory,
+    config: &mut Config,
+    matches: &ArgMatches,
+) -> bool {
+    match matches.subcommand() {
+        ("list-budgets", Some(submatches)) => list_budgets::run(dir, config, submatches),
+        ("use", Some(submatches)) => use_budget::run(dir, config, submatches),
+        ("new", Some(submatches)) => new::run(dir, config, submatches),
+        ("config", Some(submatches)) => config::run(config, submatches),
+        _ => return false,
+    }
+    true
+}
+
+/// Run a subcommand which operates on the budget.
+///
+/// Returns false if the budget shouldn't be saved, such as when the shell was left with 'quit!'.
+pub fn run(
+    budget: &mut Budget,
+    file: &mut BudgetFile,
+    config: &Config,
+    matches: &ArgMatches,
+) -> bool {
+    match matches.subcommand() {
+        ("transaction", Some(submatches)) => transaction::run(budget, config, submatches),
+        ("category", Some(submatches)) => category::run(budget, config, submatches),
+        ("account", Some(submatches)) => account::run(budget, config, submatches),
+        ("currency", Some(submatches)) => currency::run(budget, submatches),
+        ("rate", Some(submatches)) => rate::run(budget, config, submatches),
+        ("report", Some(submatches)) => report::run(budget, config, submatches),
+        ("summary", Some(submatches)) => summary::run(budget, config, submatches),
+        ("assign", Some(submatches)) => assign::run(budget, config, submatches),
+        ("schedule", Some(submatches)) => schedule::run(budget, config, submatches),
+        ("rules", Some(submatches)) => rules::run(budget, config, submatches),
+        ("payee", Some(submatches)) => payee::run(budget, config, submatches),
+        ("tag", Some(submatches)) => tag::run(budget, config, submatches),
+        ("forecast", Some(submatches)) => forecast::run(budget, config, submatches),
+        ("list", Some(submatches)) => transaction::list::run(budget, config, submatches),
+        ("transfer", Some(submatches)) => transfer::run(budget, config, submatches),
+        ("tui", Some(submatches)) => tui::run(budget, config, submatches),
+        ("shell", Some(_)) => return crate::shell::run(budget, file, config),
+        ("encrypt", Some(submatches)) => encrypt::run(file, submatches),
+        ("decrypt", Some(submatches)) => decrypt::run(file, submatches),
+        // anything else is the quick-entry syntax, such as 'budget 4.50 coffee'
+        (name, Some(submatches)) => quick::run(budget, config, name, submatches),
         _ => panic!("app::run is missing something!"),
     }
+    true
 }
 
-fn get_app<'a, 'b>() -> App<'a, 'b> {
+pub fn get_app<'a, 'b>() -> App<'a, 'b> {
     App::new("Budget-Smuggler")
-        .author(crate_authors!("\n"))
+        .author(env!("CARGO_PKG_AUTHORS"))
         .version(crate_version!())
         .about(
             r#"
@@ -56,15 +128,76 @@ fn get_app<'a, 'b>() -> App<'a, 'b> {
                                 
         Personal finance and budgeting app."#,
         )
+        .after_help(
+            "Add a transaction quickly with 'budget [in] <amount> [category] [@payee] [#tag]... \
+             [date]', such as 'budget 4.50 coffee yesterday'.",
+        )
         // app settings
         .setting(AppSettings::AllowNegativeNumbers)
+        .setting(AppSettings::AllowExternalSubcommands)
         .setting(AppSettings::DeriveDisplayOrder)
         .setting(AppSettings::GlobalVersion)
         .setting(AppSettings::SubcommandRequiredElseHelp)
+        // arguments
+        .arg(
+            Arg::with_name("budget")
+                .long("budget")
+                .short("b")
+                .global(true)
+                .takes_value(true)
+                .value_name("name")
+                .conflicts_with("file")
+                .help("The name of the budget to use"),
+        )
+        .arg(
+            Arg::with_name("file")
+                .long("file")
+                .short("f")
+                .global(true)
+                .takes_value(true)
+                .value_name("path")
+                .help("The path of a budget file to use [env: BUDGET_FILE]"),
+        )
+        .arg(
+            Arg::with_name("keyfile")
+                .long("keyfile")
+                .global(true)
+                .takes_value(true)
+                .value_name("path")
+                .help("A file containing the passphrase of an encrypted budget"),
+        )
+        .arg(
+            Arg::with_name("lock timeout")
+                .long("lock-timeout")
+                .global(true)
+                .takes_value(true)
+                .value_name("seconds")
+                .default_value("10")
+                .help("How long to wait for another process using the budget to finish"),
+        )
         // subcommands
+        .subcommand(summary::command())
         .subcommand(transaction::command())
         .subcommand(category::command())
+        .subcommand(account::command())
+        .subcommand(currency::command())
+        .subcommand(rate::command())
+        .subcommand(report::command())
         .subcommand(transfer::command())
+        .subcommand(assign::command())
+        .subcommand(schedule::command())
+        .subcommand(rules::command())
+        .subcommand(payee::command())
+        .subcommand(tag::command())
+        .subcommand(forecast::command())
+        .subcommand(tui::command())
+        .subcommand(shell::command())
+        .subcommand(encrypt::command())
+        .subcommand(decrypt::command())
+        .subcommand(list_budgets::command())
+        .subcommand(use_budget::command())
+        .subcommand(new::command())
+        .subcommand(config::command())
         .subcommand(transaction::list::command().setting(AppSettings::Hidden))
 }
 
@@ -73,6 +206,38 @@ pub trait AppTools {
     type Err;
     /// returns a converted value. the type must implement 'FromStr'. the optional format string is added to the user message to explain the correct format for this type.
     fn typed_value_of<T: FromStr>(&self, name: &str, format: Option<&str>) -> Option<T>;
+
+    /// returns an amount of money in the given currency, parsed using the configured locale.
+    fn amount_of(&self, name: &str, currency: CurrencyCode, config: &Config) -> Option<d128>;
+
+    /// returns a date, which may be relative to today, such as 'yesterday' or '-3d'.
+    fn date_of(&self, name: &str, config: &Config) -> Option<NaiveDate>;
+
+    /// returns a month, which may be relative to this month, such as 'last month' or 'mar'.
+    fn month_of(&self, name: &str, config: &Config) -> Option<CalendarMonth>;
+
+    /// returns the path of an existing category, which may be shortened or misspelt.
+    fn category_of(&self, name: &str, budget: &Budget) -> Option<String>;
+
+    /// returns the path of a category, creating a new category if the command was given
+    /// '--create' and the name matches none.
+    fn new_category_of(&self, name: &str, budget: &mut Budget) -> Option<String>;
+
+    /// returns the name of an existing account, which may be shortened or misspelt.
+    fn account_of(&self, name: &str, budget: &Budget) -> Option<String>;
+
+    /// returns the name of an existing account, or else the configured default account.
+    fn account_or_default(&self, name: &str, budget: &Budget, config: &Config) -> Option<String>;
+
+    /// returns the name of a payee with transactions, which may be shortened or misspelt.
+    fn payee_of(&self, name: &str, budget: &Budget) -> Option<String>;
+
+    /// returns the name of a payee, as with 'payee_of', or the name as given if it
+    /// matches none, for a new payee.
+    fn new_payee_of(&self, name: &str, budget: &Budget) -> Option<String>;
+
+    /// returns the name of an existing group of categories, which may be shortened or misspelt.
+    fn group_of(&self, name: &str, budget: &Budget) -> Option<String>;
 }
 
 impl<'a> AppTools for ArgMatches<'a> {
@@ -86,8 +251,122 @@ impl<'a> AppTools for ArgMatches<'a> {
                 if let Some(f) = format {
                     x.message += &format!(" [{}]", f);
                 }
-                x.exit()
+                crate::shell::exit_with(x)
             }
         }
     }
+
+    fn amount_of(&self, name: &str, currency: CurrencyCode, config: &Config) -> Option<d128> {
+        let value = self.value_of(name)?;
+        match Money::parse(value, currency, &config.locale()) {
+            Ok(m) => Some(m.amount()),
+            Err(e) => invalid_value(name, e),
+        }
+    }
+
+    fn date_of(&self, name: &str, config: &Config) -> Option<NaiveDate> {
+        let value = self.value_of(name)?;
+        let today = chrono::Local::now().date_naive();
+        match dates::parse_date(value, today, &config.calendar()) {
+            Ok(date) => Some(date),
+            Err(e) => invalid_value(name, e),
+        }
+    }
+
+    fn month_of(&self, name: &str, config: &Config) -> Option<CalendarMonth> {
+        let value = self.value_of(name)?;
+        let today = chrono::Local::now().date_naive();
+        match dates::parse_month(value, today, &config.calendar()) {
+            Ok(month) => Some(month),
+            Err(e) => invalid_value(name, e),
+        }
+    }
+
+    fn category_of(&self, name: &str, budget: &Budget) -> Option<String> {
+        let value = self.value_of(name)?;
+        Some(
+            names::category(budget, value)
+                .unwrap_or_else(|e| unknown_name(e, "use 'budget category add' to create it")),
+        )
+    }
+
+    fn new_category_of(&self, name: &str, budget: &mut Budget) -> Option<String> {
+        let value = self.value_of(name)?;
+        let create = self.is_present("create");
+        Some(new_category(budget, value, create))
+    }
+
+    fn account_of(&self, name: &str, budget: &Budget) -> Option<String> {
+        let value = self.value_of(name)?;
+        Some(
+            names::account(budget, value)
+                .unwrap_or_else(|e| unknown_name(e, "use 'budget account add' to create it")),
+        )
+    }
+
+    fn account_or_default(&self, name: &str, budget: &Budget, config: &Config) -> Option<String> {
+        let value = self.value_of(name).or_else(|| config.default_account())?;
+        Some(
+            names::account(budget, value)
+                .unwrap_or_else(|e| unknown_name(e, "use 'budget account add' to create it")),
+        )
+    }
+
+    fn payee_of(&self, name: &str, budget: &Budget) -> Option<String> {
+        let value = self.value_of(name)?;
+        Some(names::payee(budget, value).unwrap_or_else(|e| unknown_name(e, "")))
+    }
+
+    fn new_payee_of(&self, name: &str, budget: &Budget) -> Option<String> {
+        let value = self.value_of(name)?;
+        match names::payee(budget, value) {
+            Ok(payee) => Some(payee),
+            Err(NameError::Unknown { .. }) => Some(value.to_string()),
+            Err(e) => unknown_name(e, ""),
+        }
+    }
+
+    fn group_of(&self, name: &str, budget: &Budget) -> Option<String> {
+        let value = self.value_of(name)?;
+        Some(
+            names::group(budget, value).unwrap_or_else(|e| {
+                unknown_name(e, "use 'budget category group add' to create it")
+            }),
+        )
+    }
+}
+
+/// Returns the path of the category with a name, creating it if `create` is true
+/// and the name matches no category. Otherwise exits with the error for the name.
+fn new_category(budget: &mut Budget, name: &str, create: bool) -> String {
+    let path = names::new_category(budget, name, create)
+        .unwrap_or_else(|e| unknown_name(e, "use --create to create it"));
+    if budget.category(&path).is_none() {
+        if let Err(existing) = budget.add_category(path.as_str()) {
+            unreachable!("the category '{}' already exists", existing)
+        }
+    }
+    path
+}
+
+/// Exit with the error for a name which doesn't match exactly one thing. The hint
+/// is added to the error when nothing matches.
+fn unknown_name(e: NameError, hint: &str) -> ! {
+    match e {
+        NameError::Unknown { .. } if hint.is_empty() => eprintln!("{}", e),
+        // the suggestions end the message with a question mark
+        NameError::Unknown {
+            ref suggestions, ..
+        } if !suggestions.is_empty() => eprintln!("{} {}", e, hint),
+        NameError::Unknown { .. } => eprintln!("{}. {}", e, hint),
+        _ => eprintln!("{}", e),
+    }
+    crate::shell::exit(1)
+}
+
+fn invalid_value<E: std::fmt::Display>(name: &str, e: E) -> ! {
+    crate::shell::exit_with(clap::Error::with_description(
+        &format!("Invalid value for '<{}>': {}", name, e),
+        clap::ErrorKind::InvalidValue,
+    ))
 }
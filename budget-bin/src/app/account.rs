@@ -0,0 +1,32 @@
+use crate::config::Config;
+use budget_lib::Budget;
+use clap::{App, AppSettings, ArgMatches, SubCommand};
+
+mod add;
+mod list;
+mod transfer;
+mod value;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("account")
+        .about("Manages the accounts that transactions are drawn from.")
+        // app settings
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        // subcommands
+        .subcommand(add::command())
+        .subcommand(list::command())
+        .subcommand(transfer::command())
+        .subcommand(value::command())
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("add", Some(submatches)) => add::run(budget, submatches),
+        ("list", Some(submatches)) => list::run(budget, config, submatches),
+        ("transfer", Some(submatches)) => transfer::run(budget, config, submatches),
+        ("value", Some(submatches)) => value::run(budget, config, submatches),
+        // If no subcommand is found, assume 'list'
+        (_, None) => list::run(budget, config, matches),
+        _ => panic!("something is missing!"),
+    }
+}
@@ -0,0 +1,44 @@
+use crate::app::AppTools;
+use budget_lib::{Account, Budget, CurrencyCode};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("add")
+        // app settings
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .takes_value(true)
+                .help("The name of the account")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("currency")
+                .long("currency")
+                .short("c")
+                .takes_value(true)
+                .help("The ISO 4217 code of the account currency [default: the budget currency]"),
+        )
+        .arg(
+            Arg::with_name("off budget")
+                .long("off-budget")
+                .help("Track the balance of the account, such as a mortgage or investments, without budgeting for it"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let mut account = Account::new(matches.value_of("name").unwrap());
+    if let Some(currency) =
+        matches.typed_value_of::<CurrencyCode>("currency", Some("such as 'EUR'"))
+    {
+        account = account.with_currency(currency);
+    }
+    if matches.is_present("off budget") {
+        account = account.off_budget();
+    }
+
+    match budget.add_account(account) {
+        Ok(a) => log::info!("Account added: {}", a.name()),
+        Err(a) => log::warn!("Account already exists: {}", a.name()),
+    }
+}
@@ -0,0 +1,30 @@
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::Budget;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("list")
+    // app settings
+
+    // subcommands
+}
+
+pub fn run(budget: &mut Budget, config: &Config, _matches: &ArgMatches) {
+    let mut table =
+        Table::new(vec!["account", "currency", "budget", "balance"]).align(3, Align::Right);
+    let today = chrono::Local::now().date_naive();
+
+    for account in budget.accounts() {
+        let balance = budget.balance(Some(account.name()), today);
+        let currency = budget.account_currency(Some(account.name()));
+        table.push(vec![
+            account.name().to_string(),
+            currency.to_string(),
+            if account.is_off_budget() { "off" } else { "on" }.to_string(),
+            config.format_amount(balance, currency),
+        ]);
+    }
+
+    print!("{}", table);
+}
@@ -0,0 +1,59 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::dates;
+use crate::shell;
+use budget_lib::Budget;
+use chrono::Utc;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("transfer")
+        .about("Moves money from one account to another.")
+        // arguments
+        .arg(
+            Arg::with_name("amount")
+                .takes_value(true)
+                .help("The amount to transfer, in the currency of the 'from' account")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("from")
+                .takes_value(true)
+                .help("The account to transfer money out of")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("to")
+                .takes_value(true)
+                .help("The account to transfer money into")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("date")
+                .long("date")
+                .short("d")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help("The date of the transfer [default: today]"),
+        )
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let from = matches.account_of("from", budget).unwrap();
+    let to = matches.account_of("to", budget).unwrap();
+    let (from, to) = (from.as_str(), to.as_str());
+
+    let amount = matches
+        .amount_of("amount", budget.account_currency(Some(from)), config)
+        .unwrap();
+    let date = match matches.date_of("date", config) {
+        Some(d) => dates::midday(d),
+        None => Utc::now(),
+    };
+
+    if let Err(e) = budget.transfer_between_accounts(amount, from, to, date) {
+        eprintln!("unable to transfer from '{}' to '{}': {}", from, to, e);
+        shell::exit(1)
+    }
+    log::info!("transferred {} from {} to {}", amount, from, to);
+}
@@ -0,0 +1,48 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use budget_lib::Budget;
+use chrono::NaiveDate;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("value")
+        .about("Records the value of an account, such as a house or an investment fund, on a date.")
+        // app settings
+        .setting(clap::AppSettings::AllowNegativeNumbers)
+        // arguments
+        .arg(
+            Arg::with_name("account")
+                .takes_value(true)
+                .help("The name of the account")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("value")
+                .takes_value(true)
+                .help("The value of the account, in the currency of the account")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("date")
+                .long("date")
+                .short("d")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help("The date of the valuation [default: today]"),
+        )
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let account = matches.account_of("account", budget).unwrap();
+    let account = account.as_str();
+
+    let value = matches
+        .amount_of("value", budget.account_currency(Some(account)), config)
+        .unwrap();
+    let date: NaiveDate = matches
+        .date_of("date", config)
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+
+    budget.add_valuation(account, date, value);
+    log::info!("valued {} at {} on {}", account, value, date);
+}
@@ -0,0 +1,79 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::shell;
+use budget_lib::{Budget, CalendarMonth};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("assign")
+        .about("Assigns available money to a category, or to the goals which need it.")
+        // app settings
+        .setting(AppSettings::AllowNegativeNumbers)
+        // arguments
+        .arg(
+            Arg::with_name("amount")
+                .takes_value(true)
+                .required_unless("goals")
+                .help("The amount to assign. A negative amount returns money to be assigned again"),
+        )
+        .arg(
+            Arg::with_name("category")
+                .takes_value(true)
+                .required_unless("goals")
+                .help("The category to assign the money to"),
+        )
+        .arg(
+            Arg::with_name("goals")
+                .long("goals")
+                .conflicts_with_all(&["amount", "category"])
+                .help("Fund the goals which need money this month, in order of priority"),
+        )
+        .arg(
+            Arg::with_name("month")
+                .long("month")
+                .short("m")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help("The month to assign the money in, such as 2026-03 [default: this month]"),
+        )
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let month: CalendarMonth = matches
+        .month_of("month", config)
+        .unwrap_or_else(|| budget.month_of(chrono::Local::now()));
+    let currency = budget.currency();
+
+    if matches.is_present("goals") {
+        let funded = budget.fund_goals(month);
+        if funded.is_empty() {
+            println!("no goals need funding in {}", month);
+        }
+        for (category, amount) in funded {
+            println!(
+                "assigned {} to {}",
+                config.format_amount(amount, currency),
+                category
+            );
+        }
+    } else {
+        let category = matches.category_of("category", budget).unwrap();
+        let amount = matches.amount_of("amount", currency, config).unwrap();
+        if let Err(e) = budget.assign(category.as_str(), amount, month) {
+            eprintln!("{}", e);
+            shell::exit(1)
+        }
+    }
+
+    let available = budget.available(month);
+    if available.is_negative() {
+        log::warn!(
+            "{} more has been assigned than is available",
+            config.format_amount(-available, currency)
+        );
+    }
+    println!(
+        "available to assign: {}",
+        config.format_amount(available, currency)
+    );
+}
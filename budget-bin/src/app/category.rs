@@ -1,8 +1,16 @@
+use crate::config::Config;
 use budget_lib::Budget;
-use clap::{App, AppSettings, ArgMatches, SubCommand};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 
 mod add;
+pub mod goal;
+mod group;
+mod hide;
+mod income;
 mod list;
+mod merge;
+mod move_category;
+mod remove;
 mod rename;
 
 pub fn command<'a, 'b>() -> App<'a, 'b> {
@@ -13,13 +21,36 @@ pub fn command<'a, 'b>() -> App<'a, 'b> {
         .subcommand(add::command())
         .subcommand(list::command())
         .subcommand(rename::command())
+        .subcommand(remove::command())
+        .subcommand(merge::command())
+        .subcommand(move_category::command())
+        .subcommand(hide::command())
+        .subcommand(hide::unhide_command())
+        .subcommand(income::command())
+        .subcommand(goal::command())
+        .subcommand(group::command())
 }
 
-pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+/// The argument which allows a command to create the category it is given.
+pub fn create_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("create")
+        .long("create")
+        .help("Create the category if no category matches the name")
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
     match matches.subcommand() {
         ("add", Some(submatches)) => add::run(budget, submatches),
         ("list", Some(submatches)) => list::run(budget, submatches),
         ("rename", Some(submatches)) => rename::run(budget, submatches),
+        ("remove", Some(submatches)) => remove::run(budget, submatches),
+        ("merge", Some(submatches)) => merge::run(budget, submatches),
+        ("move", Some(submatches)) => move_category::run(budget, submatches),
+        ("hide", Some(submatches)) => hide::run(budget, submatches, true),
+        ("unhide", Some(submatches)) => hide::run(budget, submatches, false),
+        ("income", Some(submatches)) => income::run(budget, submatches),
+        ("goal", Some(submatches)) => goal::run(budget, config, submatches),
+        ("group", Some(submatches)) => group::run(budget, submatches),
         // If no subcommand is found, assume 'list'
         (_, None) => list::run(budget, matches),
         _ => panic!("something is missing!"),
@@ -1,3 +1,4 @@
+use crate::shell;
 use budget_lib::Budget;
 use clap::{App, Arg, ArgMatches, SubCommand};
 
@@ -8,15 +9,39 @@ pub fn command<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("name")
                 .takes_value(true)
-                .help("The name of the category")
+                .help("The name of the category, or its path such as 'transport:car:fuel'")
                 .required(true),
         )
+        .arg(
+            Arg::with_name("group")
+                .long("group")
+                .short("g")
+                .takes_value(true)
+                .help("The group to add the category to, if it is a top-level category"),
+        )
 }
 
 pub fn run(budget: &mut Budget, matches: &ArgMatches) {
     let category_name = matches.value_of("name").unwrap();
+    let group = matches.value_of("group");
+    if let Some(group) = group {
+        if budget.master_categories().all(|m| m.name() != group) {
+            eprintln!(
+                "no group named '{}'. use 'budget category group add' to create it",
+                group
+            );
+            shell::exit(1)
+        }
+    }
+
     match budget.add_category(category_name) {
         Ok(n) => log::info!("Category added: {}", n),
-        Err(n) => log::warn!("Category already exists: {}", n),
+        Err(n) => {
+            log::warn!("Category already exists: {}", n);
+            return;
+        }
+    }
+    if group.is_some() {
+        budget.move_category(category_name, group);
     }
 }
@@ -0,0 +1,107 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::shell;
+use budget_lib::{Budget, CalendarMonth, Goal, GoalKind};
+use clap::{App, Arg, ArgGroup, ArgMatches, SubCommand};
+use decimal::d128;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("goal")
+        .about("Sets or clears the goal of a category, or shows it.")
+        // arguments
+        .arg(
+            Arg::with_name("category")
+                .required(true)
+                .takes_value(true)
+                .help("The category"),
+        )
+        .arg(
+            Arg::with_name("target")
+                .long("target")
+                .takes_value(true)
+                .value_name("amount")
+                .requires("by")
+                .help("Save up a balance of this amount"),
+        )
+        .arg(
+            Arg::with_name("by")
+                .long("by")
+                .takes_value(true)
+                .value_name("month")
+                .allow_hyphen_values(true)
+                .requires("target")
+                .help("The month to save the target balance by, such as 2026-12 or dec"),
+        )
+        .arg(
+            Arg::with_name("monthly")
+                .long("monthly")
+                .takes_value(true)
+                .value_name("amount")
+                .help("Assign this amount every month"),
+        )
+        .arg(
+            Arg::with_name("cap")
+                .long("cap")
+                .takes_value(true)
+                .value_name("amount")
+                .help("Spend no more than this amount each month"),
+        )
+        .arg(
+            Arg::with_name("clear")
+                .long("clear")
+                .help("Remove the goal of the category"),
+        )
+        .group(ArgGroup::with_name("goal").args(&["target", "monthly", "cap", "clear"]))
+        .arg(
+            Arg::with_name("priority")
+                .long("priority")
+                .takes_value(true)
+                .default_value("0")
+                .help("Goals with lower numbers are funded first by 'budget assign --goals'"),
+        )
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let category = matches.category_of("category", budget).unwrap();
+    let category = category.as_str();
+
+    if matches.is_present("clear") {
+        if budget.remove_goal(category).is_none() {
+            println!("'{}' has no goal", category);
+        }
+        return;
+    }
+
+    let currency = budget.currency();
+    let amount = |name| matches.amount_of(name, currency, config);
+    let goal = if let Some(amount) = amount("target") {
+        let by: CalendarMonth = matches.month_of("by", config).unwrap();
+        Goal::target_balance(amount, by)
+    } else if let Some(amount) = amount("monthly") {
+        Goal::monthly_funding(amount)
+    } else if let Some(amount) = amount("cap") {
+        Goal::spending_cap(amount)
+    } else {
+        match budget.goal(category) {
+            Some(goal) => println!("{}", describe(goal, budget, config)),
+            None => println!("'{}' has no goal", category),
+        }
+        return;
+    };
+
+    let priority = matches.typed_value_of("priority", None).unwrap();
+    if let Err(e) = budget.set_goal(category, goal.with_priority(priority)) {
+        eprintln!("{}", e);
+        shell::exit(1)
+    }
+}
+
+/// Describe a goal, such as "save 1,200.00 by 2026-12".
+pub fn describe(goal: &Goal<d128>, budget: &Budget, config: &Config) -> String {
+    let amount = config.format_amount(goal.amount(), budget.currency());
+    match goal.kind() {
+        GoalKind::TargetBalance { by, .. } => format!("save {} by {}", amount, by),
+        GoalKind::MonthlyFunding { .. } => format!("{} a month", amount),
+        GoalKind::SpendingCap { .. } => format!("spend up to {}", amount),
+    }
+}
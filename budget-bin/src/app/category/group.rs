@@ -0,0 +1,37 @@
+use budget_lib::Budget;
+use clap::{App, AppSettings, ArgMatches, SubCommand};
+
+mod add;
+mod list;
+mod move_category;
+mod remove;
+mod rename;
+mod sort;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("group")
+        .about("Manages the master categories which categories are grouped under.")
+        // app settings
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        // subcommands
+        .subcommand(add::command())
+        .subcommand(list::command())
+        .subcommand(rename::command())
+        .subcommand(remove::command())
+        .subcommand(move_category::command())
+        .subcommand(sort::command())
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("add", Some(submatches)) => add::run(budget, submatches),
+        ("list", Some(submatches)) => list::run(budget, submatches),
+        ("rename", Some(submatches)) => rename::run(budget, submatches),
+        ("remove", Some(submatches)) => remove::run(budget, submatches),
+        ("move", Some(submatches)) => move_category::run(budget, submatches),
+        ("sort", Some(submatches)) => sort::run(budget, submatches),
+        // If no subcommand is found, assume 'list'
+        (_, None) => list::run(budget, matches),
+        _ => panic!("something is missing!"),
+    }
+}
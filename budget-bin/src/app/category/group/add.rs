@@ -0,0 +1,21 @@
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("add")
+        .about("Adds a group, after the existing groups.")
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .takes_value(true)
+                .help("The name of the group")
+                .required(true),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    match budget.add_master_category(matches.value_of("name").unwrap()) {
+        Ok(n) => log::info!("Group added: {}", n),
+        Err(n) => log::warn!("Group already exists: {}", n),
+    }
+}
@@ -0,0 +1,36 @@
+use budget_lib::Budget;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("list").about("Lists the groups in order, with their categories.")
+    // app settings
+
+    // subcommands
+}
+
+pub fn run(budget: &mut Budget, _matches: &ArgMatches) {
+    for (i, (master, categories)) in budget.grouped_categories().into_iter().enumerate() {
+        match master {
+            Some(m) => println!("{}. {}", i, m.name()),
+            None => println!("(no group)"),
+        }
+        // the position of each category among the others with the same parent
+        let mut positions: Vec<usize> = Vec::new();
+        for category in categories {
+            let depth = budget.category_depth(category);
+            positions.truncate(depth + 1);
+            match positions.get_mut(depth) {
+                Some(p) => *p += 1,
+                None => positions.push(0),
+            }
+            let hidden = if category.hidden() { " (hidden)" } else { "" };
+            println!(
+                "{}{}. {}{}",
+                "   ".repeat(depth + 1),
+                positions[depth],
+                category.name(),
+                hidden
+            );
+        }
+    }
+}
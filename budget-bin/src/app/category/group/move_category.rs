@@ -0,0 +1,61 @@
+use crate::app::AppTools;
+use crate::shell;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("move")
+        .about("Moves a category into a group, or to a position within its group.")
+        // arguments
+        .arg(
+            Arg::with_name("category")
+                .takes_value(true)
+                .help("The category to move")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("group")
+                .takes_value(true)
+                .required_unless_one(&["ungroup", "position"])
+                .help("The group to move it into"),
+        )
+        .arg(
+            Arg::with_name("ungroup")
+                .long("ungroup")
+                .conflicts_with("group")
+                .help("Take the category out of its group"),
+        )
+        .arg(
+            Arg::with_name("position")
+                .long("position")
+                .takes_value(true)
+                .help("The position in the group, counting from 0 [default: the end]"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let category = matches.category_of("category", budget).unwrap();
+    let category = category.as_str();
+    let moving_group = matches.is_present("ungroup") || matches.is_present("group");
+    if moving_group
+        && budget
+            .category(category)
+            .is_some_and(|c| budget.category_depth(c) > 0)
+    {
+        eprintln!(
+            "'{}' is a subcategory, so it belongs to the group of its parent. use 'budget category move' to move it",
+            category
+        );
+        shell::exit(1)
+    }
+
+    if matches.is_present("ungroup") {
+        budget.move_category(category, None);
+    } else if let Some(group) = matches.group_of("group", budget) {
+        budget.move_category(category, Some(&group));
+    }
+
+    if let Some(position) = matches.typed_value_of("position", Some("a number")) {
+        budget.set_category_position(category, position);
+    }
+}
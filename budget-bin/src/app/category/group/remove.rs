@@ -0,0 +1,21 @@
+use crate::app::AppTools;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("remove")
+        .about("Removes a group. Its categories are kept, without a group.")
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .takes_value(true)
+                .help("The name of the group")
+                .required(true),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let name = matches.group_of("name", budget).unwrap();
+    budget.remove_master_category(&name);
+    log::info!("Group removed: {}", name);
+}
@@ -0,0 +1,21 @@
+use crate::app::AppTools;
+use crate::shell;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("rename")
+        .about("Renames a group.")
+        .arg(Arg::with_name("old name").required(true).takes_value(true))
+        .arg(Arg::with_name("new name").required(true).takes_value(true))
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let old = matches.group_of("old name", budget).unwrap();
+    let old = old.as_str();
+    let new = matches.value_of("new name").unwrap();
+    if let Err(existing) = budget.rename_master_category(old, new) {
+        eprintln!("there is already a group named '{}'", existing);
+        shell::exit(1)
+    }
+}
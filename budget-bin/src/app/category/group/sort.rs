@@ -0,0 +1,30 @@
+use crate::app::AppTools;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("sort")
+        .about("Moves a group to a position among the groups.")
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .takes_value(true)
+                .help("The name of the group")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("position")
+                .takes_value(true)
+                .help("The position, counting from 0")
+                .required(true),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let name = matches.group_of("name", budget).unwrap();
+    let name = name.as_str();
+    let position = matches
+        .typed_value_of("position", Some("a number"))
+        .unwrap();
+    budget.set_master_category_position(name, position);
+}
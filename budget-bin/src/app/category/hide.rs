@@ -0,0 +1,39 @@
+use crate::app::AppTools;
+use crate::names;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("hide")
+        .about("Hides a category from the summary, keeping its history.")
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .required(true)
+                .takes_value(true)
+                .help("The category to hide"),
+        )
+}
+
+pub fn unhide_command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("unhide")
+        .about("Shows a hidden category in the summary again.")
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .required(true)
+                .takes_value(true)
+                .help("The category to show"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches, hidden: bool) {
+    let name = if hidden {
+        matches.category_of("name", budget).unwrap()
+    } else {
+        // the category to show is hidden, so it has to be found among the hidden ones
+        let name = matches.value_of("name").unwrap();
+        names::any_category(budget, name).unwrap_or_else(|e| crate::app::unknown_name(e, ""))
+    };
+    budget.set_category_hidden(&name, hidden);
+}
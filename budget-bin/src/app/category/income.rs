@@ -0,0 +1,25 @@
+use crate::app::AppTools;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("income")
+        .about("Marks a category as income, so that the money in it is available to assign.")
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .required(true)
+                .takes_value(true)
+                .help("The category to mark"),
+        )
+        .arg(
+            Arg::with_name("off")
+                .long("off")
+                .help("Stop treating the category as income"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let name = matches.category_of("name", budget).unwrap();
+    budget.set_category_income(&name, !matches.is_present("off"));
+}
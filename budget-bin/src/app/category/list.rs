@@ -1,15 +1,29 @@
 use budget_lib::Budget;
-use clap::{App, ArgMatches, SubCommand};
+use clap::{App, Arg, ArgMatches, SubCommand};
 
 pub fn command<'a, 'b>() -> App<'a, 'b> {
     SubCommand::with_name("list")
-    // app settings
-
-    // subcommands
+        // app settings
+        // arguments
+        .arg(
+            Arg::with_name("all")
+                .long("all")
+                .short("a")
+                .help("Include hidden categories"),
+        )
 }
 
-pub fn run(budget: &mut Budget, _matches: &ArgMatches) {
-    for category in budget.categories() {
-        println!("{}", category.name());
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let all = matches.is_present("all");
+    for (_, categories) in budget.grouped_categories() {
+        for category in categories {
+            let path = budget.category_path(category);
+            let income = if category.income() { " (income)" } else { "" };
+            if !category.hidden() {
+                println!("{}{}", path, income);
+            } else if all {
+                println!("{}{} (hidden)", path, income);
+            }
+        }
     }
 }
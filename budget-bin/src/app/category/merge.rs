@@ -0,0 +1,42 @@
+use crate::app::AppTools;
+use crate::shell;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("merge")
+        .about("Merges one category into another.")
+        .long_about(
+            "Merges one category into another. The transactions, allocations and goal of the \
+             first category are moved to the second, and the first is removed.",
+        )
+        // arguments
+        .arg(
+            Arg::with_name("from")
+                .required(true)
+                .takes_value(true)
+                .help("The category to merge, which is removed"),
+        )
+        .arg(
+            Arg::with_name("into")
+                .required(true)
+                .takes_value(true)
+                .help("The category to merge it into"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let from = matches.category_of("from", budget).unwrap();
+    let into = matches.category_of("into", budget).unwrap();
+    if from == into {
+        eprintln!("can't merge a category into itself");
+        shell::exit(1);
+    }
+    match budget.merge_category(&from, &into) {
+        Ok(count) => println!("merged {} transactions into '{}'", count, into),
+        Err(e) => {
+            eprintln!("can't merge '{}' into '{}': {}", from, into, e);
+            shell::exit(1);
+        }
+    }
+}
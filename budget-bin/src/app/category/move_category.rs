@@ -0,0 +1,54 @@
+use crate::app::AppTools;
+use crate::shell;
+use budget_lib::{Budget, MoveCategoryError};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("move")
+        .about("Moves a category, with its subcategories, under another category.")
+        // arguments
+        .arg(
+            Arg::with_name("category")
+                .required(true)
+                .takes_value(true)
+                .help("The category to move"),
+        )
+        .arg(
+            Arg::with_name("parent")
+                .takes_value(true)
+                .required_unless("top")
+                .help("The category to move it under"),
+        )
+        .arg(
+            Arg::with_name("top")
+                .long("top")
+                .conflicts_with("parent")
+                .help("Makes it a top-level category"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let category = matches.category_of("category", budget).unwrap();
+    let category = category.as_str();
+    let parent = matches.category_of("parent", budget);
+    let parent = parent.as_deref();
+
+    match budget.set_category_parent(category, parent) {
+        Ok(()) => match parent {
+            Some(parent) => println!("moved '{}' under '{}'", category, parent),
+            None => println!("moved '{}' to the top level", category),
+        },
+        Err(MoveCategoryError::NoCategory) => {
+            eprintln!("no category named '{}'", category);
+            shell::exit(1);
+        }
+        Err(MoveCategoryError::NoParent) => {
+            eprintln!("no category named '{}'", parent.unwrap_or_default());
+            shell::exit(1);
+        }
+        Err(e) => {
+            eprintln!("can't move '{}': {}", category, e);
+            shell::exit(1);
+        }
+    }
+}
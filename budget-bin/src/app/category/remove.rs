@@ -0,0 +1,62 @@
+use crate::app::AppTools;
+use crate::shell;
+use budget_lib::{Budget, RemoveCategoryError};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("remove")
+        .about("Removes a category.")
+        .long_about(
+            "Removes a category. A category with transactions or allocations can only be \
+             removed by moving them to another category with --reassign. To keep a category's \
+             history but leave it out of the summary, use 'budget category hide' instead.",
+        )
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .required(true)
+                .takes_value(true)
+                .help("The category to remove"),
+        )
+        .arg(
+            Arg::with_name("reassign")
+                .long("reassign")
+                .short("r")
+                .takes_value(true)
+                .value_name("category")
+                .help("The category to move the transactions and allocations to"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let name = matches.category_of("name", budget).unwrap();
+    let name = name.as_str();
+    let reassign = matches.category_of("reassign", budget);
+    let reassign = reassign.as_deref();
+
+    match budget.remove_category(name, reassign) {
+        Ok(_) => match reassign {
+            Some(into) => println!(
+                "removed '{}' and moved its transactions to '{}'",
+                name, into
+            ),
+            None => println!("removed '{}'", name),
+        },
+        Err(RemoveCategoryError::NoCategory) => {
+            eprintln!("no category named '{}'", name);
+            shell::exit(1);
+        }
+        Err(e @ RemoveCategoryError::InUse { .. })
+        | Err(e @ RemoveCategoryError::HasSubcategories(_)) => {
+            eprintln!(
+                "can't remove '{}': {}. use --reassign to move them to another category",
+                name, e
+            );
+            shell::exit(1);
+        }
+        Err(e) => {
+            eprintln!("can't remove '{}': {}", name, e);
+            shell::exit(1);
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use budget_lib::Budget;
+use crate::app::AppTools;
+use crate::shell;
+use budget_lib::{Budget, RenameCategoryError};
 use clap::{App, Arg, ArgMatches, SubCommand};
 
 pub fn command<'a, 'b>() -> App<'a, 'b> {
@@ -8,8 +10,28 @@ pub fn command<'a, 'b>() -> App<'a, 'b> {
 }
 
 pub fn run(budget: &mut Budget, matches: &ArgMatches) {
-    budget.rename_category(
-        matches.value_of("old name").unwrap(),
-        matches.value_of("new name").unwrap(),
-    )
+    let old = matches.category_of("old name", budget).unwrap();
+    let new = matches.value_of("new name").unwrap();
+    match budget.rename_category(old.as_str(), new) {
+        Ok(()) => (),
+        Err(RenameCategoryError::NameTaken) => {
+            eprintln!(
+                "there is already a category named '{}'. use 'budget category merge' to \
+                 combine them",
+                new
+            );
+            shell::exit(1);
+        }
+        Err(e @ RenameCategoryError::InvalidName) => {
+            eprintln!(
+                "can't rename '{}': {}. use 'budget category move' to move it",
+                old, e
+            );
+            shell::exit(1);
+        }
+        Err(e) => {
+            eprintln!("can't rename '{}': {}", old, e);
+            shell::exit(1);
+        }
+    }
 }
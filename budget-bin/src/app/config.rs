@@ -0,0 +1,28 @@
+use crate::config::Config;
+use clap::{App, AppSettings, ArgMatches, SubCommand};
+
+mod get;
+mod list;
+mod set;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("config")
+        .about("Gets and sets configuration settings.")
+        // app settings
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        // subcommands
+        .subcommand(get::command())
+        .subcommand(set::command())
+        .subcommand(list::command())
+}
+
+pub fn run(config: &mut Config, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("get", Some(submatches)) => get::run(config, submatches),
+        ("set", Some(submatches)) => set::run(config, submatches),
+        ("list", Some(submatches)) => list::run(config, submatches),
+        // If no subcommand is found, assume 'list'
+        (_, None) => list::run(config, matches),
+        _ => panic!("something is missing!"),
+    }
+}
@@ -0,0 +1,26 @@
+use crate::config::Config;
+use crate::shell;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("get")
+        .about("Prints the value of a setting.")
+        // arguments
+        .arg(
+            Arg::with_name("key")
+                .takes_value(true)
+                .help("The setting, such as 'display.currency'")
+                .required(true),
+        )
+}
+
+pub fn run(config: &mut Config, matches: &ArgMatches) {
+    match config.get(matches.value_of("key").unwrap()) {
+        Ok(Some(value)) => println!("{}", value),
+        Ok(None) => (),
+        Err(e) => {
+            eprintln!("{}", e);
+            shell::exit(1)
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use crate::config::{Config, KEYS};
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("list").about("Lists all the settings, and the configuration file.")
+}
+
+pub fn run(config: &mut Config, _matches: &ArgMatches) {
+    println!("# {}", config.file().display());
+    for key in KEYS {
+        match config.get(key) {
+            Ok(Some(value)) => println!("{} = {}", key, value),
+            _ => println!("{} =", key),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::config::Config;
+use crate::shell;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("set")
+        .about("Sets the value of a setting. An empty value clears the setting.")
+        // arguments
+        .arg(
+            Arg::with_name("key")
+                .takes_value(true)
+                .help("The setting, such as 'display.currency'")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("value")
+                .takes_value(true)
+                .help("The new value. Lists are separated by commas")
+                .required(true),
+        )
+}
+
+pub fn run(config: &mut Config, matches: &ArgMatches) {
+    let key = matches.value_of("key").unwrap();
+    let value = matches.value_of("value").unwrap();
+
+    match config.set(key, value).and_then(|_| config.save()) {
+        Ok(_) => log::info!("set {} = {}", key, value),
+        Err(e) => {
+            eprintln!("{}", e);
+            shell::exit(1)
+        }
+    }
+}
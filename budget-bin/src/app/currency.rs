@@ -0,0 +1,24 @@
+use crate::app::AppTools;
+use budget_lib::{Budget, CurrencyCode};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("currency")
+        .about("Shows or sets the currency of the budget.")
+        // arguments
+        .arg(
+            Arg::with_name("code")
+                .takes_value(true)
+                .help("The ISO 4217 code of the new currency, such as 'GBP'"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    match matches.typed_value_of::<CurrencyCode>("code", Some("such as 'GBP'")) {
+        Some(code) => {
+            budget.set_currency(code);
+            log::info!("budget currency set to {}", code);
+        }
+        None => println!("{}", budget.currency()),
+    }
+}
@@ -0,0 +1,15 @@
+use crate::sd::BudgetFile;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("decrypt").about("Stores the budget unencrypted.")
+}
+
+pub fn run(file: &mut BudgetFile, _matches: &ArgMatches) {
+    if file.is_encrypted() {
+        file.decrypt();
+        log::info!("budget will be decrypted: {:?}", file.path());
+    } else {
+        println!("budget is not encrypted");
+    }
+}
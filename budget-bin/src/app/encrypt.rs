@@ -0,0 +1,19 @@
+use crate::sd::BudgetFile;
+use crate::shell;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("encrypt").about(
+        "Encrypts the budget with a passphrase, or changes the passphrase of an encrypted budget.",
+    )
+}
+
+pub fn run(file: &mut BudgetFile, _matches: &ArgMatches) {
+    match file.encrypt() {
+        Ok(_) => log::info!("budget will be encrypted: {:?}", file.path()),
+        Err(e) => {
+            eprintln!("unable to encrypt budget: {}", e);
+            shell::exit(1)
+        }
+    }
+}
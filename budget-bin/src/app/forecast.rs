@@ -0,0 +1,96 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::{Budget, Forecast};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("forecast")
+        .about("Projects the balances of accounts and categories over the coming months.")
+        .long_about(
+            "Projects the balances of accounts and categories over the coming months, from the \
+             scheduled transactions and the average of the other spending in recent months.",
+        )
+        // arguments
+        .arg(
+            Arg::with_name("months")
+                .long("months")
+                .short("m")
+                .takes_value(true)
+                .default_value("6")
+                .help("The number of months to forecast, including this one"),
+        )
+        .arg(
+            Arg::with_name("history")
+                .long("history")
+                .takes_value(true)
+                .default_value("3")
+                .help("The number of past months to average spending over"),
+        )
+}
+
+pub fn run(budget: &Budget, config: &Config, matches: &ArgMatches) {
+    let months: u32 = matches.typed_value_of("months", None).unwrap();
+    let history: u32 = matches.typed_value_of("history", None).unwrap();
+    let today = chrono::Local::now().date_naive();
+    let forecast = Forecast::new(budget, today, months, history);
+
+    let mut header = vec!["account".to_string(), "now".to_string()];
+    header.extend(forecast.months().iter().map(|m| m.to_string()));
+    let columns = header.len();
+    let align = |mut table: Table| {
+        for i in 1..columns {
+            table = table.align(i, Align::Right);
+        }
+        table
+    };
+
+    let mut table = align(Table::new(header.clone()));
+    for a in forecast.accounts() {
+        let currency = budget.account_currency(a.account.as_deref());
+        let mut record = vec![a
+            .account
+            .clone()
+            .unwrap_or_else(|| "(no account)".to_string())];
+        record.extend(
+            Some(&a.opening)
+                .into_iter()
+                .chain(&a.balances)
+                .map(|x| config.format_amount(*x, currency)),
+        );
+        table.push(record);
+    }
+    print!("{}", table);
+
+    for a in forecast.accounts() {
+        if let Some(date) = a.first_negative {
+            let name = a.account.as_deref().unwrap_or("(no account)");
+            if date == today {
+                println!("warning: '{}' is already overdrawn", name);
+            } else {
+                println!(
+                    "warning: '{}' is forecast to be overdrawn on {}",
+                    name, date
+                );
+            }
+        }
+    }
+
+    if forecast.categories().is_empty() {
+        return;
+    }
+    println!();
+    header[0] = "category".to_string();
+    let mut table = align(Table::new(header.clone()));
+    for c in forecast.categories() {
+        let mut record = vec![c.category.clone()];
+        record.extend(
+            Some(&c.opening)
+                .into_iter()
+                .chain(&c.balances)
+                .map(|x| config.format_amount(*x, budget.currency())),
+        );
+        table.push(record);
+    }
+    print!("{}", table);
+}
@@ -0,0 +1,21 @@
+use crate::budgets::BudgetDirectory;
+use crate::config::Config;
+use crate::shell;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("list-budgets").about("Lists the budgets in the budget directory.")
+}
+
+pub fn run(dir: &BudgetDirectory, config: &Config, _matches: &ArgMatches) {
+    let current = config.default_budget();
+    let names = dir.list().unwrap_or_else(|e| {
+        eprintln!("unable to list budgets: {}", e);
+        shell::exit(1)
+    });
+
+    for name in names {
+        let marker = if name == current { "*" } else { " " };
+        println!("{} {}", marker, name);
+    }
+}
@@ -0,0 +1,41 @@
+use crate::budgets::BudgetDirectory;
+use crate::config::Config;
+use crate::sd::BudgetFile;
+use crate::shell;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("new")
+        .about("Creates a new, empty budget.")
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .takes_value(true)
+                .help("The name of the budget")
+                .required(true),
+        )
+}
+
+pub fn run(dir: &BudgetDirectory, config: &Config, matches: &ArgMatches) {
+    let name = matches.value_of("name").unwrap();
+    let path = dir.new_budget(name).unwrap_or_else(|e| {
+        eprintln!("unable to create budget: {}", e);
+        shell::exit(1)
+    });
+
+    match BudgetFile::new(path.clone(), None).save(&config.new_budget()) {
+        Ok(_) => {
+            log::info!("created budget: {}", name);
+            println!(
+                "created budget '{}'. run 'budget use {}' to switch to it",
+                name, name
+            );
+        }
+        Err(e) => {
+            eprintln!("unable to create budget: {}", e);
+            // don't leave an empty budget behind
+            let _ = std::fs::remove_file(path);
+            shell::exit(1)
+        }
+    }
+}
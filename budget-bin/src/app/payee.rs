@@ -0,0 +1,35 @@
+use crate::config::Config;
+use budget_lib::Budget;
+use clap::{App, AppSettings, ArgMatches, SubCommand};
+
+mod alias;
+mod category;
+mod list;
+mod merge;
+mod rename;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("payee")
+        .about("Manages payees, the other names they appear under, and their default categories.")
+        // app settings
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        // subcommands
+        .subcommand(list::command())
+        .subcommand(rename::command())
+        .subcommand(merge::command())
+        .subcommand(alias::command())
+        .subcommand(category::command())
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("list", Some(submatches)) => list::run(budget, config, submatches),
+        ("rename", Some(submatches)) => rename::run(budget, submatches),
+        ("merge", Some(submatches)) => merge::run(budget, submatches),
+        ("alias", Some(submatches)) => alias::run(budget, submatches),
+        ("category", Some(submatches)) => category::run(budget, submatches),
+        // If no subcommand is found, assume 'list'
+        (_, None) => list::run(budget, config, matches),
+        _ => panic!("something is missing!"),
+    }
+}
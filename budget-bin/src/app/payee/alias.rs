@@ -0,0 +1,34 @@
+use crate::app::AppTools;
+use budget_lib::{Budget, Pattern};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("alias")
+        .about("Adds another name that a payee appears under.")
+        .long_about(
+            "Adds another name that a payee appears under, such as 'AMZN Mktp' for 'Amazon'. \
+             The alias is a regular expression, which ignores case. Transactions with a payee \
+             which matches it are renamed, now and when they are imported or added.",
+        )
+        .arg(
+            Arg::with_name("payee")
+                .required(true)
+                .takes_value(true)
+                .help("The canonical name of the payee"),
+        )
+        .arg(
+            Arg::with_name("alias")
+                .required(true)
+                .takes_value(true)
+                .help("A pattern which matches the other name"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let payee = matches.value_of("payee").unwrap();
+    let alias: Pattern = matches
+        .typed_value_of("alias", Some("a regular expression"))
+        .unwrap();
+    let count = budget.add_payee_alias(payee, alias);
+    println!("renamed {} transactions to '{}'", count, payee);
+}
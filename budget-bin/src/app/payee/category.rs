@@ -0,0 +1,37 @@
+use crate::app::AppTools;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("category")
+        .about("Sets the category given to a payee's transactions when they have none.")
+        .long_about(
+            "Sets the category given to a payee's transactions when they have none. The default \
+             category is also remembered from the last transaction added with the payee and a \
+             category.",
+        )
+        .arg(
+            Arg::with_name("payee")
+                .required(true)
+                .takes_value(true)
+                .help("The payee"),
+        )
+        .arg(
+            Arg::with_name("category")
+                .takes_value(true)
+                .required_unless("clear")
+                .help("The default category"),
+        )
+        .arg(
+            Arg::with_name("clear")
+                .long("clear")
+                .conflicts_with("category")
+                .help("Remove the default category"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let payee = matches.value_of("payee").unwrap();
+    let category = matches.category_of("category", budget);
+    budget.set_payee_category(payee, category);
+}
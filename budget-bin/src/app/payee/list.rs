@@ -0,0 +1,58 @@
+use crate::app::report;
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::Budget;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("list")
+        .about("Lists the payees, with the number, total and latest date of their transactions.")
+    // app settings
+
+    // subcommands
+}
+
+pub fn run(budget: &mut Budget, config: &Config, _matches: &ArgMatches) {
+    let mut table = Table::new(vec![
+        "payee",
+        "transactions",
+        "total",
+        "last",
+        "category",
+        "aliases",
+    ])
+    .align(1, Align::Right)
+    .align(2, Align::Right);
+
+    let all_stats = budget.payee_stats();
+    for stats in &all_stats {
+        let payee = budget.payees().find(|p| p.name() == stats.payee);
+        table.push(vec![
+            stats.payee.clone(),
+            stats.count.to_string(),
+            config.format_amount(stats.total, budget.currency()),
+            stats
+                .last
+                .map(|d| d.format(config.display_date_format()).to_string())
+                .unwrap_or_default(),
+            payee
+                .and_then(|p| p.category())
+                .unwrap_or_default()
+                .to_string(),
+            payee
+                .map(|p| {
+                    p.aliases()
+                        .iter()
+                        .map(|a| a.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .unwrap_or_default(),
+        ]);
+    }
+
+    print!("{}", table);
+
+    let unconverted = all_stats.iter().map(|s| s.unconverted).sum();
+    report::warn_unconverted(unconverted, "transactions", "the totals");
+}
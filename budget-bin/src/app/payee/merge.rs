@@ -0,0 +1,40 @@
+use crate::app::AppTools;
+use crate::shell;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("merge")
+        .about("Merges one payee into another.")
+        .long_about(
+            "Merges one payee into another. The transactions of the first payee are given the \
+             name of the second, and the name of the first becomes an alias of the second, so \
+             that future imports are matched too.",
+        )
+        .arg(
+            Arg::with_name("from")
+                .required(true)
+                .takes_value(true)
+                .help("The payee to merge, which is removed"),
+        )
+        .arg(
+            Arg::with_name("into")
+                .required(true)
+                .takes_value(true)
+                .help("The payee to merge it into"),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let from = matches.payee_of("from", budget).unwrap();
+    let from = from.as_str();
+    let into = matches.payee_of("into", budget).unwrap();
+    let into = into.as_str();
+    match budget.merge_payee(from, into) {
+        Ok(count) => println!("merged {} transactions into '{}'", count, into),
+        Err(e) => {
+            eprintln!("{}", e);
+            shell::exit(1)
+        }
+    }
+}
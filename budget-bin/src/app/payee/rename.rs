@@ -0,0 +1,28 @@
+use crate::app::AppTools;
+use crate::shell;
+use budget_lib::{Budget, PayeeError};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("rename")
+        .about("Renames a payee, and all of its transactions.")
+        .arg(Arg::with_name("old name").required(true).takes_value(true))
+        .arg(Arg::with_name("new name").required(true).takes_value(true))
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let old = matches.payee_of("old name", budget).unwrap();
+    let old = old.as_str();
+    let new = matches.value_of("new name").unwrap();
+    match budget.rename_payee(old, new) {
+        Ok(count) => log::info!("renamed {} transactions from '{}' to '{}'", count, old, new),
+        Err(e @ PayeeError::Exists(_)) => {
+            eprintln!("{}. use 'budget payee merge' to combine them", e);
+            shell::exit(1)
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            shell::exit(1)
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! The quick-entry syntax for adding a transaction without a subcommand, such as
+//! `budget 4.50 coffee @starbucks yesterday` or `budget in 2000 @employer`.
+
+use crate::app::category;
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::dates;
+use crate::dates::{Calendar, DateError};
+use crate::names::{self, NameError};
+use crate::shell;
+use budget_lib::{Budget, Money, Transaction};
+use chrono::NaiveDate;
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use decimal::d128;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("budget")
+        .about("Adds a transaction quickly.")
+        .usage(
+            "budget [in] <amount> [category] [@payee] [#tag]... [date] [--create] [--account <name>]",
+        )
+        .after_help(
+            "A positive amount is money spent, unless it follows 'in'. The category may be \
+             shortened to the start of its name. Quote tags, since the shell treats '#' as the \
+             start of a comment: budget 12 lunch '#work' yesterday",
+        )
+        // app settings
+        .setting(AppSettings::AllowLeadingHyphen)
+        .setting(AppSettings::NoBinaryName)
+        // arguments
+        .arg(
+            Arg::with_name("words")
+                .multiple(true)
+                .required(true)
+                .allow_hyphen_values(true),
+        )
+        .arg(category::create_arg())
+        .arg(
+            Arg::with_name("account")
+                .long("account")
+                .short("a")
+                .takes_value(true)
+                .help("The account of the transaction [default: the configured default_account]"),
+        )
+}
+
+/// Add a transaction from the quick-entry syntax. `name` is the first argument,
+/// which clap took to be the name of a subcommand, and `matches` holds the rest.
+pub fn run(budget: &mut Budget, config: &Config, name: &str, matches: &ArgMatches) {
+    let args = std::iter::once(name).chain(matches.values_of("").into_iter().flatten());
+    let matches = command()
+        .get_matches_from_safe(args)
+        .unwrap_or_else(|e| shell::exit_with(e));
+    let words: Vec<&str> = matches.values_of("words").unwrap().collect();
+
+    let today = chrono::Local::now().date_naive();
+    let is_category = |name: &str| {
+        !matches!(
+            names::category(budget, name),
+            Err(NameError::Unknown { .. })
+        )
+    };
+    let entry = split_words(&words, today, &config.calendar(), is_category).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        shell::exit(1)
+    });
+
+    let account = matches.account_or_default("account", budget, config);
+    let account = account.as_deref();
+    let currency = budget.account_currency(account);
+    let amount = match Money::<d128>::parse(entry.amount, currency, &config.locale()) {
+        Ok(m) if !m.amount().is_negative() => m.amount(),
+        Ok(_) => {
+            eprintln!("the amount should be positive. use 'budget in <amount>' for income");
+            shell::exit(1)
+        }
+        Err(_) => {
+            eprintln!(
+                "'{}' is not a command or an amount. see 'budget --help'",
+                entry.amount
+            );
+            shell::exit(1)
+        }
+    };
+
+    let mut t = Transaction::new(if entry.income { amount } else { -amount });
+    t.set_account(account);
+    t.set_payee(entry.payee);
+    for tag in entry.tags {
+        t.tag(tag);
+    }
+    if let Some(category) = entry.category {
+        let path = super::new_category(budget, &category, matches.is_present("create"));
+        t.set_category(Some(path));
+    }
+    if let Some(date) = entry.date {
+        t.set_date_transaction(Some(dates::midday(date)));
+    }
+
+    let described = describe(budget, config, &t);
+    if let Err(e) = budget.add_entry(t) {
+        eprintln!("{}", e);
+        shell::exit(1)
+    }
+    println!("added {}", described);
+}
+
+/// The parts of a quick entry, before they are looked up in the budget.
+#[derive(Debug, PartialEq)]
+struct Entry<'a> {
+    income: bool,
+    amount: &'a str,
+    category: Option<String>,
+    payee: Option<&'a str>,
+    tags: Vec<&'a str>,
+    date: Option<NaiveDate>,
+}
+
+/// Split the words of a quick entry into its parts. `is_category` says whether
+/// some words name a category in the budget.
+///
+/// The words which aren't a payee or a tag are a category followed by a date,
+/// either of which may be left out. A category in the budget is preferred to a
+/// date, so "sun" is a category named "sunglasses" if there is one, and Sunday
+/// otherwise. A category may be several words, such as "eating out".
+fn split_words<'a>(
+    words: &[&'a str],
+    today: NaiveDate,
+    calendar: &Calendar,
+    is_category: impl Fn(&str) -> bool,
+) -> Result<Entry<'a>, String> {
+    let (income, words) = match words {
+        ["in", rest @ ..] => (true, rest),
+        words => (false, words),
+    };
+    let (amount, words) = match words {
+        [amount, rest @ ..] => (*amount, rest),
+        [] => return Err("expected an amount after 'in', such as 'budget in 2000'".to_string()),
+    };
+
+    let mut entry = Entry {
+        income,
+        amount,
+        category: None,
+        payee: None,
+        tags: Vec::new(),
+        date: None,
+    };
+    let mut plain = Vec::new();
+    for word in words {
+        if let Some(payee) = word.strip_prefix('@') {
+            entry.payee = Some(payee);
+        } else if let Some(tag) = word.strip_prefix('#') {
+            entry.tags.push(tag);
+        } else {
+            plain.push(*word);
+        }
+    }
+
+    let parse_date = |words: &[&str]| dates::parse_date(&words.join(" "), today, calendar);
+    // the longest category in the budget, with a date or nothing after it
+    let mut error = None;
+    for split in (1..=plain.len()).rev() {
+        let category = plain[..split].join(" ");
+        if !is_category(&category) {
+            continue;
+        }
+        if split == plain.len() {
+            entry.category = Some(category);
+            return Ok(entry);
+        }
+        match parse_date(&plain[split..]) {
+            Ok(date) => {
+                entry.category = Some(category);
+                entry.date = Some(date);
+                return Ok(entry);
+            }
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        }
+    }
+    // otherwise a new category, followed by the longest date
+    for split in 0..plain.len() {
+        match parse_date(&plain[split..]) {
+            Ok(date) => {
+                entry.category = Some(plain[..split].join(" ")).filter(|c| !c.is_empty());
+                entry.date = Some(date);
+                return Ok(entry);
+            }
+            Err(e @ DateError::Ambiguous(..)) => return Err(e.to_string()),
+            Err(_) => (),
+        }
+    }
+    match error {
+        Some(e) => Err(e.to_string()),
+        None => {
+            entry.category = Some(plain.join(" ")).filter(|c| !c.is_empty());
+            Ok(entry)
+        }
+    }
+}
+
+/// Describe a transaction which is about to be added, such as
+/// "-4.50 in coffee @starbucks on 2026-10-17".
+fn describe(budget: &Budget, config: &Config, t: &Transaction<d128>) -> String {
+    let mut description =
+        config.format_amount(*t.amount(), budget.account_currency(t.account().as_deref()));
+    if let Some(category) = t.category_name() {
+        description += &format!(" in {}", category);
+    }
+    if let Some(payee) = t.payee() {
+        description += &format!(" @{}", payee);
+    }
+    for tag in t.tags() {
+        description += &format!(" #{}", tag);
+    }
+    description += &format!(
+        " on {}",
+        t.date()
+            .with_timezone(&chrono::Local)
+            .format(config.display_date_format())
+    );
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(line: &str) -> Result<Entry<'_>, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let categories = ["eating out", "groceries", "sunglasses"];
+        let is_category = |name: &str| {
+            categories
+                .iter()
+                .any(|c| c.starts_with(&name.to_lowercase()))
+        };
+        // a sunday
+        let today = "2026-10-18".parse().unwrap();
+        split_words(&words, today, &Calendar::default(), is_category)
+    }
+
+    fn date(s: &str) -> Option<NaiveDate> {
+        Some(s.parse().unwrap())
+    }
+
+    #[test]
+    fn splitting_words() {
+        let entry = split("4.50 groceries @tesco #food yesterday").unwrap();
+        assert_eq!(
+            entry,
+            Entry {
+                income: false,
+                amount: "4.50",
+                category: Some("groceries".to_string()),
+                payee: Some("tesco"),
+                tags: vec!["food"],
+                date: date("2026-10-17"),
+            }
+        );
+
+        // a category may be several words
+        let entry = split("12 eating out").unwrap();
+        assert_eq!(entry.category.as_deref(), Some("eating out"));
+        assert_eq!(entry.date, None);
+        let entry = split("12 eating out last fri").unwrap();
+        assert_eq!(entry.category.as_deref(), Some("eating out"));
+        assert_eq!(entry.date, date("2026-10-16"));
+
+        // a category in the budget comes before a date
+        assert_eq!(split("5 sun").unwrap().category.as_deref(), Some("sun"));
+        let entry = split("5 fri").unwrap();
+        assert_eq!((entry.category, entry.date), (None, date("2026-10-16")));
+
+        // a new category, which is created or refused later
+        let entry = split("in 2000 salary 2 days ago").unwrap();
+        assert!(entry.income);
+        assert_eq!(entry.category.as_deref(), Some("salary"));
+        assert_eq!(entry.date, date("2026-10-16"));
+
+        assert!(split("in").unwrap_err().contains("expected an amount"));
+        assert!(split("5 groceries yesterdy")
+            .unwrap_err()
+            .contains("'yesterdy' is not a date"));
+        assert!(split("5 groceries 3/4").unwrap_err().contains("ambiguous"));
+    }
+}
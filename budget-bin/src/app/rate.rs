@@ -0,0 +1,29 @@
+use crate::config::Config;
+use budget_lib::Budget;
+use clap::{App, AppSettings, ArgMatches, SubCommand};
+
+mod add;
+mod import;
+mod list;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("rate")
+        .about("Manages the exchange rates used for accounts in foreign currencies.")
+        // app settings
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        // subcommands
+        .subcommand(add::command())
+        .subcommand(import::command())
+        .subcommand(list::command())
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("add", Some(submatches)) => add::run(budget, config, submatches),
+        ("import", Some(submatches)) => import::run(budget, submatches),
+        ("list", Some(submatches)) => list::run(budget, config, submatches),
+        // If no subcommand is found, assume 'list'
+        (_, None) => list::run(budget, config, matches),
+        _ => panic!("something is missing!"),
+    }
+}
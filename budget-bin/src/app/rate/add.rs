@@ -0,0 +1,61 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::shell;
+use budget_lib::{Budget, Currency, CurrencyCode};
+use chrono::NaiveDate;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use decimal::d128;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("add")
+        .about("Adds the exchange rate between two currencies on a date.")
+        // app settings
+        // arguments
+        .arg(
+            Arg::with_name("from")
+                .takes_value(true)
+                .help("The currency being converted from, such as 'EUR'")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("to")
+                .takes_value(true)
+                .help("The currency being converted to, such as 'GBP'")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("rate")
+                .takes_value(true)
+                .help("The number of units of 'to' for each unit of 'from'")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("date")
+                .long("date")
+                .short("d")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help("The date that the rate applies from [default: today]"),
+        )
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let from: CurrencyCode = matches
+        .typed_value_of("from", Some("such as 'EUR'"))
+        .unwrap();
+    let to: CurrencyCode = matches.typed_value_of("to", Some("such as 'GBP'")).unwrap();
+    let date: NaiveDate = matches
+        .date_of("date", config)
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+
+    let rate = match <d128 as Currency>::parse(matches.value_of("rate").unwrap()) {
+        Ok(r) if r > d128::zero() => r,
+        _ => {
+            eprintln!("the exchange rate must be a positive number");
+            shell::exit(1)
+        }
+    };
+
+    budget.add_rate(from, to, date, rate);
+    log::info!("Exchange rate added: {} {} {} {}", date, from, to, rate);
+}
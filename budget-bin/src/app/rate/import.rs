@@ -0,0 +1,79 @@
+use crate::shell;
+use budget_lib::{Budget, Currency, ExchangeRate};
+use chrono::NaiveDate;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use decimal::d128;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("import")
+        .about("Imports exchange rates from a CSV file.")
+        .long_about(
+            "Imports exchange rates from a CSV file.\n\n\
+             Each row has the columns 'date,from,to,rate', such as '2026-01-31,EUR,GBP,0.86'. \
+             A header row is optional.",
+        )
+        // app settings
+        // arguments
+        .arg(
+            Arg::with_name("path")
+                .takes_value(true)
+                .help("The CSV file to import")
+                .required(true),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let path = matches.value_of("path").unwrap();
+    let mut reader = match csv::ReaderBuilder::new()
+        .has_headers(false)
+        .trim(csv::Trim::All)
+        .from_path(path)
+    {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("unable to read '{}': {}", path, e);
+            shell::exit(1)
+        }
+    };
+
+    let mut rates = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record.unwrap_or_else(|e| {
+            eprintln!("unable to read '{}': {}", path, e);
+            shell::exit(1)
+        });
+
+        match parse_record(&record) {
+            Some(rate) => rates.push(rate),
+            // the first row may be a header
+            None if i == 0 => continue,
+            None => {
+                eprintln!(
+                    "line {}: expected 'date,from,to,rate', such as '2026-01-31,EUR,GBP,0.86'",
+                    i + 1
+                );
+                shell::exit(1)
+            }
+        }
+    }
+
+    let count = rates.len();
+    budget.add_rates(rates);
+    log::info!("imported {} exchange rates from {}", count, path);
+    println!("imported {} exchange rates", count);
+}
+
+fn parse_record(record: &csv::StringRecord) -> Option<ExchangeRate> {
+    if record.len() != 4 {
+        return None;
+    }
+
+    Some(ExchangeRate {
+        date: record[0].parse::<NaiveDate>().ok()?,
+        from: record[1].parse().ok()?,
+        to: record[2].parse().ok()?,
+        rate: <d128 as Currency>::parse(&record[3])
+            .ok()
+            .filter(|r| *r > d128::zero())?,
+    })
+}
@@ -0,0 +1,26 @@
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::Budget;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("list")
+    // app settings
+
+    // subcommands
+}
+
+pub fn run(budget: &mut Budget, config: &Config, _matches: &ArgMatches) {
+    let mut table = Table::new(vec!["date", "from", "to", "rate"]).align(3, Align::Right);
+
+    for r in budget.rates().iter() {
+        table.push(vec![
+            r.date.format(config.display_date_format()).to_string(),
+            r.from.to_string(),
+            r.to.to_string(),
+            r.rate.to_string(),
+        ]);
+    }
+
+    print!("{}", table);
+}
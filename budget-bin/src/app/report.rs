@@ -0,0 +1,135 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::shell;
+use budget_lib::{Budget, CalendarMonth};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use std::str::FromStr;
+
+mod cashflow;
+mod fx;
+mod networth;
+mod spending;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("report")
+        .about("Reports on the budget.")
+        // app settings
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        // subcommands
+        .subcommand(fx::command())
+        .subcommand(spending::command())
+        .subcommand(cashflow::command())
+        .subcommand(networth::command())
+}
+
+pub fn run(budget: &Budget, config: &Config, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("fx", Some(submatches)) => fx::run(budget, config, submatches),
+        ("spending", Some(submatches)) => spending::run(budget, config, submatches),
+        ("cashflow", Some(submatches)) => cashflow::run(budget, config, submatches),
+        ("networth", Some(submatches)) => networth::run(budget, config, submatches),
+        _ => panic!("something is missing!"),
+    }
+}
+
+/// The ways that a report can be written out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("'{}' is not a report format", s)),
+        }
+    }
+}
+
+/// The arguments for choosing the range of months that a report covers.
+pub fn month_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("from")
+            .long("from")
+            .takes_value(true)
+            .allow_hyphen_values(true)
+            .value_name("month")
+            .help(
+                "The first month of the report, such as 2026-01 [default: 11 months before --to]",
+            ),
+        Arg::with_name("to")
+            .long("to")
+            .takes_value(true)
+            .allow_hyphen_values(true)
+            .value_name("month")
+            .help("The last month of the report, such as 2026-09 [default: this month]"),
+    ]
+}
+
+/// The argument for choosing the format of a report.
+pub fn format_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("format")
+        .long("format")
+        .takes_value(true)
+        .possible_values(&["table", "csv", "json"])
+        .default_value("table")
+        .help("How the report is written out")
+}
+
+/// The argument for drawing a report as a chart rather than a table.
+pub fn chart_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("chart")
+        .long("chart")
+        .conflicts_with("format")
+        .help("Draw the report as a chart")
+}
+
+/// Returns the range of months chosen with the arguments from `month_args`.
+pub fn month_range(
+    budget: &Budget,
+    matches: &ArgMatches,
+    config: &Config,
+) -> (CalendarMonth, CalendarMonth) {
+    let to = matches
+        .month_of("to", config)
+        .unwrap_or_else(|| budget.month_of(chrono::Local::now()));
+    let from = matches
+        .month_of("from", config)
+        .unwrap_or_else(|| (0..11).fold(to, |m, _| m.pred()));
+
+    if to < from {
+        eprintln!("the report must end after it starts");
+        shell::exit(1)
+    }
+    (from, to)
+}
+
+/// Warn that amounts are left out of a report, because there is no exchange rate
+/// for their currency.
+pub fn warn_unconverted(count: usize, what: &str, from: &str) {
+    if count > 0 {
+        eprintln!(
+            "warning: {} {} are left out of {}, because there is no exchange rate for \
+             their currency. add one with 'budget rate add'",
+            count, what, from
+        );
+    }
+}
+
+/// Write out rows of plain values as CSV, with a header row.
+pub fn write_csv(header: Vec<String>, rows: Vec<Vec<String>>) {
+    let mut writer = csv::Writer::from_writer(std::io::stdout());
+    for row in std::iter::once(header).chain(rows) {
+        if let Err(e) = writer.write_record(&row) {
+            eprintln!("unable to write report: {}", e);
+            shell::exit(1)
+        }
+    }
+}
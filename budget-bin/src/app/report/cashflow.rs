@@ -0,0 +1,153 @@
+use crate::app::report::{self, Format};
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::{Budget, CashFlowReport, Currency};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use decimal::d128;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("cashflow")
+        .about("Shows the income and spending of each month, and the savings rate.")
+        // arguments
+        .args(&report::month_args())
+        .arg(
+            Arg::with_name("by account")
+                .long("by-account")
+                .help("Break the cash flow down by account rather than by month"),
+        )
+        .arg(report::format_arg())
+}
+
+pub fn run(budget: &Budget, config: &Config, matches: &ArgMatches) {
+    let (from, to) = report::month_range(budget, matches, config);
+    let cashflow = CashFlowReport::new(budget, from, to);
+    report::warn_unconverted(cashflow.unconverted(), "transactions", "the report");
+    let format = matches.typed_value_of("format", None).unwrap();
+
+    let (header, rows, total) = if matches.is_present("by account") {
+        by_account(&cashflow)
+    } else {
+        by_month(&cashflow)
+    };
+
+    match format {
+        Format::Table => {
+            let format = |x: &Value| match x {
+                Value::Text(s) => s.clone(),
+                Value::Amount(x) => config.format_amount(*x, budget.currency()),
+                Value::Rate(x) => x
+                    .map(|x| format!("{}%", Currency::format(&(x * d128::from(100)), 1)))
+                    .unwrap_or_default(),
+            };
+            let mut table = Table::new(header.to_vec());
+            for i in 1..header.len() {
+                table = table.align(i, Align::Right);
+            }
+            for row in rows.iter().chain(Some(&total)) {
+                table.push(row.iter().map(format).collect::<Vec<String>>());
+            }
+            print!("{}", table);
+        }
+        Format::Csv => {
+            let minor_units = budget.currency().minor_units();
+            report::write_csv(
+                header.iter().map(ToString::to_string).collect(),
+                rows.iter()
+                    .map(|row| {
+                        row.iter()
+                            .map(|x| x.plain(minor_units).unwrap_or_default())
+                            .collect()
+                    })
+                    .collect(),
+            );
+        }
+        Format::Json => {
+            let minor_units = budget.currency().minor_units();
+            let to_json = |row: &Vec<Value>| {
+                header
+                    .iter()
+                    .zip(row)
+                    .map(|(h, x)| (h.replace(' ', "_"), x.plain(minor_units).into()))
+                    .collect::<serde_json::Map<_, _>>()
+            };
+            let json = serde_json::json!({
+                "currency": budget.currency().as_str(),
+                "from": from.to_string(),
+                "to": to.to_string(),
+                "rows": rows.iter().map(to_json).collect::<Vec<_>>(),
+                "total": to_json(&total),
+            });
+            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        }
+    }
+}
+
+/// A value in a cash flow report, before it is formatted for output.
+enum Value {
+    Text(String),
+    Amount(d128),
+    Rate(Option<d128>),
+}
+
+impl Value {
+    /// Format the value for CSV or JSON output.
+    fn plain(&self, minor_units: u32) -> Option<String> {
+        match self {
+            Value::Text(s) => Some(s.clone()),
+            Value::Amount(x) => Some(Currency::format(x, minor_units)),
+            Value::Rate(x) => x.map(|x| Currency::format(&x, 4)),
+        }
+    }
+}
+
+type Rows = (&'static [&'static str], Vec<Vec<Value>>, Vec<Value>);
+
+fn by_month(cashflow: &CashFlowReport) -> Rows {
+    let header = &["month", "inflow", "outflow", "net", "savings rate"];
+    let row = |label: String, m: &budget_lib::MonthFlow| {
+        vec![
+            Value::Text(label),
+            Value::Amount(m.inflow),
+            Value::Amount(m.outflow),
+            Value::Amount(m.net()),
+            Value::Rate(m.savings_rate()),
+        ]
+    };
+
+    let rows = cashflow
+        .months()
+        .iter()
+        .map(|m| row(m.month.to_string(), m))
+        .collect();
+    (header, rows, row("total".to_string(), &cashflow.total()))
+}
+
+fn by_account(cashflow: &CashFlowReport) -> Rows {
+    let header = &["account", "inflow", "outflow", "transfers", "net"];
+
+    let mut total = [d128::zero(); 4];
+    let rows = cashflow
+        .accounts()
+        .iter()
+        .map(|a| {
+            let values = [a.inflow, a.outflow, a.transfers, a.net()];
+            for (t, x) in total.iter_mut().zip(&values) {
+                *t += *x;
+            }
+
+            let name = a
+                .account
+                .clone()
+                .unwrap_or_else(|| "(no account)".to_string());
+            std::iter::once(Value::Text(name))
+                .chain(values.iter().map(|x| Value::Amount(*x)))
+                .collect()
+        })
+        .collect();
+
+    let total = std::iter::once(Value::Text("total".to_string()))
+        .chain(total.iter().map(|x| Value::Amount(*x)))
+        .collect();
+    (header, rows, total)
+}
@@ -0,0 +1,55 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::Budget;
+use chrono::NaiveDate;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("fx")
+        .about("Shows the unrealised exchange gains and losses of foreign currency accounts.")
+        // arguments
+        .arg(
+            Arg::with_name("date")
+                .long("date")
+                .short("d")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help("The date to value the accounts on [default: today]"),
+        )
+}
+
+pub fn run(budget: &Budget, config: &Config, matches: &ArgMatches) {
+    let date: NaiveDate = matches
+        .date_of("date", config)
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    let currency = budget.currency();
+    let format = |x: Option<_>| {
+        x.map(|x| config.format_amount(x, currency))
+            .unwrap_or_else(|| "no rate".to_string())
+    };
+
+    let mut table = Table::new(vec![
+        "account",
+        "balance",
+        "book value",
+        "market value",
+        "gain",
+    ])
+    .align(1, Align::Right)
+    .align(2, Align::Right)
+    .align(3, Align::Right)
+    .align(4, Align::Right);
+
+    for p in budget.fx_positions(date) {
+        table.push(vec![
+            p.account.clone(),
+            config.format_amount(p.balance, p.currency),
+            format(p.book_value),
+            format(p.market_value),
+            format(p.gain()),
+        ]);
+    }
+
+    print!("{}", table);
+}
@@ -0,0 +1,119 @@
+use crate::app::report::{self, Format};
+use crate::app::AppTools;
+use crate::chart;
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::{Budget, Currency, NetWorthReport};
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("networth")
+        .about("Shows the balance of every account, and the total, at the end of each month.")
+        // arguments
+        .args(&report::month_args())
+        .arg(report::format_arg())
+        .arg(report::chart_arg())
+}
+
+pub fn run(budget: &Budget, config: &Config, matches: &ArgMatches) {
+    let (from, to) = report::month_range(budget, matches, config);
+    let networth = NetWorthReport::new(budget, from, to);
+    report::warn_unconverted(networth.unconverted(), "balances", "the net worth");
+
+    if matches.is_present("chart") {
+        let labels: Vec<String> = networth
+            .rows()
+            .iter()
+            .map(|r| r.month.to_string())
+            .collect();
+        let values: Vec<f64> = networth
+            .rows()
+            .iter()
+            .map(|r| chart::to_f64(r.total))
+            .collect();
+        let format = |x: f64| {
+            let x = x.to_string().parse().unwrap_or_default();
+            config.format_amount(x, budget.currency())
+        };
+        print!(
+            "{}",
+            chart::line_chart(&labels, &values, 12, format, chart::Charset::detect())
+        );
+        return;
+    }
+
+    let mut header = vec!["month".to_string()];
+    header.extend(
+        networth
+            .accounts()
+            .iter()
+            .map(|a| a.clone().unwrap_or_else(|| "(no account)".to_string())),
+    );
+    header.push("net worth".to_string());
+
+    match matches.typed_value_of("format", None).unwrap() {
+        Format::Table => {
+            let mut table = Table::new(header.clone());
+            for i in 1..header.len() {
+                table = table.align(i, Align::Right);
+            }
+            for row in networth.rows() {
+                let mut record = vec![row.month.to_string()];
+                record.extend(
+                    row.balances
+                        .iter()
+                        .chain(Some(&Some(row.total)))
+                        .map(|x| match x {
+                            Some(x) => config.format_amount(*x, budget.currency()),
+                            None => "no rate".to_string(),
+                        }),
+                );
+                table.push(record);
+            }
+            print!("{}", table);
+        }
+        Format::Csv => {
+            let minor_units = budget.currency().minor_units();
+            let rows = networth
+                .rows()
+                .iter()
+                .map(|row| {
+                    let mut record = vec![row.month.to_string()];
+                    record.extend(row.balances.iter().chain(Some(&Some(row.total))).map(|x| {
+                        x.map(|x| Currency::format(&x, minor_units))
+                            .unwrap_or_default()
+                    }));
+                    record
+                })
+                .collect();
+            report::write_csv(header, rows);
+        }
+        Format::Json => {
+            let minor_units = budget.currency().minor_units();
+            let months: Vec<_> = networth
+                .rows()
+                .iter()
+                .map(|row| {
+                    let accounts: serde_json::Map<_, _> = header[1..]
+                        .iter()
+                        .zip(&row.balances)
+                        .map(|(a, x)| {
+                            let x = x.map(|x| Currency::format(&x, minor_units));
+                            (a.clone(), x.into())
+                        })
+                        .collect();
+                    serde_json::json!({
+                        "month": row.month.to_string(),
+                        "accounts": accounts,
+                        "net_worth": Currency::format(&row.total, minor_units),
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "currency": budget.currency().as_str(),
+                "months": months,
+            });
+            println!("{}", serde_json::to_string_pretty(&json).unwrap());
+        }
+    }
+}
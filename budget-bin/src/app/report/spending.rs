@@ -0,0 +1,208 @@
+use crate::app::report::{self, Format};
+use crate::app::AppTools;
+use crate::chart;
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::{Budget, Currency, Grouping, SpendingReport};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use decimal::d128;
+use std::cmp::Ordering;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("spending")
+        .about("Shows the spending over a range of months, broken down by category.")
+        // arguments
+        .args(&report::month_args())
+        .arg(
+            Arg::with_name("group")
+                .long("group")
+                .short("g")
+                .takes_value(true)
+                .possible_values(&["master", "category", "payee", "tag"])
+                .default_value("category")
+                .help("How the spending is broken down"),
+        )
+        .arg(
+            Arg::with_name("tag")
+                .long("tag")
+                .short("t")
+                .takes_value(true)
+                .help("Only count the transactions with a tag, or one of its subtags"),
+        )
+        .arg(report::format_arg())
+        .arg(report::chart_arg())
+}
+
+pub fn run(budget: &Budget, config: &Config, matches: &ArgMatches) {
+    let (from, to) = report::month_range(budget, matches, config);
+    let grouping: Grouping = matches.typed_value_of("group", None).unwrap();
+    let spending = match matches.value_of("tag") {
+        Some(tag) => SpendingReport::filtered(budget, from, to, grouping, |t| t.has_tag(tag)),
+        None => SpendingReport::new(budget, from, to, grouping),
+    };
+    report::warn_unconverted(spending.unconverted(), "transactions", "the report");
+
+    if matches.is_present("chart") {
+        print_chart(&spending);
+        return;
+    }
+
+    // categories are shown under their groups
+    let by_category = grouping == Grouping::Category;
+    match matches.typed_value_of("format", None).unwrap() {
+        Format::Table => print_table(budget, config, &spending, by_category),
+        Format::Csv => print_csv(budget, &spending, by_category),
+        Format::Json => print_json(budget, &spending, by_category),
+    }
+}
+
+fn print_table(budget: &Budget, config: &Config, spending: &SpendingReport, by_category: bool) {
+    let format = |x| config.format_amount(x, budget.currency());
+    let percent = |x: d128| format!("{}%", Currency::format(&(x * d128::from(100)), 1));
+
+    let mut table = Table::new(vec!["group", "total", "per month", "share"])
+        .align(1, Align::Right)
+        .align(2, Align::Right)
+        .align(3, Align::Right);
+
+    let headings = by_category && budget.master_categories().next().is_some();
+    let mut master = None;
+    for row in spending.rows() {
+        if headings && master != Some(row.master_category()) {
+            master = Some(row.master_category());
+            table.push(vec![
+                row.master_category().unwrap_or("(no group)").to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+            ]);
+        }
+        // subcategories and subtags are shown by name under their parents
+        let indent = row.depth() + usize::from(headings);
+        let group = format!("{}{}", "  ".repeat(indent), row.name());
+        table.push(vec![
+            group,
+            format(row.total()),
+            format(row.average()),
+            percent(spending.share(row)),
+        ]);
+    }
+    table.push(vec![
+        "total".to_string(),
+        format(spending.total()),
+        format(spending.average()),
+        String::new(),
+    ]);
+
+    print!("{}", table);
+}
+
+/// The number of groups drawn separately on the chart. The rest are drawn together.
+const CHART_GROUPS: usize = 7;
+
+fn print_chart(spending: &SpendingReport) {
+    let labels: Vec<String> = spending.months().iter().map(ToString::to_string).collect();
+    let values = |row: &budget_lib::SpendingRow| -> Vec<f64> {
+        row.by_month().iter().map(|x| chart::to_f64(*x)).collect()
+    };
+
+    // the biggest groups are drawn separately, and subcategories are drawn as part of their parents
+    let mut rows: Vec<&budget_lib::SpendingRow> =
+        spending.rows().iter().filter(|r| r.depth() == 0).collect();
+    rows.sort_by(|a, b| b.total().partial_cmp(&a.total()).unwrap_or(Ordering::Equal));
+    let mut series: Vec<(String, Vec<f64>)> = rows
+        .iter()
+        .take(CHART_GROUPS)
+        .map(|row| (row.group().to_string(), values(row)))
+        .collect();
+    if rows.len() > CHART_GROUPS {
+        let mut other = vec![0.0; labels.len()];
+        for row in &rows[CHART_GROUPS..] {
+            for (o, x) in other.iter_mut().zip(values(row)) {
+                *o += x;
+            }
+        }
+        series.push(("(other)".to_string(), other));
+    }
+
+    print!(
+        "{}",
+        chart::stacked_bars(&labels, &series, 60, chart::Charset::detect())
+    );
+}
+
+fn print_csv(budget: &Budget, spending: &SpendingReport, by_category: bool) {
+    let amount = |x: d128| Currency::format(&x, budget.currency().minor_units());
+
+    let mut header: Vec<String> = vec![
+        "group".into(),
+        "depth".into(),
+        "total".into(),
+        "average".into(),
+        "share".into(),
+    ];
+    if by_category {
+        header.insert(1, "master category".into());
+    }
+    header.extend(spending.months().iter().map(ToString::to_string));
+
+    let rows = spending
+        .rows()
+        .iter()
+        .map(|row| {
+            let mut record = vec![
+                row.group().to_string(),
+                row.depth().to_string(),
+                amount(row.total()),
+                amount(row.average()),
+                Currency::format(&spending.share(row), 4),
+            ];
+            if by_category {
+                record.insert(1, row.master_category().unwrap_or_default().to_string());
+            }
+            record.extend(row.by_month().iter().map(|x| amount(*x)));
+            record
+        })
+        .collect();
+
+    report::write_csv(header, rows);
+}
+
+fn print_json(budget: &Budget, spending: &SpendingReport, by_category: bool) {
+    let amount = |x: d128| Currency::format(&x, budget.currency().minor_units());
+
+    let groups: Vec<_> = spending
+        .rows()
+        .iter()
+        .map(|row| {
+            let months: serde_json::Map<_, _> = spending
+                .months()
+                .iter()
+                .zip(row.by_month())
+                .map(|(m, x)| (m.to_string(), amount(*x).into()))
+                .collect();
+            let mut group = serde_json::json!({
+                "group": row.group(),
+                "depth": row.depth(),
+                "total": amount(row.total()),
+                "average": amount(row.average()),
+                "share": Currency::format(&spending.share(row), 4),
+                "months": months,
+            });
+            if by_category {
+                group["master_category"] = row.master_category().into();
+            }
+            group
+        })
+        .collect();
+
+    let json = serde_json::json!({
+        "currency": budget.currency().as_str(),
+        "from": spending.months().first().map(ToString::to_string),
+        "to": spending.months().last().map(ToString::to_string),
+        "total": amount(spending.total()),
+        "average": amount(spending.average()),
+        "groups": groups,
+    });
+    println!("{}", serde_json::to_string_pretty(&json).unwrap());
+}
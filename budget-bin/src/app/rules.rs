@@ -0,0 +1,164 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::{Action, Budget, Pattern, Rule, RuleChange, Transaction};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use decimal::d128;
+use uuid::Uuid;
+
+mod add;
+mod apply;
+mod list;
+mod remove;
+mod test;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("rules")
+        .about("Manages rules which categorise transactions, such as when they are imported.")
+        // app settings
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        // subcommands
+        .subcommand(add::command())
+        .subcommand(list::command())
+        .subcommand(test::command())
+        .subcommand(apply::command())
+        .subcommand(remove::command())
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("add", Some(submatches)) => add::run(budget, config, submatches),
+        ("list", Some(submatches)) => list::run(budget, config, submatches),
+        ("test", Some(submatches)) => test::run(budget, config, submatches),
+        ("apply", Some(submatches)) => apply::run(budget, config, submatches),
+        ("remove", Some(submatches)) => remove::run(budget, submatches),
+        // If no subcommand is found, assume 'list'
+        (_, None) => list::run(budget, config, matches),
+        _ => panic!("something is missing!"),
+    }
+}
+
+/// Returns the short id of a rule, as shown by 'budget rules list'.
+fn short_id(uuid: &Uuid) -> String {
+    uuid.to_string()[..8].to_string()
+}
+
+/// Describe the conditions of a rule, such as "payee /tesco/, 10.00 to 100.00".
+fn describe_conditions(rule: &Rule, budget: &Budget, config: &Config) -> String {
+    let currency = budget.account_currency(rule.account());
+    let amount = |x: d128| config.format_amount(x, currency);
+
+    let mut conditions = Vec::new();
+    if let Some(p) = rule.payee() {
+        conditions.push(format!("payee {}", p));
+    }
+    if let Some(p) = rule.description() {
+        conditions.push(format!("description {}", p));
+    }
+    match rule.amount() {
+        (Some(min), Some(max)) => conditions.push(format!("{} to {}", amount(min), amount(max))),
+        (Some(min), None) => conditions.push(format!("at least {}", amount(min))),
+        (None, Some(max)) => conditions.push(format!("at most {}", amount(max))),
+        (None, None) => (),
+    }
+    if let Some(a) = rule.account() {
+        conditions.push(format!("account {}", a));
+    }
+
+    if conditions.is_empty() {
+        "any transaction".to_string()
+    } else {
+        conditions.join(", ")
+    }
+}
+
+/// Describe the actions of a rule, such as "category groceries, tag #food".
+fn describe_actions(rule: &Rule) -> String {
+    rule.actions()
+        .iter()
+        .map(|action| match action {
+            Action::SetCategory(c) => format!("category {}", c),
+            Action::SetPayee(p) => format!("payee {}", p),
+            Action::AddTags(tags) => format!("tag {}", tags.join(" ")),
+            Action::Split(shares) => {
+                let shares: Vec<String> = shares
+                    .iter()
+                    .map(|s| format!("{} {}%", s.category, s.percent))
+                    .collect();
+                format!("split {}", shares.join(" "))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// The arguments for choosing which transactions to run the rules over.
+fn filter_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("filter")
+            .long("filter")
+            .takes_value(true)
+            .value_name("pattern")
+            .help("Only transactions with a payee or description which matches the pattern"),
+        Arg::with_name("all")
+            .long("all")
+            .help("Include transactions which already have a category"),
+    ]
+}
+
+/// Returns a function which selects the transactions chosen by the filter arguments.
+fn filter(matches: &ArgMatches) -> impl Fn(&Transaction<d128>) -> bool {
+    let pattern: Option<Pattern> = matches.typed_value_of("filter", Some("a regular expression"));
+    let all = matches.is_present("all");
+
+    move |t| {
+        let text_matches = |p: &Pattern| {
+            t.payee().as_deref().is_some_and(|s| p.is_match(s))
+                || t.description().as_deref().is_some_and(|s| p.is_match(s))
+        };
+        (all || t.category().is_none()) && pattern.as_ref().is_none_or(text_matches)
+    }
+}
+
+/// Show the changes made by the rules, with a row for each part of a split transaction.
+fn print_changes(changes: &[RuleChange], budget: &Budget, config: &Config) {
+    let mut table = Table::new(vec![
+        "date",
+        "amount",
+        "payee",
+        "description",
+        "category",
+        "new category",
+        "tags",
+    ])
+    .align(1, Align::Right);
+
+    for change in changes {
+        let before = &change.before;
+        for (i, t) in change.after.iter().enumerate() {
+            let first = i == 0;
+            table.push(vec![
+                if first {
+                    before
+                        .date()
+                        .format(config.display_date_format())
+                        .to_string()
+                } else {
+                    String::new()
+                },
+                config.format_amount(*t.amount(), budget.account_currency(t.account().as_deref())),
+                t.payee().clone().unwrap_or_default(),
+                t.description().clone().unwrap_or_default(),
+                if first {
+                    budget.category_name(before).unwrap_or_default()
+                } else {
+                    String::new()
+                },
+                budget.category_name(t).unwrap_or_default(),
+                t.tags().cloned().collect::<Vec<String>>().join(" "),
+            ]);
+        }
+    }
+
+    print!("{}", table);
+}
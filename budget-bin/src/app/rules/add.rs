@@ -0,0 +1,172 @@
+use crate::app::category;
+use crate::app::{new_category, AppTools};
+use crate::config::Config;
+use crate::shell;
+use budget_lib::{Action, Budget, Currency, Pattern, Rule, Share};
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
+use decimal::d128;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("add")
+        .about("Adds a rule, to be applied after the existing rules.")
+        .long_about(
+            "Adds a rule, to be applied after the existing rules.\n\n\
+             A rule matches transactions on all of the conditions it is given, and then \
+             makes each of the changes it is given. Patterns are regular expressions, \
+             and ignore case.",
+        )
+        // app settings
+        .setting(AppSettings::AllowNegativeNumbers)
+        // arguments
+        .arg(
+            Arg::with_name("payee")
+                .long("payee")
+                .takes_value(true)
+                .value_name("pattern")
+                .help("Match transactions with a payee which matches the pattern"),
+        )
+        .arg(
+            Arg::with_name("description")
+                .long("description")
+                .takes_value(true)
+                .value_name("pattern")
+                .help("Match transactions with a description which matches the pattern"),
+        )
+        .arg(
+            Arg::with_name("min")
+                .long("min")
+                .takes_value(true)
+                .value_name("amount")
+                .help("Match transactions of at least this amount, whether in or out"),
+        )
+        .arg(
+            Arg::with_name("max")
+                .long("max")
+                .takes_value(true)
+                .value_name("amount")
+                .help("Match transactions of at most this amount, whether in or out"),
+        )
+        .arg(
+            Arg::with_name("account")
+                .long("account")
+                .short("a")
+                .takes_value(true)
+                .help("Match transactions in this account"),
+        )
+        .arg(
+            Arg::with_name("set category")
+                .long("set-category")
+                .short("c")
+                .takes_value(true)
+                .value_name("category")
+                .help("Set the category of the transaction"),
+        )
+        .arg(
+            Arg::with_name("set payee")
+                .long("set-payee")
+                .takes_value(true)
+                .value_name("payee")
+                .help("Set the payee of the transaction"),
+        )
+        .arg(
+            Arg::with_name("tag")
+                .long("tag")
+                .short("t")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Add a tag to the transaction. May be given more than once"),
+        )
+        .arg(
+            Arg::with_name("split")
+                .long("split")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("category=percent")
+                .help("Split the transaction between categories, such as --split food=70 --split household=30"),
+        )
+        .arg(category::create_arg())
+        .group(
+            ArgGroup::with_name("actions")
+                .args(&["set category", "set payee", "tag", "split"])
+                .multiple(true)
+                .required(true),
+        )
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let mut rule = Rule::new();
+
+    let pattern = |name| matches.typed_value_of::<Pattern>(name, Some("a regular expression"));
+    if let Some(p) = pattern("payee") {
+        rule = rule.with_payee(p);
+    }
+    if let Some(p) = pattern("description") {
+        rule = rule.with_description(p);
+    }
+
+    let account = matches.account_of("account", budget);
+    if let Some(name) = &account {
+        rule = rule.with_account(name.as_str());
+    }
+    let currency = budget.account_currency(account.as_deref());
+    let min = matches.amount_of("min", currency, config).map(|x| x.abs());
+    let max = matches.amount_of("max", currency, config).map(|x| x.abs());
+    if min.is_some() || max.is_some() {
+        rule = rule.with_amount(min, max);
+    }
+
+    if let Some(category) = matches.new_category_of("set category", budget) {
+        rule = rule.with_action(Action::SetCategory(category));
+    }
+    if let Some(payee) = matches.value_of("set payee") {
+        rule = rule.with_action(Action::SetPayee(payee.to_string()));
+    }
+    if let Some(tags) = matches.values_of("tag") {
+        rule = rule.with_action(Action::AddTags(tags.map(String::from).collect()));
+    }
+    if let Some(splits) = matches.values_of("split") {
+        let create = matches.is_present("create");
+        let shares: Vec<Share> = splits
+            .map(parse_share)
+            .map(|mut s| {
+                s.category = new_category(budget, &s.category, create);
+                s
+            })
+            .collect();
+        let split = Action::split(shares).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            shell::exit(1)
+        });
+        rule = rule.with_action(split);
+    }
+
+    log::info!("added rule {}", rule.uuid());
+    if let Err(e) = budget.add_rule(rule) {
+        eprintln!("{}", e);
+        shell::exit(1)
+    }
+}
+
+/// Parse a share of a split, such as "food=70".
+fn parse_share(s: &str) -> Share {
+    let share = s.rsplit_once('=').and_then(|(category, percent)| {
+        let percent = <d128 as Currency>::parse(percent.trim().trim_end_matches('%')).ok()?;
+        Some(Share {
+            category: category.trim().to_string(),
+            percent,
+        })
+    });
+
+    match share {
+        Some(s) if !s.category.is_empty() && !s.percent.is_negative() => s,
+        _ => {
+            eprintln!(
+                "invalid split '{}'. expected 'category=percent', such as 'food=70'",
+                s
+            );
+            shell::exit(1)
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::config::Config;
+use crate::shell;
+use budget_lib::Budget;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("apply")
+        .about("Runs the rules over the transactions in the ledger.")
+        .long_about(
+            "Runs the rules over the transactions in the ledger. By default only transactions \
+             without a category are changed. Reconciled transactions and transfers between \
+             accounts are never changed.",
+        )
+        // arguments
+        .args(&super::filter_args())
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let changes = budget
+        .apply_rules(super::filter(matches))
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "can't apply the rules: {}. create it with 'budget category add', or change \
+                 the rules which use it",
+                e
+            );
+            shell::exit(1)
+        });
+    if changes.is_empty() {
+        println!("the rules don't change any transactions");
+        return;
+    }
+
+    super::print_changes(&changes, budget, config);
+    log::info!("rules changed {} transactions", changes.len());
+    println!("\nchanged {} transactions", changes.len());
+}
@@ -0,0 +1,24 @@
+use crate::config::Config;
+use crate::table::Table;
+use budget_lib::Budget;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("list").about("Lists the rules, in the order they are applied.")
+    // app settings
+
+    // subcommands
+}
+
+pub fn run(budget: &mut Budget, config: &Config, _matches: &ArgMatches) {
+    let mut table = Table::new(vec!["id", "when", "then"]);
+    for rule in budget.rules() {
+        table.push(vec![
+            super::short_id(rule.uuid()),
+            super::describe_conditions(rule, budget, config),
+            super::describe_actions(rule),
+        ]);
+    }
+
+    print!("{}", table);
+}
@@ -0,0 +1,40 @@
+use crate::shell;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("remove")
+        .about("Removes a rule. Transactions which it has already changed are kept as they are.")
+        // arguments
+        .arg(
+            Arg::with_name("id")
+                .takes_value(true)
+                .help("The id of the rule, as shown by 'budget rules list'")
+                .required(true),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let id = matches.value_of("id").unwrap().to_lowercase();
+    let matching: Vec<_> = budget
+        .rules()
+        .iter()
+        .map(|r| *r.uuid())
+        .filter(|u| u.to_string().starts_with(&id))
+        .collect();
+
+    match matching.as_slice() {
+        [uuid] => {
+            budget.remove_rule(uuid);
+            log::info!("removed rule {}", uuid);
+        }
+        [] => {
+            eprintln!("no rule with id '{}'", id);
+            shell::exit(1)
+        }
+        _ => {
+            eprintln!("more than one rule has an id starting '{}'", id);
+            shell::exit(1)
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::config::Config;
+use budget_lib::Budget;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("test")
+        .about("Shows the changes that the rules would make, without making them.")
+        // arguments
+        .args(&super::filter_args())
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let changes = budget.rule_changes(super::filter(matches));
+    if changes.is_empty() {
+        println!("the rules don't change any transactions");
+        return;
+    }
+
+    super::print_changes(&changes, budget, config);
+    println!(
+        "\n{} transactions would change. use 'budget rules apply' to change them",
+        changes.len()
+    );
+}
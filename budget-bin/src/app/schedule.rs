@@ -0,0 +1,68 @@
+use crate::config::Config;
+use crate::shell;
+use budget_lib::Budget;
+use clap::{App, AppSettings, ArgMatches, SubCommand};
+use uuid::Uuid;
+
+mod add;
+mod list;
+mod remove;
+mod skip;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("schedule")
+        .about("Manages transactions which recur on a schedule, such as rent or a salary.")
+        // app settings
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        // subcommands
+        .subcommand(add::command())
+        .subcommand(list::command())
+        .subcommand(skip::command())
+        .subcommand(remove::command())
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("add", Some(submatches)) => add::run(budget, config, submatches),
+        ("list", Some(submatches)) => list::run(budget, config, submatches),
+        ("skip", Some(submatches)) => skip::run(budget, config, submatches),
+        ("remove", Some(submatches)) => remove::run(budget, submatches),
+        // If no subcommand is found, assume 'list'
+        (_, None) => list::run(budget, config, matches),
+        _ => panic!("something is missing!"),
+    }
+}
+
+/// Returns the short id of a scheduled transaction, as shown by 'budget schedule list'.
+fn short_id(uuid: &Uuid) -> String {
+    uuid.to_string()[..8].to_string()
+}
+
+/// Find the scheduled transaction with the given id, which may be shortened.
+fn find(budget: &Budget, id: &str) -> Uuid {
+    if id.is_empty() {
+        eprintln!("the id of a scheduled transaction can't be empty");
+        shell::exit(1)
+    }
+    let matching: Vec<&Uuid> = budget
+        .schedules()
+        .iter()
+        .map(|s| s.uuid())
+        .filter(|u| u.to_string().starts_with(&id.to_lowercase()))
+        .collect();
+
+    match matching.as_slice() {
+        [uuid] => **uuid,
+        [] => {
+            eprintln!("no scheduled transaction with id '{}'", id);
+            shell::exit(1)
+        }
+        _ => {
+            eprintln!(
+                "more than one scheduled transaction has an id starting '{}'",
+                id
+            );
+            shell::exit(1)
+        }
+    }
+}
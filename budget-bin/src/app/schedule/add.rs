@@ -0,0 +1,204 @@
+use crate::app::category;
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::shell;
+use budget_lib::{Budget, Recurrence, ScheduledTransaction, Transaction};
+use chrono::{Datelike, NaiveDate};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("add")
+        .about("Adds a transaction which recurs on a schedule.")
+        // app settings
+        .setting(AppSettings::TrailingVarArg)
+        .setting(AppSettings::AllowNegativeNumbers)
+        // arguments
+        .arg(
+            Arg::with_name("amount")
+                .takes_value(true)
+                .help("The amount of each transaction")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("every")
+                .long("every")
+                .short("e")
+                .takes_value(true)
+                .default_value("month")
+                .help("How often the transaction recurs, such as 'week', '2 weeks' or '3 months'"),
+        )
+        .arg(
+            Arg::with_name("day")
+                .long("day")
+                .takes_value(true)
+                .conflicts_with("last business day")
+                .help(
+                    "The day of the month for monthly transactions [default: the day of --start]",
+                ),
+        )
+        .arg(
+            Arg::with_name("last business day")
+                .long("last-business-day")
+                .help("Monthly transactions happen on the last weekday of the month"),
+        )
+        .arg(
+            Arg::with_name("start")
+                .long("start")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help("The date of the first transaction [default: today]"),
+        )
+        .arg(
+            Arg::with_name("until")
+                .long("until")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .conflicts_with("count")
+                .help("The last date that the transaction may happen on"),
+        )
+        .arg(
+            Arg::with_name("count")
+                .long("count")
+                .takes_value(true)
+                .help("The number of times the transaction happens"),
+        )
+        .arg(
+            Arg::with_name("account")
+                .long("account")
+                .short("a")
+                .takes_value(true)
+                .help("The account of the transaction [default: the configured default_account]"),
+        )
+        .arg(
+            Arg::with_name("category")
+                .long("category")
+                .short("c")
+                .takes_value(true)
+                .help("The category of the transaction"),
+        )
+        .arg(category::create_arg())
+        .arg(
+            Arg::with_name("payee")
+                .long("payee")
+                .short("p")
+                .takes_value(true)
+                .help("Who the transaction is with"),
+        )
+        .arg(Arg::with_name("description").multiple(true))
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let account = matches.account_or_default("account", budget, config);
+    let account = account.as_deref();
+
+    let amount = matches
+        .amount_of("amount", budget.account_currency(account), config)
+        .unwrap();
+    let today = chrono::Local::now().date_naive();
+    let start: NaiveDate = matches.date_of("start", config).unwrap_or(today);
+
+    let recurrence = recurrence(matches, start).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        shell::exit(1)
+    });
+
+    let mut template = Transaction::new(amount)
+        .with_description(
+            matches
+                .values_of("description")
+                .map(|d| d.collect::<Vec<_>>().join(" ")),
+        )
+        .with_payee(matches.value_of("payee"));
+    template.set_account(account);
+    template.set_category(matches.new_category_of("category", budget));
+
+    let mut schedule = ScheduledTransaction::new(template, start, recurrence).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        shell::exit(1)
+    });
+    if let Some(until) = matches.date_of("until", config) {
+        schedule = schedule.with_until(until);
+    }
+    if let Some(count) = matches.typed_value_of("count", Some("a whole number")) {
+        schedule = schedule.with_count(count);
+    }
+
+    match schedule.next_date() {
+        Some(d) => println!(
+            "{}, starting {}",
+            recurrence,
+            d.format(config.display_date_format())
+        ),
+        None => println!("{}, but it has already ended", recurrence),
+    }
+    if let Err(e) = budget.add_schedule(schedule) {
+        eprintln!("{}", e);
+        shell::exit(1)
+    }
+
+    // the schedule may have started already
+    let added = budget.add_due_transactions(today);
+    if added > 0 {
+        println!("added {} transactions which have already fallen due", added);
+    }
+}
+
+fn recurrence(matches: &ArgMatches, start: NaiveDate) -> Result<Recurrence, String> {
+    let every = matches.value_of("every").unwrap();
+    let (n, unit) = match every.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [unit] => (1, *unit),
+        [n, unit] => match n.parse() {
+            Ok(n) if n > 0 => (n, *unit),
+            _ => return Err(format!("'{}' is not a whole number", n)),
+        },
+        _ => return Err(format!("'{}' is not a period, such as '2 weeks'", every)),
+    };
+
+    let monthly = |n| {
+        if matches.is_present("last business day") {
+            return Ok(Recurrence::LastBusinessDay(n));
+        }
+        let day = match matches.value_of("day") {
+            Some(d) => match d.parse() {
+                Ok(d) if (1..=31).contains(&d) => d,
+                _ => return Err(format!("'{}' is not a day of the month", d)),
+            },
+            None => start.day(),
+        };
+        Ok(Recurrence::Months { every: n, day })
+    };
+
+    let unit = unit.trim_end_matches('s');
+    // a longer period is surely a mistake, and soon runs past the last date there is
+    let most = match unit {
+        "day" => 36_525,
+        "week" => 5_218,
+        "month" => 1_200,
+        "year" => 100,
+        _ => u32::MAX,
+    };
+    if n > most {
+        return Err(format!("'{}' is more than 100 years", every));
+    }
+    if unit == "day" || unit == "week" {
+        for monthly in ["day", "last business day"] {
+            if matches.is_present(monthly) {
+                return Err(format!(
+                    "--{} is only for schedules which recur every month or year",
+                    monthly.replace(' ', "-")
+                ));
+            }
+        }
+    }
+
+    match unit {
+        "day" => Ok(Recurrence::Days(n)),
+        "week" => Ok(Recurrence::Weeks(n)),
+        "month" => monthly(n),
+        "year" => monthly(n * 12),
+        _ => Err(format!(
+            "'{}' is not a period. use days, weeks, months or years",
+            unit
+        )),
+    }
+}
@@ -0,0 +1,41 @@
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::Budget;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("list")
+    // app settings
+
+    // subcommands
+}
+
+pub fn run(budget: &mut Budget, config: &Config, _matches: &ArgMatches) {
+    let mut table = Table::new(vec![
+        "id",
+        "next",
+        "amount",
+        "schedule",
+        "category",
+        "payee",
+        "description",
+    ])
+    .align(2, Align::Right);
+
+    for s in budget.schedules() {
+        let t = s.template();
+        table.push(vec![
+            super::short_id(s.uuid()),
+            s.next_date()
+                .map(|d| d.format(config.display_date_format()).to_string())
+                .unwrap_or_else(|| "ended".to_string()),
+            config.format_amount(*t.amount(), budget.account_currency(t.account().as_deref())),
+            s.recurrence().to_string(),
+            budget.category_name(t).unwrap_or_default(),
+            t.payee().clone().unwrap_or_default(),
+            t.description().clone().unwrap_or_default(),
+        ]);
+    }
+
+    print!("{}", table);
+}
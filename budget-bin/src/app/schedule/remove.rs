@@ -0,0 +1,22 @@
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("remove")
+        .about(
+            "Removes a scheduled transaction. Transactions which have already happened are kept.",
+        )
+        // arguments
+        .arg(
+            Arg::with_name("id")
+                .takes_value(true)
+                .help("The id of the scheduled transaction, as shown by 'budget schedule list'")
+                .required(true),
+        )
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let uuid = super::find(budget, matches.value_of("id").unwrap());
+    budget.remove_schedule(&uuid);
+    log::info!("removed scheduled transaction {}", uuid);
+}
@@ -0,0 +1,23 @@
+use crate::config::Config;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("skip")
+        .about("Skips the next occurrence of a scheduled transaction.")
+        // arguments
+        .arg(
+            Arg::with_name("id")
+                .takes_value(true)
+                .help("The id of the scheduled transaction, as shown by 'budget schedule list'")
+                .required(true),
+        )
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let uuid = super::find(budget, matches.value_of("id").unwrap());
+    match budget.skip_schedule(&uuid) {
+        Some(date) => println!("skipped {}", date.format(config.display_date_format())),
+        None => println!("the schedule has ended"),
+    }
+}
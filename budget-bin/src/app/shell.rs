@@ -0,0 +1,13 @@
+use clap::{App, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("shell")
+        .about("Runs commands one after another, loading the budget only once.")
+        .after_help(
+            "Commands take the same arguments as they do on the command line, such as \
+             'summary' or 'category add food'. Press tab to complete the names of commands, \
+             categories, payees and accounts. 'save' saves the budget, 'exit' saves it and \
+             leaves, and 'quit!' leaves without saving. The prompt shows a '*' while there are \
+             unsaved changes.",
+        )
+}
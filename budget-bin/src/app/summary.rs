@@ -0,0 +1,131 @@
+use crate::app::category::goal;
+use crate::app::AppTools;
+use crate::chart::{self, Charset};
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::{Budget, CalendarMonth, Currency, GoalKind, Grouping, SpendingReport};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use decimal::d128;
+
+/// The number of months shown in the spending trend of each category.
+const TREND_MONTHS: usize = 12;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("summary")
+        .about("Summarises the spending in each category for a month, and the progress of goals.")
+        // arguments
+        .arg(
+            Arg::with_name("month")
+                .long("month")
+                .short("m")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help(
+                    "The month to summarise, such as 2026-03 or 'last month' [default: this month]",
+                ),
+        )
+}
+
+pub fn run(budget: &Budget, config: &Config, matches: &ArgMatches) {
+    let month: CalendarMonth = matches
+        .month_of("month", config)
+        .unwrap_or_else(|| budget.month_of(chrono::Local::now()));
+    let from = (1..TREND_MONTHS).fold(month, |m, _| m.pred());
+    let spending = SpendingReport::new(budget, from, month, Grouping::Category);
+    let charset = Charset::detect();
+
+    let groups = budget.grouped_categories();
+    // only show the groups if the budget has any
+    let headings = budget.master_categories().next().is_some();
+
+    let mut table = Table::new(vec![
+        "category", "spent", "trend", "goal", "needed", "progress",
+    ])
+    .align(1, Align::Right)
+    .align(4, Align::Right)
+    .align(5, Align::Right);
+    let by_month = |category: &str| {
+        spending
+            .rows()
+            .iter()
+            .find(|r| r.group() == category)
+            .map(|r| r.by_month().to_vec())
+            .unwrap_or_else(|| vec![d128::zero(); TREND_MONTHS])
+    };
+    for (master, categories) in groups {
+        // hidden categories are left out, along with groups of only hidden categories
+        let visible: Vec<_> = categories.iter().filter(|c| !c.hidden()).collect();
+        if visible.is_empty() && !categories.is_empty() {
+            continue;
+        }
+        if headings {
+            // subcategories are already counted in their parents
+            let mut total = vec![d128::zero(); TREND_MONTHS];
+            for c in visible.iter().filter(|c| budget.category_depth(c) == 0) {
+                for (t, x) in total.iter_mut().zip(by_month(&budget.category_path(c))) {
+                    *t += x;
+                }
+            }
+            table.push(vec![
+                master.map_or("(no group)", |m| m.name()).to_string(),
+                config.format_amount(total[TREND_MONTHS - 1], budget.currency()),
+                chart::sparkline(&trend(&total), charset),
+            ]);
+        }
+
+        for category in visible {
+            let path = budget.category_path(category);
+            let indent = budget.category_depth(category) + usize::from(headings);
+            let label = format!("{}{}", "  ".repeat(indent), category.name());
+            table.push(row(
+                budget,
+                config,
+                charset,
+                label,
+                &path,
+                &by_month(&path),
+                month,
+            ));
+        }
+    }
+
+    print!("{}", table);
+    println!(
+        "\navailable to assign: {}",
+        config.format_amount(budget.available(month), budget.currency())
+    );
+}
+
+fn trend(by_month: &[d128]) -> Vec<f64> {
+    by_month.iter().map(|x| chart::to_f64(*x)).collect()
+}
+
+/// The row of the summary for a category, with the label to show for it.
+fn row(
+    budget: &Budget,
+    config: &Config,
+    charset: Charset,
+    label: String,
+    category: &str,
+    by_month: &[d128],
+    month: CalendarMonth,
+) -> Vec<String> {
+    let mut record = vec![
+        label,
+        config.format_amount(by_month[TREND_MONTHS - 1], budget.currency()),
+        chart::sparkline(&trend(by_month), charset),
+    ];
+    match (budget.goal(category), budget.goal_status(category, month)) {
+        (Some(g), Some(status)) => {
+            let needed = match g.kind() {
+                GoalKind::SpendingCap { .. } => String::new(),
+                _ => config.format_amount(status.needed, budget.currency()),
+            };
+            record.push(goal::describe(g, budget, config));
+            record.push(needed);
+            record.push(format!("{}%", status.percent().format(0)));
+        }
+        _ => record.extend(vec![String::new(); 3]),
+    }
+    record
+}
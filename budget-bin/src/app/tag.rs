@@ -0,0 +1,33 @@
+use crate::config::Config;
+use budget_lib::Budget;
+use clap::{App, AppSettings, ArgMatches, SubCommand};
+
+mod delete;
+mod list;
+mod rename;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("tag")
+        .about("Manages the tags of transactions.")
+        .long_about(
+            "Manages the tags of transactions. Tags can be nested with a '/', such as \
+             'holiday/2026', and a transaction with a subtag has its parent tags too.",
+        )
+        // app settings
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        // subcommands
+        .subcommand(list::command())
+        .subcommand(rename::command())
+        .subcommand(delete::command())
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    match matches.subcommand() {
+        ("list", Some(submatches)) => list::run(budget, config, submatches),
+        ("rename", Some(submatches)) => rename::run(budget, submatches),
+        ("delete", Some(submatches)) => delete::run(budget, submatches),
+        // If no subcommand is found, assume 'list'
+        (_, None) => list::run(budget, config, matches),
+        _ => panic!("something is missing!"),
+    }
+}
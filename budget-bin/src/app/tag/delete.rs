@@ -0,0 +1,14 @@
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("delete")
+        .about("Removes a tag, and its subtags, from every transaction, schedule and rule.")
+        .arg(Arg::with_name("name").required(true).takes_value(true))
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let name = matches.value_of("name").unwrap();
+    let count = budget.delete_tag(name);
+    println!("removed '{}' from {} transactions", name, count);
+}
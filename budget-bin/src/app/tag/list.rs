@@ -0,0 +1,44 @@
+use crate::app::report;
+use crate::config::Config;
+use crate::table::{Align, Table};
+use budget_lib::Budget;
+use clap::{App, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("list")
+        .about("Lists the tags, with the number, total and latest date of their transactions.")
+    // app settings
+
+    // subcommands
+}
+
+pub fn run(budget: &mut Budget, config: &Config, _matches: &ArgMatches) {
+    let mut table = Table::new(vec!["tag", "transactions", "total", "last"])
+        .align(1, Align::Right)
+        .align(2, Align::Right);
+
+    for stats in budget.tag_stats() {
+        // subtags are shown by name under their parents
+        let depth = stats.tag.matches('/').count();
+        let name = stats.tag.rsplit('/').next().unwrap_or_default();
+        table.push(vec![
+            format!("{}{}", "  ".repeat(depth), name),
+            stats.count.to_string(),
+            config.format_amount(stats.total, budget.currency()),
+            stats
+                .last
+                .map(|d| d.format(config.display_date_format()).to_string())
+                .unwrap_or_default(),
+        ]);
+    }
+
+    print!("{}", table);
+
+    // a transaction with several tags is left out of several totals, but counted once
+    let unconverted = budget
+        .ledger()
+        .into_iter()
+        .filter(|t| t.tags().next().is_some() && budget.to_budget_currency(t).is_none())
+        .count();
+    report::warn_unconverted(unconverted, "transactions", "the totals");
+}
@@ -0,0 +1,22 @@
+use crate::shell;
+use budget_lib::Budget;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("rename")
+        .about("Renames a tag, and its subtags, in every transaction, schedule and rule.")
+        .arg(Arg::with_name("old name").required(true).takes_value(true))
+        .arg(Arg::with_name("new name").required(true).takes_value(true))
+}
+
+pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+    let old = matches.value_of("old name").unwrap();
+    let new = matches.value_of("new name").unwrap();
+    match budget.rename_tag(old, new) {
+        Ok(count) => println!("renamed '{}' to '{}' in {} transactions", old, new, count),
+        Err(e) => {
+            eprintln!("can't rename '{}' to '{}': {}", old, new, e);
+            shell::exit(1);
+        }
+    }
+}
@@ -1,7 +1,9 @@
+use crate::config::Config;
 use budget_lib::Budget;
 use clap::{App, ArgMatches, SubCommand};
 
 mod add;
+mod import;
 pub mod list;
 
 pub fn command<'a, 'b>() -> App<'a, 'b> {
@@ -10,14 +12,16 @@ pub fn command<'a, 'b>() -> App<'a, 'b> {
         // subcommands
         .subcommand(add::command())
         .subcommand(list::command())
+        .subcommand(import::command())
 }
 
-pub fn run(budget: &mut Budget, matches: &ArgMatches) {
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
     match matches.subcommand() {
-        ("add", Some(submatches)) => add::run(budget, submatches),
-        ("list", Some(submatches)) => list::run(budget, submatches),
+        ("add", Some(submatches)) => add::run(budget, config, submatches),
+        ("list", Some(submatches)) => list::run(budget, config, submatches),
+        ("import", Some(submatches)) => import::run(budget, config, submatches),
         // If no subcommand is found, assume 'list'
-        (_, None) => list::run(budget, matches),
+        (_, None) => list::run(budget, config, matches),
         _ => panic!("something is missing!"),
     }
 }
@@ -1,12 +1,16 @@
+use crate::app::category;
 use crate::app::AppTools;
+use crate::config::Config;
+use crate::dates;
+use crate::shell;
 use budget_lib::{Budget, Transaction};
 use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
-use decimal::d128;
 
 pub fn command<'a, 'b>() -> App<'a, 'b> {
     SubCommand::with_name("add")
-        .setting(AppSettings::TrailingVarArg)
         // app settings
+        .setting(AppSettings::TrailingVarArg)
+        .setting(AppSettings::AllowNegativeNumbers)
         // arguments
         .arg(
             Arg::with_name("amount")
@@ -14,20 +18,81 @@ pub fn command<'a, 'b>() -> App<'a, 'b> {
                 .help("The amount of the transaction")
                 .required(true),
         )
+        .arg(
+            Arg::with_name("account")
+                .long("account")
+                .short("a")
+                .takes_value(true)
+                .help("The account of the transaction [default: the configured default_account]"),
+        )
+        .arg(
+            Arg::with_name("category")
+                .long("category")
+                .short("c")
+                .takes_value(true)
+                .help("The category of the transaction [default: the payee's category]"),
+        )
+        .arg(category::create_arg())
+        .arg(
+            Arg::with_name("payee")
+                .long("payee")
+                .short("p")
+                .takes_value(true)
+                .help("Who the transaction was with"),
+        )
+        .arg(
+            Arg::with_name("date")
+                .long("date")
+                .short("d")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help(
+                    "The date of the transaction, such as 2026-03-15, yesterday, -3d or \
+                     'last friday' [default: today]",
+                ),
+        )
+        .arg(
+            Arg::with_name("tag")
+                .long("tag")
+                .short("t")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help("Tag the transaction, such as holiday/2026. May be given more than once"),
+        )
         .arg(Arg::with_name("description").multiple(true))
 }
 
-pub fn run(budget: &mut Budget, matches: &ArgMatches) {
-    let amount: d128 = matches.typed_value_of("amount", None).unwrap();
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let account = matches.account_or_default("account", budget, config);
+    let account = account.as_deref();
+
+    let amount = matches
+        .amount_of("amount", budget.account_currency(account), config)
+        .unwrap();
     let description = matches.values_of("description").map(collect_sentence);
 
-    budget.add(Transaction::new(amount).with_description(description))
+    let mut t = Transaction::new(amount)
+        .with_description(description)
+        .with_payee(matches.new_payee_of("payee", budget));
+    t.set_account(account);
+    t.set_category(matches.new_category_of("category", budget));
+    for tag in matches.values_of("tag").into_iter().flatten() {
+        t.tag(tag);
+    }
+    if let Some(date) = matches.date_of("date", config) {
+        t.set_date_transaction(Some(dates::midday(date)));
+    }
+    if let Err(e) = budget.add_entry(t) {
+        eprintln!("{}", e);
+        shell::exit(1)
+    }
 }
 
 fn collect_sentence<'a>(mut tokens: impl Iterator<Item = &'a str>) -> String {
     let mut sentence = "".to_string();
     if let Some(x) = tokens.next() {
-        sentence.push_str(&x);
+        sentence.push_str(x);
     }
     for token in tokens {
         sentence += &format!(" {}", token)
@@ -0,0 +1,120 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::dates;
+use crate::shell;
+use budget_lib::{Budget, CurrencyCode, Money, Transaction};
+use chrono::NaiveDate;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use decimal::d128;
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("import")
+        .about("Imports transactions from a CSV file, and categorises them with the rules.")
+        .long_about(
+            "Imports transactions from a CSV file, and categorises them with the rules.\n\n\
+             Each row has the columns 'date,amount,payee,description', such as \
+             '2026-01-31,-42.10,TESCO STORES,card payment'. The payee and description may be \
+             empty. A header row is optional.",
+        )
+        // app settings
+        // arguments
+        .arg(
+            Arg::with_name("path")
+                .takes_value(true)
+                .help("The CSV file to import")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("account")
+                .long("account")
+                .short("a")
+                .takes_value(true)
+                .help("The account of the transactions [default: the configured default_account]"),
+        )
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let account = matches.account_or_default("account", budget, config);
+    let account = account.as_deref();
+    let currency = budget.account_currency(account);
+
+    let path = matches.value_of("path").unwrap();
+    let mut reader = match csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_path(path)
+    {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("unable to read '{}': {}", path, e);
+            shell::exit(1)
+        }
+    };
+
+    let mut transactions = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        let record = record.unwrap_or_else(|e| {
+            eprintln!("unable to read '{}': {}", path, e);
+            shell::exit(1)
+        });
+
+        match parse_record(&record, currency, config) {
+            Some(mut t) => {
+                t.set_account(account);
+                transactions.push(t);
+            }
+            // the first row may be a header
+            None if i == 0 => continue,
+            None => {
+                eprintln!(
+                    "line {}: expected 'date,amount,payee,description', such as \
+                     '2026-01-31,-42.10,TESCO STORES,card payment'",
+                    i + 1
+                );
+                shell::exit(1)
+            }
+        }
+    }
+
+    let rows = transactions.len();
+    let count = budget.import_all(transactions).unwrap_or_else(|e| {
+        eprintln!(
+            "can't import the transactions: {}. create it with 'budget category add', or \
+             change the rule or payee which uses it",
+            e
+        );
+        shell::exit(1)
+    });
+    log::info!("imported {} transactions from {}", count, path);
+    if count < rows {
+        println!(
+            "imported {} transactions. {} were already in the budget",
+            count,
+            rows - count
+        );
+    } else {
+        println!("imported {} transactions", count);
+    }
+}
+
+fn parse_record(
+    record: &csv::StringRecord,
+    currency: CurrencyCode,
+    config: &Config,
+) -> Option<Transaction<d128>> {
+    if record.len() < 2 || record.len() > 4 {
+        return None;
+    }
+
+    let date = record[0].parse::<NaiveDate>().ok()?;
+    let amount: Money<d128> = Money::parse(&record[1], currency, &config.locale()).ok()?;
+    let text = |i: usize| record.get(i).filter(|s| !s.is_empty());
+
+    Some(
+        Transaction::new(amount.amount())
+            .with_date_transaction(dates::midday(date))
+            .with_payee(text(2))
+            .with_description(text(3)),
+    )
+}
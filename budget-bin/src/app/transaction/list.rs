@@ -1,13 +1,52 @@
+use crate::config::Config;
+use crate::table::{Align, Table};
 use budget_lib::Budget;
-use clap::{App, ArgMatches, SubCommand};
+use clap::{App, Arg, ArgMatches, SubCommand};
 
 pub fn command<'a, 'b>() -> App<'a, 'b> {
-    SubCommand::with_name("list").about("Lists the transactions in the budget.")
-    // app settings
-
-    // subcommands
+    SubCommand::with_name("list")
+        .about("Lists the transactions in the budget.")
+        // arguments
+        .arg(
+            Arg::with_name("tag")
+                .long("tag")
+                .short("t")
+                .takes_value(true)
+                .help("Only list the transactions with a tag, or one of its subtags"),
+        )
 }
 
-pub fn run(budget: &mut Budget, _matches: &ArgMatches) {
-    println!("{:?}", budget.ledger());
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let tag = matches.value_of("tag");
+    let mut table = Table::new(vec![
+        "date",
+        "amount",
+        "account",
+        "category",
+        "payee",
+        "description",
+        "tags",
+    ])
+    .align(1, Align::Right);
+
+    for t in budget
+        .ledger()
+        .into_iter()
+        .filter(|t| tag.is_none_or(|tag| t.has_tag(tag)))
+    {
+        table.push(vec![
+            t.date()
+                .with_timezone(&chrono::Local)
+                .format(config.display_date_format())
+                .to_string(),
+            config.format_amount(*t.amount(), budget.account_currency(t.account().as_deref())),
+            t.account().clone().unwrap_or_default(),
+            budget.category_name(t).unwrap_or_default(),
+            t.payee().clone().unwrap_or_default(),
+            t.description().clone().unwrap_or_default(),
+            t.tags().cloned().collect::<Vec<_>>().join(" "),
+        ]);
+    }
+
+    print!("{}", table);
 }
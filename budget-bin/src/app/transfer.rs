@@ -1,7 +1,9 @@
+use crate::app::category;
 use crate::app::AppTools;
+use crate::config::Config;
+use crate::shell;
 use budget_lib::Budget;
 use clap::{App, Arg, ArgMatches, SubCommand};
-use decimal::d128;
 
 pub fn command<'a, 'b>() -> App<'a, 'b> {
     SubCommand::with_name("transfer")
@@ -25,17 +27,36 @@ pub fn command<'a, 'b>() -> App<'a, 'b> {
                 .help("The category to transfer funds into")
                 .required(true),
         )
+        .arg(
+            Arg::with_name("month")
+                .long("month")
+                .short("m")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help("The month to transfer the funds in, such as 2026-03 [default: this month]"),
+        )
+        .arg(category::create_arg())
 }
 
-pub fn run(budget: &mut Budget, matches: &ArgMatches) {
-    let amount: d128 = matches.typed_value_of("amount", None).unwrap();
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let amount = matches
+        .amount_of("amount", budget.currency(), config)
+        .unwrap();
+    let from = matches.new_category_of("from category", budget).unwrap();
+    let to = matches.new_category_of("to category", budget).unwrap();
     match budget.transfer(
         amount,
-        matches.value_of("to category").unwrap(),
-        matches.value_of("from category").unwrap(),
-        chrono::Local::today(),
+        to.as_str(),
+        from.as_str(),
+        matches.month_of("month", config).map_or_else(
+            || chrono::Local::now().date_naive(),
+            |m| m.start(budget.first_day_of_month()),
+        ),
     ) {
         Ok(_) => (),
-        Err(_) => println!("unable to transfer"),
+        Err(e) => {
+            eprintln!("unable to transfer: {}", e);
+            shell::exit(1)
+        }
     }
 }
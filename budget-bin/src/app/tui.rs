@@ -0,0 +1,38 @@
+use crate::app::AppTools;
+use crate::config::Config;
+use crate::shell;
+use crate::tui;
+use budget_lib::{Budget, CalendarMonth};
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("tui")
+        .about("Budgets a month at a time in a full-screen interface.")
+        .after_help(
+            "The budget pane shows each category for the month. Select a category and press \
+             enter to change the amount budgeted for it. The transactions pane lists the \
+             transactions in the month; press / to filter them, and enter to edit the selected \
+             cell. Use [ and ] to change the month, and q to save and quit.",
+        )
+        // arguments
+        .arg(
+            Arg::with_name("month")
+                .long("month")
+                .short("m")
+                .takes_value(true)
+                .allow_hyphen_values(true)
+                .help(
+                    "The month to start at, such as 2026-03 or 'last month' [default: this month]",
+                ),
+        )
+}
+
+pub fn run(budget: &mut Budget, config: &Config, matches: &ArgMatches) {
+    let month: CalendarMonth = matches
+        .month_of("month", config)
+        .unwrap_or_else(|| budget.month_of(chrono::Local::now()));
+    tui::run(budget, config, month).unwrap_or_else(|e| {
+        eprintln!("unable to run the interface: {}", e);
+        shell::exit(1)
+    });
+}
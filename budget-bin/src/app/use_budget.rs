@@ -0,0 +1,36 @@
+use crate::budgets::BudgetDirectory;
+use crate::config::Config;
+use crate::shell;
+use clap::{App, Arg, ArgMatches, SubCommand};
+
+pub fn command<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name("use")
+        .about("Sets the budget to use when no other is specified.")
+        // arguments
+        .arg(
+            Arg::with_name("name")
+                .takes_value(true)
+                .help("The name of the budget")
+                .required(true),
+        )
+}
+
+pub fn run(dir: &BudgetDirectory, config: &mut Config, matches: &ArgMatches) {
+    let name = matches.value_of("name").unwrap();
+    if !dir.exists(name) {
+        eprintln!("no budget named '{}'", name);
+        shell::exit(1)
+    }
+    if let Err(e) = config.set_default_budget(name) {
+        eprintln!("{}", e);
+        shell::exit(1)
+    }
+
+    match config.save() {
+        Ok(_) => log::info!("using budget: {}", name),
+        Err(e) => {
+            eprintln!("unable to save configuration: {}", e);
+            shell::exit(1)
+        }
+    }
+}
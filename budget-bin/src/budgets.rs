@@ -0,0 +1,232 @@
+//! The budget directory, which holds any number of named budgets.
+//!
+//! Budgets are stored as `<root>/budgets/<name>.budget`. The budget to use by
+//! default is set in the configuration, and is "default" if unset.
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// The name of the budget to use when none is configured.
+pub const DEFAULT_NAME: &str = "default";
+const EXTENSION: &str = "budget";
+
+/// Environment variable which may hold the path of the budget file to use.
+pub const BUDGET_FILE_VAR: &str = "BUDGET_FILE";
+
+pub struct BudgetDirectory {
+    root: PathBuf,
+}
+
+impl BudgetDirectory {
+    /// Open the budget directory, creating it if required.
+    ///
+    /// A budget saved by an earlier version at `<root>/budget` is moved to
+    /// become the "default" budget.
+    pub fn open<P: Into<PathBuf>>(root: P) -> io::Result<Self> {
+        let dir = BudgetDirectory { root: root.into() };
+        fs::DirBuilder::new()
+            .recursive(true)
+            .create(dir.budgets())?;
+
+        let legacy = dir.root.join("budget");
+        let default = dir.path_of(DEFAULT_NAME);
+        if legacy.is_file() && !default.exists() {
+            fs::rename(&legacy, &default)?;
+        }
+
+        Ok(dir)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn budgets(&self) -> PathBuf {
+        self.root.join("budgets")
+    }
+
+    /// Returns the path of the named budget. The budget may not exist.
+    pub fn path_of(&self, name: &str) -> PathBuf {
+        self.budgets().join(name).with_extension(EXTENSION)
+    }
+
+    /// Returns the names of all the budgets in the directory, sorted alphabetically.
+    pub fn list(&self) -> io::Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(self.budgets())? {
+            let path = entry?.path();
+            if path.extension() == Some(EXTENSION.as_ref()) {
+                if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.path_of(name).is_file()
+    }
+
+    /// Create the empty file of a new budget with the given name, checking that the
+    /// name is valid and not already in use, and return its path.
+    ///
+    /// The file is created only if it doesn't exist, so that two processes can't both
+    /// create the same budget.
+    pub fn new_budget(&self, name: &str) -> io::Result<PathBuf> {
+        validate_name(name)?;
+        let path = self.path_of(name);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(_) => Ok(path),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => Err(io::Error::new(
+                ErrorKind::AlreadyExists,
+                format!("a budget named '{}' already exists", name),
+            )),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Determine which budget file to use.
+    ///
+    /// In order of preference, this is the given file, the given budget name,
+    /// the file named by `BUDGET_FILE`, or the default budget.
+    pub fn select(
+        &self,
+        file: Option<&str>,
+        name: Option<&str>,
+        default: &str,
+    ) -> io::Result<PathBuf> {
+        self.select_from(file, name, std::env::var_os(BUDGET_FILE_VAR), default)
+    }
+
+    /// Determine which budget file to use, given the value of `BUDGET_FILE`.
+    fn select_from(
+        &self,
+        file: Option<&str>,
+        name: Option<&str>,
+        env_file: Option<std::ffi::OsString>,
+        default: &str,
+    ) -> io::Result<PathBuf> {
+        if let Some(f) = file {
+            return Ok(f.into());
+        }
+        if let Some(n) = name {
+            validate_name(n)?;
+            if !self.exists(n) {
+                return Err(io::Error::new(
+                    ErrorKind::NotFound,
+                    format!(
+                        "no budget named '{}'. use 'budget new {}' to create it",
+                        n, n
+                    ),
+                ));
+            }
+            return Ok(self.path_of(n));
+        }
+        if let Some(f) = env_file {
+            return Ok(f.into());
+        }
+        // the default is read from the configuration, which may have been edited by hand
+        validate_name(default)?;
+        Ok(self.path_of(default))
+    }
+}
+
+/// Check that a budget name is only letters, numbers, '-' and '_', so that it
+/// can't name a file outside the budget directory.
+pub fn validate_name(name: &str) -> io::Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "invalid budget name '{}'. names may only contain letters, numbers, '-' and '_'",
+                name
+            ),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A budget directory in a new temporary directory.
+    fn directory(test: &str) -> BudgetDirectory {
+        let root = std::env::temp_dir().join(format!("budget-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        BudgetDirectory::open(root).unwrap()
+    }
+
+    #[test]
+    fn names() {
+        assert!(validate_name("household_2026").is_ok());
+        assert!(validate_name("joint-account").is_ok());
+        for name in &["", "../escape", "a/b", "with space", "dot.ted"] {
+            assert!(validate_name(name).is_err(), "'{}' should be invalid", name);
+        }
+    }
+
+    #[test]
+    fn selection_precedence() {
+        let dir = directory("select");
+        dir.new_budget("named").unwrap();
+        let env = || Some("from-env.budget".into());
+        let select = |file, name, env| dir.select_from(file, name, env, DEFAULT_NAME).unwrap();
+
+        assert_eq!(
+            select(Some("given.budget"), Some("named"), env()),
+            PathBuf::from("given.budget")
+        );
+        assert_eq!(select(None, Some("named"), env()), dir.path_of("named"));
+        assert_eq!(select(None, None, env()), PathBuf::from("from-env.budget"));
+        assert_eq!(select(None, None, None), dir.path_of(DEFAULT_NAME));
+        assert!(dir
+            .select_from(None, Some("missing"), None, DEFAULT_NAME)
+            .is_err());
+        assert!(dir.select_from(None, None, None, "../escape").is_err());
+
+        fs::remove_dir_all(dir.root()).unwrap();
+    }
+
+    #[test]
+    fn creating_budgets() {
+        let dir = directory("create");
+        assert_eq!(dir.new_budget("home").unwrap(), dir.path_of("home"));
+        assert!(dir.exists("home"));
+        let e = dir.new_budget("home").unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::AlreadyExists);
+        assert_eq!(dir.list().unwrap(), vec!["home"]);
+
+        fs::remove_dir_all(dir.root()).unwrap();
+    }
+
+    #[test]
+    fn legacy_budget() {
+        let root = std::env::temp_dir().join(format!("budget-legacy-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("budget"), "transactions: []").unwrap();
+
+        let dir = BudgetDirectory::open(&root).unwrap();
+        assert!(!root.join("budget").exists());
+        assert_eq!(
+            fs::read_to_string(dir.path_of(DEFAULT_NAME)).unwrap(),
+            "transactions: []"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+//! Plain text charts for printing to the terminal.
+//!
+//! Charts are drawn with Unicode block characters, or with ASCII on terminals
+//! which don't support Unicode.
+
+use decimal::d128;
+use std::fmt::Write;
+
+/// The characters that charts are drawn with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Charset {
+    Unicode,
+    Ascii,
+}
+
+impl Charset {
+    /// Choose the characters supported by the terminal, judging by the locale.
+    pub fn detect() -> Self {
+        let utf8 = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|v| std::env::var(v).ok())
+            .find(|v| !v.is_empty())
+            .map(|v| {
+                let v = v.to_lowercase();
+                v.contains("utf-8") || v.contains("utf8")
+            });
+        let dumb = std::env::var("TERM").map(|t| t == "dumb").unwrap_or(false);
+
+        match utf8 {
+            Some(true) if !dumb => Charset::Unicode,
+            _ => Charset::Ascii,
+        }
+    }
+
+    /// The characters for increasing levels in a sparkline.
+    fn levels(self) -> &'static [char] {
+        match self {
+            Charset::Unicode => &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+            Charset::Ascii => &['_', '.', ':', '-', '=', '+', '*', '#'],
+        }
+    }
+
+    /// The characters used to fill the segments of a stacked bar.
+    fn fills(self) -> &'static [char] {
+        match self {
+            Charset::Unicode => &['█', '▓', '▒', '░', '▚', '▞', '▌', '▐'],
+            Charset::Ascii => &['#', '=', '%', '+', '@', 'o', '*', ':'],
+        }
+    }
+
+    fn point(self) -> char {
+        match self {
+            Charset::Unicode => '●',
+            Charset::Ascii => '*',
+        }
+    }
+
+    fn vertical(self) -> char {
+        match self {
+            Charset::Unicode => '│',
+            Charset::Ascii => '|',
+        }
+    }
+}
+
+/// Convert an amount to a float, for scaling it onto a chart.
+pub fn to_f64(x: d128) -> f64 {
+    x.to_string().parse().unwrap_or(0.0)
+}
+
+/// Draw a sparkline, with one character for each value.
+pub fn sparkline(values: &[f64], charset: Charset) -> String {
+    let levels = charset.levels();
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+    values
+        .iter()
+        .map(|v| {
+            if max > min {
+                let level = (v - min) / (max - min) * (levels.len() - 1) as f64;
+                levels[level.round() as usize]
+            } else {
+                levels[0]
+            }
+        })
+        .collect()
+}
+
+/// Draw a horizontal bar for each label, made up of a segment for each series.
+///
+/// Values should be positive. The longest bar is `width` characters. A legend
+/// is drawn below the bars. Series after the eighth share the last fill.
+pub fn stacked_bars(
+    labels: &[String],
+    series: &[(String, Vec<f64>)],
+    width: usize,
+    charset: Charset,
+) -> String {
+    let fills = charset.fills();
+    let fill = |i: usize| fills[i.min(fills.len() - 1)];
+    let totals: Vec<f64> = (0..labels.len())
+        .map(|i| series.iter().map(|(_, v)| v[i].max(0.0)).sum())
+        .collect();
+    let max = totals.iter().cloned().fold(0.0, f64::max);
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+    let mut chart = String::new();
+    for (i, label) in labels.iter().enumerate() {
+        let mut bar = String::new();
+        let mut drawn = 0;
+        let mut sum = 0.0;
+        for (j, (_, values)) in series.iter().enumerate() {
+            // scale the running total, so that rounding doesn't add up along the bar
+            sum += values[i].max(0.0);
+            let end = if max > 0.0 {
+                (sum / max * width as f64).round() as usize
+            } else {
+                0
+            };
+            for _ in drawn..end {
+                bar.push(fill(j));
+            }
+            drawn = drawn.max(end);
+        }
+        writeln!(chart, "{:<w$}  {}", label, bar, w = label_width).unwrap();
+    }
+
+    chart.push('\n');
+    for (j, (name, _)) in series.iter().enumerate() {
+        writeln!(chart, "{} {}", fill(j), name).unwrap();
+    }
+    chart
+}
+
+/// Draw a line chart of a series of values, with a column for each label.
+///
+/// The chart is `height` rows tall, with the highest and lowest values marked
+/// on the vertical axis using `format`.
+pub fn line_chart(
+    labels: &[String],
+    values: &[f64],
+    height: usize,
+    format: impl Fn(f64) -> String,
+    charset: Charset,
+) -> String {
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let row_of = |v: f64| {
+        if max > min {
+            ((v - min) / (max - min) * (height - 1) as f64).round() as usize
+        } else {
+            0
+        }
+    };
+
+    // each label is centred in a column wide enough for the longest one
+    let column = labels.iter().map(|l| l.chars().count()).max().unwrap_or(1) + 1;
+    let (top, bottom) = (format(max), format(min));
+    let axis_width = top.chars().count().max(bottom.chars().count());
+
+    let mut grid = vec![vec![' '; column * values.len()]; height];
+    let mut previous: Option<usize> = None;
+    for (i, v) in values.iter().enumerate() {
+        let row = row_of(*v);
+        let x = i * column + column / 2;
+        // join up with the previous point, so that steep changes are still a line
+        if let Some(p) = previous {
+            let (low, high) = (p.min(row), p.max(row));
+            for line in &mut grid[(low + 1)..high] {
+                line[x] = charset.vertical();
+            }
+        }
+        grid[row][x] = charset.point();
+        previous = Some(row);
+    }
+
+    let mut chart = String::new();
+    for (r, line) in grid.iter().enumerate().rev() {
+        let axis = if r == height - 1 {
+            top.as_str()
+        } else if r == 0 {
+            bottom.as_str()
+        } else {
+            ""
+        };
+        let line: String = line.iter().collect();
+        writeln!(
+            chart,
+            "{:>w$} {} {}",
+            axis,
+            charset.vertical(),
+            line.trim_end(),
+            w = axis_width
+        )
+        .unwrap();
+    }
+
+    let mut footer = String::new();
+    for label in labels {
+        write!(footer, "{:^w$}", label, w = column).unwrap();
+    }
+    writeln!(chart, "{:>w$}   {}", "", footer.trim_end(), w = axis_width).unwrap();
+    chart
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparklines() {
+        assert_eq!(sparkline(&[0.0, 3.5, 7.0], Charset::Unicode), "▁▅█");
+        assert_eq!(sparkline(&[0.0, 3.5, 7.0], Charset::Ascii), "_=#");
+        assert_eq!(sparkline(&[2.0, 2.0], Charset::Ascii), "__");
+    }
+
+    #[test]
+    fn bars() {
+        let labels = vec!["jan".to_string(), "feb".to_string()];
+        let series = vec![
+            ("rent".to_string(), vec![5.0, 5.0]),
+            ("food".to_string(), vec![5.0, 0.0]),
+        ];
+        let chart = stacked_bars(&labels, &series, 10, Charset::Ascii);
+        assert_eq!(chart, "jan  #####=====\nfeb  #####\n\n# rent\n= food\n");
+    }
+}
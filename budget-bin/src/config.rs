@@ -0,0 +1,432 @@
+//! User configuration, stored as TOML.
+//!
+//! The configuration is read from `$XDG_CONFIG_HOME/budget/config.toml` if that
+//! file exists, and otherwise from `config.toml` in the budget directory. Every
+//! setting is optional, and a missing file is the same as an empty one.
+//!
+//! Settings are addressed by key, such as `display.currency`, for the
+//! `budget config` command.
+
+use crate::budgets;
+use crate::dates;
+use budget_lib::{Budget, CurrencyCode, Locale, Money, ParseCurrencyCodeError};
+use decimal::d128;
+use serde_derive::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "config.toml";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(toml::de::Error),
+    Serialise(toml::ser::Error),
+    UnknownKey(String),
+    InvalidValue { key: String, reason: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "IO error: {}", e),
+            ConfigError::Parse(e) => write!(f, "Parse error: {}", e),
+            ConfigError::Serialise(e) => write!(f, "Serialise error: {}", e),
+            ConfigError::UnknownKey(k) => write!(f, "unknown setting '{}'", k),
+            ConfigError::InvalidValue { key, reason } => {
+                write!(f, "invalid value for '{}': {}", key, reason)
+            }
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The budget to use when none is specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_budget: Option<String>,
+
+    /// The most verbose level of message written to the log file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    log_level: Option<String>,
+
+    /// The account assigned to new transactions when none is specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_account: Option<String>,
+
+    /// The categories that new budgets are created with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_categories: Option<Vec<String>>,
+
+    display: Display,
+    input: Input,
+    calendar: Calendar,
+
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Display {
+    /// The ISO 4217 code of the budget currency
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<CurrencyCode>,
+
+    /// The locale used for formatting amounts, such as 'en-GB'
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locale: Option<String>,
+
+    /// The strftime-style format used for displaying dates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date_format: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Input {
+    /// The strftime-style format accepted for dates, in addition to ISO 8601
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date_format: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Calendar {
+    /// The day that weeks start on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_day_of_week: Option<chrono::Weekday>,
+
+    /// The day of the month, from 1 to 28, that budget months start on. The
+    /// transactions and money assigned in each budget are grouped by these months.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_day_of_month: Option<u32>,
+}
+
+/// The keys of all the settings, in the order they are listed.
+pub const KEYS: &[&str] = &[
+    "default_budget",
+    "default_account",
+    "default_categories",
+    "log_level",
+    "display.currency",
+    "display.locale",
+    "display.date_format",
+    "input.date_format",
+    "calendar.first_day_of_week",
+    "calendar.first_day_of_month",
+];
+
+impl Config {
+    /// Returns the path to the configuration file.
+    ///
+    /// This is the XDG configuration file if it exists, and otherwise the file in
+    /// the budget directory.
+    pub fn path(budget_root: &Path) -> PathBuf {
+        dirs::config_dir()
+            .map(|d| d.join("budget").join(FILE_NAME))
+            .filter(|p| p.is_file())
+            .unwrap_or_else(|| budget_root.join(FILE_NAME))
+    }
+
+    /// Load the configuration from the given file. If the file doesn't exist, the
+    /// default configuration is returned.
+    pub fn load<P: Into<PathBuf>>(path: P) -> Result<Config, ConfigError> {
+        let path = path.into();
+        let mut config: Config = match std::fs::read_to_string(&path) {
+            Ok(s) => toml::from_str(&s).map_err(ConfigError::Parse)?,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Config::default(),
+            Err(e) => return Err(ConfigError::Io(e)),
+        };
+        config.path = path;
+        Ok(config)
+    }
+
+    pub fn save(&self) -> Result<(), ConfigError> {
+        let s = toml::to_string(self).map_err(ConfigError::Serialise)?;
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(ConfigError::Io)?;
+        }
+        std::fs::write(&self.path, s).map_err(ConfigError::Io)
+    }
+
+    pub fn file(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn default_budget(&self) -> &str {
+        self.default_budget
+            .as_deref()
+            .unwrap_or(crate::budgets::DEFAULT_NAME)
+    }
+
+    pub fn set_default_budget<S: Into<String>>(&mut self, name: S) -> Result<(), ConfigError> {
+        let name = name.into();
+        budgets::validate_name(&name).map_err(|e| ConfigError::InvalidValue {
+            key: "default_budget".to_string(),
+            reason: e.to_string(),
+        })?;
+        self.default_budget = Some(name);
+        Ok(())
+    }
+
+    pub fn log_level(&self) -> log::LevelFilter {
+        self.log_level
+            .as_ref()
+            .and_then(|l| l.parse().ok())
+            .unwrap_or(log::LevelFilter::Debug)
+    }
+
+    /// Returns the account assigned to new transactions when none is specified.
+    pub fn default_account(&self) -> Option<&str> {
+        self.default_account.as_deref()
+    }
+
+    /// Create a new budget, with the configured currency and default categories.
+    pub fn new_budget(&self) -> Budget {
+        let mut budget = match &self.default_categories {
+            Some(c) => Budget::with_categories(c),
+            None => Budget::default(),
+        };
+        budget.set_currency(self.currency());
+        budget.set_first_day_of_month(self.calendar().first_day_of_month);
+        budget
+    }
+
+    /// Returns the configured currency, or `CurrencyCode::NONE` if it isn't set.
+    pub fn currency(&self) -> CurrencyCode {
+        self.display.currency.unwrap_or_default()
+    }
+
+    /// Returns the locale used for formatting amounts. If this isn't set, the locale
+    /// is taken from the environment.
+    pub fn locale(&self) -> Locale {
+        match &self.display.locale {
+            Some(l) => Locale::new(l),
+            None => ["LC_ALL", "LC_MONETARY", "LANG"]
+                .iter()
+                .filter_map(|v| std::env::var(v).ok())
+                .find(|v| !v.is_empty())
+                .map(|v| Locale::new(&v))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Format an amount in the given currency, for display.
+    ///
+    /// Amounts without a currency, such as those in budgets created before
+    /// currencies were supported, are shown in the configured currency.
+    pub fn format_amount(&self, amount: d128, currency: CurrencyCode) -> String {
+        let currency = if currency == CurrencyCode::NONE {
+            self.currency()
+        } else {
+            currency
+        };
+        Money::new(amount, currency).format(&self.locale())
+    }
+
+    pub fn display_date_format(&self) -> &str {
+        self.display.date_format.as_deref().unwrap_or("%Y-%m-%d")
+    }
+
+    /// Returns the format that dates are entered in, as well as ISO 8601, if one is set.
+    pub fn input_date_format(&self) -> Option<&str> {
+        self.input.date_format.as_deref()
+    }
+
+    /// Returns how dates are entered: the input date format, and the days that
+    /// weeks and months start on.
+    pub fn calendar(&self) -> dates::Calendar<'_> {
+        let default = dates::Calendar::default();
+        dates::Calendar {
+            format: self.input_date_format(),
+            first_day_of_week: self
+                .calendar
+                .first_day_of_week
+                .unwrap_or(default.first_day_of_week),
+            // a day out of range can only have been written by hand, and is ignored
+            first_day_of_month: self
+                .calendar
+                .first_day_of_month
+                .filter(|d| (1..=28).contains(d))
+                .unwrap_or(default.first_day_of_month),
+        }
+    }
+
+    /// Returns the value of a setting, or None if it isn't set.
+    pub fn get(&self, key: &str) -> Result<Option<String>, ConfigError> {
+        let value = match key {
+            "default_budget" => self.default_budget.clone(),
+            "default_account" => self.default_account.clone(),
+            "default_categories" => self.default_categories.as_ref().map(|c| c.join(",")),
+            "log_level" => self.log_level.clone(),
+            "display.currency" => self.display.currency.map(String::from),
+            "display.locale" => self.display.locale.clone(),
+            "display.date_format" => self.display.date_format.clone(),
+            "input.date_format" => self.input.date_format.clone(),
+            "calendar.first_day_of_week" => self.calendar.first_day_of_week.map(|d| d.to_string()),
+            "calendar.first_day_of_month" => {
+                self.calendar.first_day_of_month.map(|d| d.to_string())
+            }
+            _ => return Err(ConfigError::UnknownKey(key.to_string())),
+        };
+        Ok(value)
+    }
+
+    /// Set the value of a setting. An empty value clears the setting.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let invalid = |reason: &str| ConfigError::InvalidValue {
+            key: key.to_string(),
+            reason: reason.to_string(),
+        };
+        let value = Some(value.trim()).filter(|v| !v.is_empty());
+
+        match key {
+            "default_budget" => match value {
+                Some(v) => self.set_default_budget(v)?,
+                None => self.default_budget = None,
+            },
+            "default_account" => self.default_account = value.map(Into::into),
+            "default_categories" => {
+                self.default_categories = value.map(|v| {
+                    v.split(',')
+                        .map(str::trim)
+                        .filter(|c| !c.is_empty())
+                        .map(Into::into)
+                        .collect()
+                })
+            }
+            "log_level" => {
+                if let Some(v) = value {
+                    v.parse::<log::LevelFilter>()
+                        .map_err(|_| invalid("expected off, error, warn, info, debug or trace"))?;
+                }
+                self.log_level = value.map(str::to_lowercase)
+            }
+            "display.currency" => {
+                self.display.currency = value
+                    .map(|v| {
+                        v.parse()
+                            .map_err(|e: ParseCurrencyCodeError| invalid(&e.to_string()))
+                    })
+                    .transpose()?
+            }
+            "display.locale" => self.display.locale = value.map(Into::into),
+            "display.date_format" => {
+                self.display.date_format = value
+                    .map(|v| validate_date_format(v).map_err(|r| invalid(&r)))
+                    .transpose()?
+            }
+            "input.date_format" => {
+                self.input.date_format = value
+                    .map(|v| validate_date_format(v).map_err(|r| invalid(&r)))
+                    .transpose()?
+            }
+            "calendar.first_day_of_week" => {
+                self.calendar.first_day_of_week = value
+                    .map(|v| {
+                        v.parse()
+                            .map_err(|_| invalid("expected a day, such as 'mon'"))
+                    })
+                    .transpose()?
+            }
+            "calendar.first_day_of_month" => {
+                self.calendar.first_day_of_month = value
+                    .map(|v| match v.parse() {
+                        Ok(d) if (1..=28).contains(&d) => Ok(d),
+                        _ => Err(invalid("expected a day between 1 and 28")),
+                    })
+                    .transpose()?
+            }
+            _ => return Err(ConfigError::UnknownKey(key.to_string())),
+        }
+        Ok(())
+    }
+}
+
+fn validate_date_format(format: &str) -> Result<String, String> {
+    use chrono::format::{Item, StrftimeItems};
+
+    if StrftimeItems::new(format).any(|i| i == Item::Error) {
+        Err(format!("'{}' is not a valid strftime format", format))
+    } else {
+        Ok(format.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setting_values() {
+        let mut config = Config::default();
+        for key in KEYS {
+            assert_eq!(config.get(key).unwrap(), None);
+        }
+
+        config.set("display.currency", "eur").unwrap();
+        assert_eq!(
+            config.get("display.currency").unwrap().as_deref(),
+            Some("EUR")
+        );
+        config.set("log_level", "DEBUG").unwrap();
+        assert_eq!(config.get("log_level").unwrap().as_deref(), Some("debug"));
+        config.set("default_categories", "food, rent,,").unwrap();
+        assert_eq!(
+            config.get("default_categories").unwrap().as_deref(),
+            Some("food,rent")
+        );
+        config.set("input.date_format", "%d/%m/%Y").unwrap();
+        assert_eq!(config.input_date_format(), Some("%d/%m/%Y"));
+        config.set("calendar.first_day_of_week", "sunday").unwrap();
+        config.set("calendar.first_day_of_month", "25").unwrap();
+        assert_eq!(
+            config.get("calendar.first_day_of_week").unwrap().as_deref(),
+            Some("Sun")
+        );
+        let calendar = config.calendar();
+        assert_eq!(calendar.first_day_of_week, chrono::Weekday::Sun);
+        assert_eq!(calendar.first_day_of_month, 25);
+
+        // an empty value clears the setting
+        config.set("display.currency", " ").unwrap();
+        assert_eq!(config.get("display.currency").unwrap(), None);
+    }
+
+    #[test]
+    fn invalid_values() {
+        let mut config = Config::default();
+        let invalid = |e| matches!(e, ConfigError::InvalidValue { .. });
+
+        assert!(invalid(config.set("log_level", "loud").unwrap_err()));
+        assert!(invalid(config.set("default_budget", "../x").unwrap_err()));
+        assert!(invalid(
+            config.set("display.currency", "euros").unwrap_err()
+        ));
+        assert!(invalid(
+            config.set("display.date_format", "%Q").unwrap_err()
+        ));
+        assert!(invalid(config.set("input.date_format", "%").unwrap_err()));
+        assert!(invalid(
+            config
+                .set("calendar.first_day_of_week", "someday")
+                .unwrap_err()
+        ));
+        assert!(invalid(
+            config.set("calendar.first_day_of_month", "29").unwrap_err()
+        ));
+        assert!(matches!(
+            config.get("colour"),
+            Err(ConfigError::UnknownKey(_))
+        ));
+
+        // a refused value leaves the setting as it was
+        assert_eq!(config.get("log_level").unwrap(), None);
+    }
+}
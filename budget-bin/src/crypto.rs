@@ -0,0 +1,268 @@
+//! At-rest encryption of the budget file.
+//!
+//! An encrypted budget is the serialised YAML budget, sealed with
+//! ChaCha20-Poly1305 under a key derived from a passphrase with Argon2id.
+//!
+//! The file layout is-
+//!
+//! | bytes | contents                                    |
+//! | ----- | ------------------------------------------- |
+//! | 6     | magic number and format version             |
+//! | 12    | argon2 memory cost, time cost and lanes     |
+//! | 16    | argon2 salt                                 |
+//! | 12    | chacha20poly1305 nonce                      |
+//! | ..    | ciphertext and authentication tag           |
+//!
+//! The header is authenticated along with the ciphertext, so tampering with
+//! the key derivation parameters is detected on decryption.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+const MAGIC: &[u8; 6] = b"BSENC\x01";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 12 + SALT_LEN + NONCE_LEN;
+
+/// The most memory, in KiB, that decrypting a file may ask Argon2 to use. The
+/// parameters are read before the file is authenticated, so a crafted file could
+/// otherwise demand any amount.
+const MAX_M_COST: u32 = 1024 * 1024;
+/// The most passes over memory that decrypting a file may ask Argon2 to make.
+const MAX_T_COST: u32 = 64;
+/// The most lanes that decrypting a file may ask Argon2 to use.
+const MAX_P_COST: u32 = 64;
+
+/// Environment variable which may hold the passphrase of an encrypted budget.
+pub const PASSPHRASE_VAR: &str = "BUDGET_PASSPHRASE";
+
+/// Environment variable which may hold the path to a keyfile for an encrypted budget.
+pub const KEYFILE_VAR: &str = "BUDGET_KEYFILE";
+
+/// A passphrase, which is wiped from memory when dropped.
+pub type Passphrase = Zeroizing<String>;
+
+#[derive(Debug)]
+pub enum CryptoError {
+    /// The data is too short, or doesn't start with the expected magic number
+    Format,
+    /// The key derivation parameters are invalid
+    Kdf(argon2::Error),
+    /// The key derivation parameters in the file would take too long or use too much memory
+    Cost,
+    /// Decryption failed. Either the passphrase is wrong, or the file has been tampered with
+    Decrypt,
+    /// The passphrase could not be read
+    Passphrase(std::io::Error),
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CryptoError::Format => write!(f, "not an encrypted budget file"),
+            CryptoError::Kdf(e) => write!(f, "key derivation error: {}", e),
+            CryptoError::Cost => write!(
+                f,
+                "the key derivation parameters of the file are too costly (the file is corrupted)"
+            ),
+            CryptoError::Decrypt => write!(
+                f,
+                "unable to decrypt budget (wrong passphrase, or the file is corrupted)"
+            ),
+            CryptoError::Passphrase(e) => write!(f, "unable to read passphrase: {}", e),
+        }
+    }
+}
+
+impl Error for CryptoError {}
+
+/// Returns true if the data is an encrypted budget.
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Encrypt the plaintext with a key derived from the passphrase.
+///
+/// A fresh salt and nonce are generated each time this is called.
+pub fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, CryptoError> {
+    let params = Params::default();
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&params.m_cost().to_le_bytes());
+    header.extend_from_slice(&params.t_cost().to_le_bytes());
+    header.extend_from_slice(&params.p_cost().to_le_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let key = derive_key(passphrase, &salt, params)?;
+    let ciphertext = ChaCha20Poly1305::new(&key)
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: &header,
+            },
+        )
+        .map_err(|_| CryptoError::Decrypt)?;
+
+    header.extend(ciphertext);
+    Ok(header)
+}
+
+/// Decrypt data previously encrypted with [`encrypt`].
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Zeroizing<Vec<u8>>, CryptoError> {
+    if data.len() < HEADER_LEN || !is_encrypted(data) {
+        return Err(CryptoError::Format);
+    }
+    let (header, ciphertext) = data.split_at(HEADER_LEN);
+
+    let u32_at = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+    let (m_cost, t_cost, p_cost) = (u32_at(6), u32_at(10), u32_at(14));
+    if m_cost > MAX_M_COST || t_cost > MAX_T_COST || p_cost > MAX_P_COST {
+        return Err(CryptoError::Cost);
+    }
+    let params = Params::new(m_cost, t_cost, p_cost, None).map_err(CryptoError::Kdf)?;
+    let salt = &header[18..18 + SALT_LEN];
+    let nonce = Nonce::from_slice(&header[18 + SALT_LEN..]);
+
+    let key = derive_key(passphrase, salt, params)?;
+    ChaCha20Poly1305::new(&key)
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| CryptoError::Decrypt)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: Params) -> Result<Key, CryptoError> {
+    let mut key = Key::default();
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(CryptoError::Kdf)?;
+    Ok(key)
+}
+
+/// Determine the passphrase for an existing encrypted budget.
+///
+/// In order of preference, the passphrase is taken from the given keyfile, the keyfile
+/// named by `BUDGET_KEYFILE`, the `BUDGET_PASSPHRASE` environment variable, or else the
+/// user is prompted for it.
+pub fn passphrase(keyfile: Option<&Path>) -> Result<Passphrase, CryptoError> {
+    match non_interactive_passphrase(keyfile)? {
+        Some(p) => Ok(p),
+        None => rpassword::prompt_password("budget passphrase: ")
+            .map(Zeroizing::new)
+            .map_err(CryptoError::Passphrase),
+    }
+}
+
+/// Determine the passphrase with which to encrypt a budget.
+///
+/// This is the same as [`passphrase`], except that when prompting the user is asked
+/// to confirm the new passphrase.
+pub fn new_passphrase(keyfile: Option<&Path>) -> Result<Passphrase, CryptoError> {
+    if let Some(p) = non_interactive_passphrase(keyfile)? {
+        return Ok(p);
+    }
+
+    loop {
+        let p = Zeroizing::new(
+            rpassword::prompt_password("new budget passphrase: ")
+                .map_err(CryptoError::Passphrase)?,
+        );
+        let confirm = Zeroizing::new(
+            rpassword::prompt_password("confirm passphrase: ").map_err(CryptoError::Passphrase)?,
+        );
+        if p.is_empty() {
+            eprintln!("passphrase must not be empty");
+        } else if p != confirm {
+            eprintln!("passphrases don't match");
+        } else {
+            return Ok(p);
+        }
+    }
+}
+
+fn non_interactive_passphrase(keyfile: Option<&Path>) -> Result<Option<Passphrase>, CryptoError> {
+    let keyfile = keyfile
+        .map(Path::to_path_buf)
+        .or_else(|| std::env::var_os(KEYFILE_VAR).map(Into::into));
+
+    if let Some(path) = keyfile {
+        log::info!("reading passphrase from keyfile {:?}", path);
+        let mut contents =
+            Zeroizing::new(std::fs::read_to_string(path).map_err(CryptoError::Passphrase)?);
+        let len = contents.trim_end_matches(&['\r', '\n'][..]).len();
+        contents.truncate(len);
+        return not_empty(contents).map(Some);
+    }
+
+    if let Ok(p) = std::env::var(PASSPHRASE_VAR) {
+        log::info!("reading passphrase from {}", PASSPHRASE_VAR);
+        return not_empty(Zeroizing::new(p)).map(Some);
+    }
+
+    Ok(None)
+}
+
+/// Refuse an empty passphrase, as the prompt does.
+fn not_empty(passphrase: Passphrase) -> Result<Passphrase, CryptoError> {
+    if passphrase.is_empty() {
+        return Err(CryptoError::Passphrase(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "the passphrase is empty",
+        )));
+    }
+    Ok(passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let plaintext = b"transactions: []";
+        let data = encrypt(plaintext, "hunter2").unwrap();
+
+        assert!(is_encrypted(&data));
+        assert_eq!(&decrypt(&data, "hunter2").unwrap()[..], &plaintext[..]);
+    }
+
+    #[test]
+    fn wrong_passphrase() {
+        let data = encrypt(b"transactions: []", "hunter2").unwrap();
+        assert!(decrypt(&data, "hunter3").is_err());
+    }
+
+    #[test]
+    fn tampered_header() {
+        let mut data = encrypt(b"transactions: []", "hunter2").unwrap();
+        data[20] ^= 1;
+        assert!(decrypt(&data, "hunter2").is_err());
+    }
+
+    #[test]
+    fn costly_header() {
+        let mut data = encrypt(b"transactions: []", "hunter2").unwrap();
+        // ask for 4 TiB of memory
+        data[6..10].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(decrypt(&data, "hunter2"), Err(CryptoError::Cost)));
+    }
+}
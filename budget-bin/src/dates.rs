@@ -0,0 +1,526 @@
+//! Parsing the dates and months given on the command line.
+//!
+//! As well as ISO 8601 dates, such as "2026-03-15", dates can be given in the
+//! configured `input.date_format`, relative to today ("yesterday", "-3d",
+//! "2 weeks ago", "last friday", "this week"), or by the name of the month
+//! ("15 mar"). Months can be given as "2026-03", "mar", "mar 2026", "last
+//! month" or "2 months ago", or as any date in the month.
+//!
+//! Weeks start on the configured `calendar.first_day_of_week`, so "this week"
+//! is the latest such day. Months start on `calendar.first_day_of_month`, so
+//! with months starting on the 25th, "this month" on the 10th of March is the
+//! month from the 25th of February, which is called February.
+
+/// The time given to a transaction which was entered with only a date.
+pub use budget_lib::midday;
+use budget_lib::CalendarMonth;
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use std::fmt;
+
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// How dates are entered: the format they are written in, and the days that
+/// weeks and months start on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Calendar<'a> {
+    /// The strftime-style format that dates are entered in, if one is set
+    pub format: Option<&'a str>,
+    /// The day that weeks start on
+    pub first_day_of_week: Weekday,
+    /// The day of the month, from 1 to 28, that months start on
+    pub first_day_of_month: u32,
+}
+
+impl Default for Calendar<'_> {
+    fn default() -> Self {
+        Calendar {
+            format: None,
+            first_day_of_week: Weekday::Mon,
+            first_day_of_month: 1,
+        }
+    }
+}
+
+/// The error returned when a date or month can't be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateError {
+    /// The input isn't a date
+    NotADate(String),
+    /// The input isn't a month
+    NotAMonth(String),
+    /// The input could mean any of several things
+    Ambiguous(String, Vec<String>),
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DateError::NotADate(input) => write!(
+                f,
+                "'{}' is not a date, such as '2026-03-15', 'yesterday', '-3d', 'last friday' or \
+                 '2 weeks ago'",
+                input
+            ),
+            DateError::NotAMonth(input) => write!(
+                f,
+                "'{}' is not a month, such as '2026-03', 'mar', 'last month' or '2 months ago'",
+                input
+            ),
+            DateError::Ambiguous(input, options) => {
+                let (last, rest) = options.split_last().expect("there are several options");
+                write!(
+                    f,
+                    "'{}' is ambiguous: it could be {} or {}",
+                    input,
+                    rest.join(", "),
+                    last
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DateError {}
+
+/// Parse a date, relative to `today`.
+pub fn parse_date(
+    input: &str,
+    today: NaiveDate,
+    calendar: &Calendar,
+) -> Result<NaiveDate, DateError> {
+    let s = input.trim().to_lowercase();
+    let err = || DateError::NotADate(input.to_string());
+
+    // the configured format comes first, since it settles any ambiguity
+    if let Some(date) = calendar
+        .format
+        .and_then(|f| NaiveDate::parse_from_str(input.trim(), f).ok())
+    {
+        return Ok(date);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    match s.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return today.pred_opt().ok_or_else(err),
+        "tomorrow" => return today.succ_opt().ok_or_else(err),
+        "this week" => return week_start(today, calendar.first_day_of_week).ok_or_else(err),
+        "last week" => {
+            return week_start(today, calendar.first_day_of_week)
+                .and_then(|d| d.checked_sub_days(Days::new(7)))
+                .ok_or_else(err)
+        }
+        "next week" => {
+            return week_start(today, calendar.first_day_of_week)
+                .and_then(|d| d.checked_add_days(Days::new(7)))
+                .ok_or_else(err)
+        }
+        _ => (),
+    }
+    if let Some(date) = offset(&s, today) {
+        return date.ok_or_else(err);
+    }
+    if let Some(date) = weekday(&s, today)? {
+        return Ok(date);
+    }
+    if let Some(date) = numeric(input, &s, today, calendar.format)? {
+        return Ok(date);
+    }
+    if let Some(date) = day_and_month(&s, today)? {
+        return Ok(date);
+    }
+    Err(err())
+}
+
+/// Parse a month, relative to the month of `today`.
+pub fn parse_month(
+    input: &str,
+    today: NaiveDate,
+    calendar: &Calendar,
+) -> Result<CalendarMonth, DateError> {
+    let s = input.trim().to_lowercase();
+    let this_month = CalendarMonth::containing(today, calendar.first_day_of_month);
+
+    match s.as_str() {
+        "this month" => return Ok(this_month),
+        "last month" => return Ok(this_month.pred()),
+        "next month" => return Ok(this_month.succ()),
+        _ => (),
+    }
+    if let Ok(month) = s.parse() {
+        return Ok(month);
+    }
+    let words: Vec<&str> = s.split_whitespace().collect();
+    let month = match words.as_slice() {
+        [name] => find_name(input, name, &MONTHS)?.map(|m| (today.year(), m)),
+        [name, year] => match (find_name(input, name, &MONTHS)?, year_of(year)) {
+            (Some(m), Some(year)) => Some((year, m)),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some((year, m)) = month {
+        return CalendarMonth::new_opt(year, (m as u32 + 1).into())
+            .ok_or_else(|| DateError::NotAMonth(input.to_string()));
+    }
+
+    // otherwise any date in the month will do
+    match parse_date(input, today, calendar) {
+        Ok(date) => Ok(CalendarMonth::containing(date, calendar.first_day_of_month)),
+        Err(DateError::NotADate(_)) => Err(DateError::NotAMonth(input.to_string())),
+        Err(e) => Err(e),
+    }
+}
+
+/// The first day of the week containing `date`, for weeks starting on `first`.
+fn week_start(date: NaiveDate, first: Weekday) -> Option<NaiveDate> {
+    let days = (date.weekday().num_days_from_monday() + 7 - first.num_days_from_monday()) % 7;
+    date.checked_sub_days(Days::new(days.into()))
+}
+
+/// A number of days, weeks, months or years before or after today, such as
+/// "-3d", "+1w", "2 weeks ago" or "in 3 days".
+///
+/// Returns None if the input isn't an offset, and Some(None) if it is but the
+/// date is out of range.
+fn offset(s: &str, today: NaiveDate) -> Option<Option<NaiveDate>> {
+    let (count, unit, forwards) = match s.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [count, unit, "ago"] => (count_of(count)?, *unit, false),
+        ["in", count, unit] => (count_of(count)?, *unit, true),
+        [short] => {
+            let forwards = match short.chars().next()? {
+                '+' => true,
+                '-' => false,
+                _ => return None,
+            };
+            let split = short.find(|c: char| c.is_alphabetic())?;
+            (short[1..split].parse().ok()?, &short[split..], forwards)
+        }
+        _ => return None,
+    };
+
+    let unit = unit.strip_suffix('s').unwrap_or(unit);
+    let (days, months) = match unit {
+        "d" | "day" => (count, 0),
+        "w" | "week" => match count.checked_mul(7) {
+            Some(days) => (days, 0),
+            None => return Some(None),
+        },
+        "m" | "month" => (0, count),
+        "y" | "year" => match count.checked_mul(12) {
+            Some(months) => (0, months),
+            None => return Some(None),
+        },
+        _ => return None,
+    };
+    Some(if forwards {
+        today
+            .checked_add_days(Days::new(days.into()))
+            .and_then(|d| d.checked_add_months(Months::new(months)))
+    } else {
+        today
+            .checked_sub_days(Days::new(days.into()))
+            .and_then(|d| d.checked_sub_months(Months::new(months)))
+    })
+}
+
+fn count_of(word: &str) -> Option<u32> {
+    match word {
+        "a" | "an" | "one" => Some(1),
+        _ => word.parse().ok(),
+    }
+}
+
+/// A day of the week, such as "friday", "last fri" or "next friday". A day on
+/// its own is the latest such day, which may be today.
+fn weekday(s: &str, today: NaiveDate) -> Result<Option<NaiveDate>, DateError> {
+    let (name, which) = match s.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [name] => (*name, None),
+        ["last", name] => (*name, Some(false)),
+        ["next", name] => (*name, Some(true)),
+        _ => return Ok(None),
+    };
+    let day = match find_name(s, name, &WEEKDAYS)? {
+        Some(i) => i as u32,
+        None => return Ok(None),
+    };
+
+    let today_num = today.weekday().num_days_from_monday();
+    let date = match which {
+        None => today.checked_sub_days(Days::new(((today_num + 7 - day) % 7).into())),
+        Some(false) => today.checked_sub_days(Days::new(((today_num + 6 - day) % 7 + 1).into())),
+        Some(true) => today.checked_add_days(Days::new(((day + 6 - today_num) % 7 + 1).into())),
+    };
+    Ok(date)
+}
+
+/// A date of numbers separated by '/' or '.', such as "15/03/2026" or "3/15".
+/// Without a configured format saying which comes first, it is ambiguous
+/// whether the day or month comes first unless only one makes a valid date.
+/// A date which starts with the year, such as "2026/03/15", is always year,
+/// month then day.
+fn numeric(
+    input: &str,
+    s: &str,
+    today: NaiveDate,
+    format: Option<&str>,
+) -> Result<Option<NaiveDate>, DateError> {
+    let parts: Option<Vec<u32>> = s.split(['/', '.']).map(|p| p.parse().ok()).collect();
+    let (a, b, year) = match parts.as_deref() {
+        Some([y, m, d]) if *y >= 1000 => {
+            return NaiveDate::from_ymd_opt(*y as i32, *m, *d)
+                .map(Some)
+                .ok_or_else(|| DateError::NotADate(input.to_string()))
+        }
+        Some([a, b]) => (*a, *b, today.year()),
+        Some([a, b, y]) if *y < 100 => (*a, *b, 2000 + *y as i32),
+        Some([a, b, y]) => (*a, *b, *y as i32),
+        _ => return Ok(None),
+    };
+
+    let day_first = format.and_then(|f| Some(f.find("%d")? < f.find("%m")?));
+    let mut dates = Vec::new();
+    if day_first != Some(false) {
+        dates.extend(NaiveDate::from_ymd_opt(year, b, a));
+    }
+    if day_first != Some(true) {
+        dates.extend(NaiveDate::from_ymd_opt(year, a, b));
+    }
+    dates.dedup();
+    match dates.as_slice() {
+        [] => Err(DateError::NotADate(input.to_string())),
+        [date] => Ok(Some(*date)),
+        _ => Err(DateError::Ambiguous(
+            input.to_string(),
+            dates.iter().map(ToString::to_string).collect(),
+        )),
+    }
+}
+
+/// A day and the name of a month, in either order, with an optional year, such
+/// as "15 mar", "march 15" or "15 march 2026".
+fn day_and_month(s: &str, today: NaiveDate) -> Result<Option<NaiveDate>, DateError> {
+    let words: Vec<&str> = s.split_whitespace().collect();
+    let (first, second, year) = match words.as_slice() {
+        [first, second] => (*first, *second, today.year()),
+        [first, second, year] => match year_of(year) {
+            Some(year) => (*first, *second, year),
+            None => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    let (day, name) = match (first.parse::<u32>(), second.parse::<u32>()) {
+        (Ok(day), Err(_)) => (day, second),
+        (Err(_), Ok(day)) => (day, first),
+        _ => return Ok(None),
+    };
+    match find_name(s, name, &MONTHS)? {
+        Some(m) => Ok(NaiveDate::from_ymd_opt(year, m as u32 + 1, day)),
+        None => Ok(None),
+    }
+}
+
+/// A year, where two digits are a year of this century, as in "15/03/26".
+fn year_of(word: &str) -> Option<i32> {
+    match word.parse() {
+        Ok(year) if (0..100).contains(&year) => Some(2000 + year),
+        Ok(year) => Some(year),
+        Err(_) => None,
+    }
+}
+
+/// Find the name which starts with `word`, which must be at least two letters.
+/// Returns its index, or an error if more than one name starts with it.
+fn find_name(input: &str, word: &str, names: &[&str]) -> Result<Option<usize>, DateError> {
+    if word.len() < 2 {
+        return Ok(None);
+    }
+    let found: Vec<usize> = (0..names.len())
+        .filter(|i| names[*i].starts_with(word))
+        .collect();
+    match found.as_slice() {
+        [] => Ok(None),
+        [i] => Ok(Some(*i)),
+        _ => Err(DateError::Ambiguous(
+            input.to_string(),
+            found.iter().map(|i| names[*i].to_string()).collect(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn format(format: &str) -> Calendar<'_> {
+        Calendar {
+            format: Some(format),
+            ..Calendar::default()
+        }
+    }
+
+    #[test]
+    fn relative_dates() {
+        // a sunday
+        let today = date("2026-10-18");
+        let parse = |s| {
+            parse_date(s, today, &Calendar::default())
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(parse("2026-03-15"), "2026-03-15");
+        assert_eq!(parse("Yesterday"), "2026-10-17");
+        assert_eq!(parse("-3d"), "2026-10-15");
+        assert_eq!(parse("+1w"), "2026-10-25");
+        assert_eq!(parse("2 weeks ago"), "2026-10-04");
+        assert_eq!(parse("a month ago"), "2026-09-18");
+        assert_eq!(parse("in 3 days"), "2026-10-21");
+        assert_eq!(parse("sunday"), "2026-10-18");
+        assert_eq!(parse("last sun"), "2026-10-11");
+        assert_eq!(parse("friday"), "2026-10-16");
+        assert_eq!(parse("next friday"), "2026-10-23");
+        assert_eq!(parse("15 mar"), "2026-03-15");
+        assert_eq!(parse("march 15 2025"), "2025-03-15");
+        assert_eq!(parse("15 mar 25"), "2025-03-15");
+
+        // offsets too far away are not dates, rather than overflowing
+        for s in ["+999999999w", "999999999 years ago"] {
+            assert_eq!(
+                parse_date(s, today, &Calendar::default()),
+                Err(DateError::NotADate(s.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn ambiguous_dates() {
+        let today = date("2026-10-18");
+
+        assert_eq!(
+            parse_date("3/4", today, &Calendar::default()),
+            Err(DateError::Ambiguous(
+                "3/4".to_string(),
+                vec!["2026-04-03".to_string(), "2026-03-04".to_string()]
+            ))
+        );
+        // only one way round is a date
+        assert_eq!(
+            parse_date("15/3/26", today, &Calendar::default()),
+            Ok(date("2026-03-15"))
+        );
+        // the configured format says which way round it is
+        assert_eq!(
+            parse_date("3/4", today, &format("%m/%d/%Y")),
+            Ok(date("2026-03-04"))
+        );
+        assert_eq!(
+            parse_date("03.04.2026", today, &format("%d.%m.%Y")),
+            Ok(date("2026-04-03"))
+        );
+        // a date starting with the year is never ambiguous
+        assert_eq!(
+            parse_date("2026/03/04", today, &Calendar::default()),
+            Ok(date("2026-03-04"))
+        );
+        assert_eq!(
+            parse_date("2026.3.4", today, &format("%d.%m.%Y")),
+            Ok(date("2026-03-04"))
+        );
+        assert_eq!(
+            parse_date("2026/02/30", today, &Calendar::default()),
+            Err(DateError::NotADate("2026/02/30".to_string()))
+        );
+
+        assert!(matches!(
+            parse_date("t", today, &Calendar::default()),
+            Err(DateError::NotADate(_))
+        ));
+        assert!(matches!(
+            parse_date("last t", today, &Calendar::default()),
+            Err(DateError::NotADate(_))
+        ));
+        assert!(matches!(
+            parse_date("last tu", today, &Calendar::default()),
+            Ok(d) if d == date("2026-10-13")
+        ));
+        assert!(matches!(
+            parse_month("ju", today, &Calendar::default()),
+            Err(DateError::Ambiguous(_, names)) if names == vec!["june", "july"]
+        ));
+    }
+
+    #[test]
+    fn months() {
+        let today = date("2026-01-10");
+        let parse = |s| parse_month(s, today, &Calendar::default()).map(|m| m.to_string());
+
+        assert_eq!(parse("2026-03"), Ok("2026-03".to_string()));
+        assert_eq!(parse("last month"), Ok("2025-12".to_string()));
+        assert_eq!(parse("mar"), Ok("2026-03".to_string()));
+        assert_eq!(parse("March 2025"), Ok("2025-03".to_string()));
+        assert_eq!(parse("mar 25"), Ok("2025-03".to_string()));
+        assert_eq!(parse("-2m"), Ok("2025-11".to_string()));
+        assert_eq!(parse("yesterday"), Ok("2026-01".to_string()));
+        assert_eq!(parse("soon"), Err(DateError::NotAMonth("soon".to_string())));
+        assert!(parse("99999999-01").is_err());
+        assert!(parse("mar 99999999").is_err());
+    }
+
+    #[test]
+    fn weeks_and_months_start_on_the_configured_days() {
+        // a sunday
+        let today = date("2026-10-18");
+        let parse = |s, calendar| parse_date(s, today, &calendar).unwrap().to_string();
+        let sundays = Calendar {
+            first_day_of_week: Weekday::Sun,
+            ..Calendar::default()
+        };
+
+        assert_eq!(parse("this week", Calendar::default()), "2026-10-12");
+        assert_eq!(parse("last week", Calendar::default()), "2026-10-05");
+        assert_eq!(parse("next week", Calendar::default()), "2026-10-19");
+        assert_eq!(parse("this week", sundays), "2026-10-18");
+        assert_eq!(parse("last week", sundays), "2026-10-11");
+
+        let payday = Calendar {
+            first_day_of_month: 25,
+            ..Calendar::default()
+        };
+        let month = |s, today| parse_month(s, date(today), &payday).unwrap().to_string();
+        assert_eq!(month("this month", "2026-03-10"), "2026-02");
+        assert_eq!(month("this month", "2026-03-25"), "2026-03");
+        assert_eq!(month("last month", "2026-01-10"), "2025-11");
+        assert_eq!(month("2026-03-24", "2026-10-18"), "2026-02");
+        // a month given by name is that month
+        assert_eq!(month("mar", "2026-10-18"), "2026-03");
+    }
+}
@@ -0,0 +1,153 @@
+//! Advisory locking of the budget file.
+//!
+//! Every invocation of the app loads the whole budget, modifies it, and saves it
+//! again. The lock is held for that whole cycle so that two concurrent invocations
+//! can't overwrite each other's changes.
+//!
+//! The lock is an OS-level advisory lock on a file alongside the budget, so it is
+//! released by the OS if the process holding it crashes. The lock file records the
+//! process holding the lock, and is emptied when the lock is released cleanly. A
+//! non-empty lock file which isn't locked was therefore left behind by a process
+//! which didn't exit cleanly, and is recovered.
+
+use fs2::FileExt;
+use std::error::Error;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+pub enum LockError {
+    Io(io::Error),
+    /// The lock wasn't released by its holder within the timeout
+    Timeout {
+        holder: Option<String>,
+    },
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LockError::Io(e) => write!(f, "IO error: {}", e),
+            LockError::Timeout { holder } => {
+                write!(f, "the budget is in use by another process")?;
+                if let Some(h) = holder {
+                    write!(f, " ({})", h)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for LockError {}
+
+impl From<io::Error> for LockError {
+    fn from(e: io::Error) -> Self {
+        LockError::Io(e)
+    }
+}
+
+/// An exclusive lock on a budget, which is released when dropped.
+#[derive(Debug)]
+pub struct Lock {
+    file: File,
+}
+
+impl Lock {
+    /// Acquire the lock at the given path, waiting up to `timeout` for another process to release it.
+    pub fn acquire<P: AsRef<Path>>(path: P, timeout: Duration) -> Result<Lock, LockError> {
+        let path = path.as_ref();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let start = Instant::now();
+        while let Err(e) = file.try_lock_exclusive() {
+            if e.kind() != fs2::lock_contended_error().kind() {
+                return Err(LockError::Io(e));
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(LockError::Timeout {
+                    holder: read_holder(&mut file),
+                });
+            }
+
+            if start.elapsed() < POLL_INTERVAL {
+                log::info!("waiting for lock {:?}", path);
+                eprintln!("waiting for another process to finish with the budget...");
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+
+        if let Some(holder) = read_holder(&mut file) {
+            log::warn!("recovered stale lock {:?} left by {}", path, holder);
+        }
+
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(
+            file,
+            "pid {} since {}",
+            std::process::id(),
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        )?;
+        file.sync_all()?;
+
+        log::info!("acquired lock {:?}", path);
+        Ok(Lock { file })
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if let Err(e) = self.file.set_len(0) {
+            log::warn!("unable to clear lock file: {}", e);
+        }
+        if let Err(e) = FileExt::unlock(&self.file) {
+            log::warn!("unable to release lock: {}", e);
+        }
+    }
+}
+
+fn read_holder(file: &mut File) -> Option<String> {
+    let mut holder = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut holder).ok()?;
+    let holder = holder.trim();
+    if holder.is_empty() {
+        None
+    } else {
+        Some(holder.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contended() {
+        let path = std::env::temp_dir().join(format!("budget-lock-{}", std::process::id()));
+
+        let lock = Lock::acquire(&path, Duration::from_secs(0)).unwrap();
+        match Lock::acquire(&path, Duration::from_millis(200)) {
+            Err(LockError::Timeout { holder }) => {
+                assert!(holder.unwrap().starts_with("pid "))
+            }
+            x => panic!("expected timeout, got {:?}", x),
+        }
+
+        drop(lock);
+        Lock::acquire(&path, Duration::from_secs(0)).unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,7 +1,7 @@
 use std::fs;
 use std::path::Path;
 
-pub fn setup_logging(log_path: &Path) {
+pub fn setup_logging(log_path: &Path, level: log::LevelFilter) {
     fern::Dispatch::new()
         .format(|out, message, record| {
             out.finish(format_args!(
@@ -12,7 +12,7 @@ pub fn setup_logging(log_path: &Path) {
                 message
             ))
         })
-        .level(log::LevelFilter::Debug)
+        .level(level)
         .chain(
             fs::OpenOptions::new()
                 .write(true)
@@ -1,35 +1,105 @@
 #![warn(clippy::all)]
 
 mod app;
+mod budgets;
+mod chart;
+mod config;
+mod crypto;
+mod dates;
+mod lock;
 mod logging;
+mod names;
 mod sd;
+mod shell;
+mod table;
+mod tui;
+use app::AppTools;
 use budget_lib::Budget;
+use std::time::Duration;
 
 fn main() {
+    let matches = app::get_app().get_matches();
+
     let budget_root = dirs::home_dir()
         .expect("unable to determine home directory")
         .join(".budget");
 
+    let config_path = config::Config::path(&budget_root);
+    let mut config = config::Config::load(&config_path).unwrap_or_else(|e| {
+        eprintln!("unable to load configuration {:?}: {}", config_path, e);
+        std::process::exit(1)
+    });
+
     // create the Budget directory if it doesn't already exist
-    std::fs::DirBuilder::new()
-        .recursive(true)
-        .create(&budget_root)
+    let dir = budgets::BudgetDirectory::open(&budget_root).unwrap_or_else(|e| {
+        panic!(
+            "unable to create budget directory {:?}, error: {}",
+            &budget_root, e
+        )
+    });
+
+    // set up logging for the app
+    logging::setup_logging(&dir.root().join("log"), config.log_level());
+    log::info!("using configuration {:?}", config_path);
+
+    if app::run_without_budget(&dir, &mut config, &matches) {
+        return;
+    }
+
+    let path = dir
+        .select(
+            matches.value_of("file"),
+            matches.value_of("budget"),
+            config.default_budget(),
+        )
         .unwrap_or_else(|e| {
-            panic!(
-                "unable to create budget directory {:?}, error: {}",
-                &budget_root, e
-            )
+            eprintln!("{}", e);
+            std::process::exit(1)
         });
+    log::info!("using budget {:?}", path);
+    let mut file = sd::BudgetFile::new(path, matches.value_of("keyfile").map(Into::into));
 
-    // set up logging for the app
-    logging::setup_logging(&budget_root.join("log"));
+    // lock the budget until it has been saved, so that concurrent invocations can't clobber each other
+    let timeout: u64 = matches
+        .typed_value_of("lock timeout", Some("whole seconds"))
+        .unwrap();
+    let _lock = lock::Lock::acquire(file.sibling("lock"), Duration::from_secs(timeout))
+        .unwrap_or_else(|e| {
+            eprintln!("unable to lock budget {:?}: {}", file.path(), e);
+            std::process::exit(1)
+        });
 
     // load the budget from the budget directory, or create a new one
-    //let mut budget = Budget::from_directory(&budget_root).unwrap_or_default();
-    let mut budget: Budget = sd::from_file(&budget_root).unwrap_or_default();
+    let mut budget: Budget = file
+        .load()
+        .unwrap_or_else(|e| {
+            eprintln!("unable to load budget {:?}: {}", file.path(), e);
+            std::process::exit(1)
+        })
+        .unwrap_or_else(|| config.new_budget());
+    budget.set_first_day_of_month(config.calendar().first_day_of_month);
+
+    // add any scheduled transactions which have fallen due since the budget was last used
+    let added = budget.add_due_transactions(chrono::Local::now().date_naive());
+    if added > 0 {
+        log::info!("added {} scheduled transactions", added);
+    }
+
+    if !app::run(&mut budget, &mut file, &config, &matches) {
+        return;
+    }
 
-    app::run(&mut budget);
+    let unconverted = budget.unconverted().count();
+    if unconverted > 0 {
+        eprintln!(
+            "warning: {} transactions are left out of the budget, because there is no \
+             exchange rate for their currency. add one with 'budget rate add'",
+            unconverted
+        );
+    }
 
-    //budget.save_to_directory(&budget_root).unwrap();
-    sd::to_file(&budget, &budget_root.join("budget")).unwrap();
+    file.save(&budget).unwrap_or_else(|e| {
+        eprintln!("unable to save budget {:?}: {}", file.path(), e);
+        std::process::exit(1)
+    });
 }
@@ -0,0 +1,271 @@
+//! Looking up the categories, accounts, payees and groups named on the command
+//! line, which may be shortened, in a different case, or misspelt.
+//!
+//! A name matches exactly, or else ignoring case, or else as the start of a
+//! single name. Names which don't match are an error, which suggests the names
+//! closest to it by edit distance.
+
+use budget_lib::Budget;
+use std::fmt;
+
+/// The most suggestions given for a name which doesn't match.
+const SUGGESTIONS: usize = 3;
+
+/// The error returned when a name doesn't match exactly one thing.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NameError {
+    /// Nothing has the name. Holds the closest names, if any are close.
+    Unknown {
+        kind: &'static str,
+        name: String,
+        suggestions: Vec<String>,
+    },
+    /// The name is the start of more than one name.
+    Ambiguous {
+        kind: &'static str,
+        name: String,
+        options: Vec<String>,
+    },
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NameError::Unknown {
+                kind,
+                name,
+                suggestions,
+            } => {
+                write!(f, "no {} named '{}'", kind, name)?;
+                if !suggestions.is_empty() {
+                    write!(f, ". did you mean {}?", alternatives(suggestions))?;
+                }
+                Ok(())
+            }
+            NameError::Ambiguous {
+                kind,
+                name,
+                options,
+            } => write!(
+                f,
+                "'{}' could be the {} {}",
+                name,
+                kind,
+                alternatives(options)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for NameError {}
+
+/// Quote a list of names, such as "'a', 'b' or 'c'".
+fn alternatives(names: &[String]) -> String {
+    let quoted: Vec<String> = names.iter().map(|n| format!("'{}'", n)).collect();
+    match quoted.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+/// Find the category with a name, returning its path. A category can be named by
+/// its path, or by its own name when the name has no ':' in it. Hidden categories
+/// only match their exact name or path.
+pub fn category(budget: &Budget, name: &str) -> Result<String, NameError> {
+    find_category(budget, name, false)
+}
+
+/// Find the category with a name, like [`category`], including the hidden
+/// categories.
+pub fn any_category(budget: &Budget, name: &str) -> Result<String, NameError> {
+    find_category(budget, name, true)
+}
+
+fn find_category(budget: &Budget, name: &str, hidden: bool) -> Result<String, NameError> {
+    if let Some(c) = budget.category(name) {
+        return Ok(budget.category_path(c));
+    }
+    let visible: Vec<_> = budget
+        .categories()
+        .filter(|c| hidden || !c.hidden())
+        .collect();
+    let paths: Vec<String> = visible.iter().map(|c| budget.category_path(c)).collect();
+    let entries = if name.contains(':') {
+        paths.iter().map(|p| (p.as_str(), p.as_str())).collect()
+    } else {
+        visible
+            .iter()
+            .zip(&paths)
+            .map(|(c, p)| (c.name(), p.as_str()))
+            .collect()
+    };
+    find("category", name, entries)
+}
+
+/// Find the category with a name, like [`category`]. If `create` is true, a
+/// name which matches no category is taken to be a new category, and returned
+/// as it is.
+pub fn new_category(budget: &Budget, name: &str, create: bool) -> Result<String, NameError> {
+    match category(budget, name) {
+        Err(NameError::Unknown { .. }) if create => Ok(name.to_string()),
+        result => result,
+    }
+}
+
+/// Find the account with a name.
+pub fn account(budget: &Budget, name: &str) -> Result<String, NameError> {
+    let entries = budget.accounts().map(|a| (a.name(), a.name())).collect();
+    find("account", name, entries)
+}
+
+/// Find the payee with a name, from the registered payees and the payees of
+/// transactions.
+pub fn payee(budget: &Budget, name: &str) -> Result<String, NameError> {
+    if let Some(p) = budget.payee(name) {
+        return Ok(p.name().to_string());
+    }
+    let stats = budget.payee_stats();
+    let entries = stats
+        .iter()
+        .map(|s| (s.payee.as_str(), s.payee.as_str()))
+        .collect();
+    find("payee", name, entries)
+}
+
+/// Find the group of categories with a name.
+pub fn group(budget: &Budget, name: &str) -> Result<String, NameError> {
+    let entries = budget
+        .master_categories()
+        .map(|m| (m.name(), m.name()))
+        .collect();
+    find("group", name, entries)
+}
+
+/// Find the value whose key matches `name`. Each value may have several keys.
+fn find(kind: &'static str, name: &str, entries: Vec<(&str, &str)>) -> Result<String, NameError> {
+    let lower = name.to_lowercase();
+    let matching = |f: &dyn Fn(&str) -> bool| {
+        let mut values: Vec<&str> = entries
+            .iter()
+            .filter(|(key, _)| f(key))
+            .map(|(_, value)| *value)
+            .collect();
+        values.sort_unstable();
+        values.dedup();
+        values
+    };
+
+    let tests: [&dyn Fn(&str) -> bool; 3] = [
+        &|key| key == name,
+        &|key| key.to_lowercase() == lower,
+        &|key| key.to_lowercase().starts_with(&lower),
+    ];
+    for test in tests.iter() {
+        match matching(test).as_slice() {
+            [] => continue,
+            [value] => return Ok(value.to_string()),
+            values => {
+                return Err(NameError::Ambiguous {
+                    kind,
+                    name: name.to_string(),
+                    options: values.iter().map(ToString::to_string).collect(),
+                })
+            }
+        }
+    }
+
+    Err(NameError::Unknown {
+        kind,
+        name: name.to_string(),
+        suggestions: suggestions(name, &entries),
+    })
+}
+
+/// The values with a key close to `name`, closest first.
+fn suggestions(name: &str, entries: &[(&str, &str)]) -> Vec<String> {
+    let lower = name.to_lowercase();
+    // allow about one mistake for every three letters
+    let max = (lower.chars().count() / 3).max(1);
+
+    let mut close: Vec<(usize, &str)> = entries
+        .iter()
+        .map(|(key, value)| {
+            let distance = strsim::damerau_levenshtein(&lower, &key.to_lowercase());
+            (distance, *value)
+        })
+        .filter(|(distance, _)| *distance <= max)
+        .collect();
+    close.sort_unstable();
+    let mut values: Vec<String> = Vec::new();
+    for (_, value) in close {
+        if !values.iter().any(|v| v == value) {
+            values.push(value.to_string());
+        }
+    }
+    values.truncate(SUGGESTIONS);
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries<'a>(names: &[&'a str]) -> Vec<(&'a str, &'a str)> {
+        names.iter().map(|n| (*n, *n)).collect()
+    }
+
+    #[test]
+    fn matching_names() {
+        let names = entries(&["groceries", "rent", "restaurants", "Savings"]);
+        let find = |name| find("category", name, names.clone());
+
+        assert_eq!(find("rent"), Ok("rent".to_string()));
+        assert_eq!(find("savings"), Ok("Savings".to_string()));
+        assert_eq!(find("groc"), Ok("groceries".to_string()));
+        assert_eq!(
+            find("re").unwrap_err().to_string(),
+            "'re' could be the category 'rent' or 'restaurants'"
+        );
+        assert_eq!(
+            find("grocries").unwrap_err().to_string(),
+            "no category named 'grocries'. did you mean 'groceries'?"
+        );
+        assert_eq!(
+            find("rant").unwrap_err().to_string(),
+            "no category named 'rant'. did you mean 'rent'?"
+        );
+        assert_eq!(
+            find("holiday").unwrap_err().to_string(),
+            "no category named 'holiday'"
+        );
+    }
+
+    #[test]
+    fn creating_and_hidden_categories() {
+        let mut budget = Budget::with_categories(vec!["groceries", "Eating Out"]);
+        assert!(budget.set_category_hidden("groceries", true));
+
+        assert_eq!(
+            new_category(&budget, "eating out", true),
+            Ok("Eating Out".to_string())
+        );
+        assert_eq!(
+            new_category(&budget, "eat", true),
+            Ok("Eating Out".to_string())
+        );
+        assert_eq!(
+            new_category(&budget, "holiday", true),
+            Ok("holiday".to_string())
+        );
+        assert!(new_category(&budget, "holiday", false).is_err());
+
+        assert_eq!(category(&budget, "groceries"), Ok("groceries".to_string()));
+        assert!(category(&budget, "Groceries").is_err());
+        assert_eq!(
+            any_category(&budget, "Groceries"),
+            Ok("groceries".to_string())
+        );
+        assert_eq!(any_category(&budget, "groc"), Ok("groceries".to_string()));
+    }
+}
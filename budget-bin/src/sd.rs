@@ -1,15 +1,16 @@
+use crate::crypto::{self, CryptoError, Passphrase};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::error::Error;
 use std::fmt;
-use std::fs::File;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub enum SerialiseError {
     Io(io::Error),
     Parse(serde_yaml::Error),
+    Crypto(CryptoError),
 }
 
 impl fmt::Display for SerialiseError {
@@ -17,28 +18,125 @@ impl fmt::Display for SerialiseError {
         match self {
             SerialiseError::Io(e) => write!(f, "IO error: {}", e),
             SerialiseError::Parse(e) => write!(f, "Parse Error: {}", e),
+            SerialiseError::Crypto(e) => write!(f, "Encryption error: {}", e),
         }
     }
 }
 
 impl Error for SerialiseError {}
 
-pub fn to_file<T: Serialize, P: AsRef<Path>>(t: &T, path: P) -> Result<(), SerialiseError> {
-    match File::create(path) {
-        Err(x) => Err(SerialiseError::Io(x)),
-        Ok(file) => match serde_yaml::to_writer(file, t) {
-            Err(x) => Err(SerialiseError::Parse(x)),
-            Ok(_) => Ok(()),
-        },
+impl From<CryptoError> for SerialiseError {
+    fn from(e: CryptoError) -> Self {
+        SerialiseError::Crypto(e)
     }
 }
 
-pub fn from_file<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T, SerialiseError> {
-    match File::open(path) {
-        Err(x) => Err(SerialiseError::Io(x)),
-        Ok(file) => match serde_yaml::from_reader(file) {
-            Err(x) => Err(SerialiseError::Parse(x)),
-            Ok(x) => Ok(x),
-        },
+/// A file on disk holding a budget, which may be encrypted.
+///
+/// Whether or not the budget is encrypted is detected when it is loaded, and
+/// the budget is saved back in the same mode unless it is changed with
+/// [`BudgetFile::encrypt`] or [`BudgetFile::decrypt`].
+pub struct BudgetFile {
+    path: PathBuf,
+    keyfile: Option<PathBuf>,
+    passphrase: Option<Passphrase>,
+}
+
+impl BudgetFile {
+    /// Create a new BudgetFile. The optional keyfile is used in preference to
+    /// the environment when a passphrase is needed.
+    pub fn new<P: Into<PathBuf>>(path: P, keyfile: Option<PathBuf>) -> Self {
+        BudgetFile {
+            path: path.into(),
+            keyfile,
+            passphrase: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path of a file kept alongside the budget, named by adding a
+    /// suffix to the budget's file name, such as "home.yaml.lock".
+    pub fn sibling(&self, suffix: &str) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(suffix);
+        self.path.with_file_name(name)
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.passphrase.is_some()
+    }
+
+    /// Load the budget, decrypting it if required.
+    ///
+    /// If the file doesn't exist yet, `None` is returned.
+    pub fn load<T: DeserializeOwned>(&mut self) -> Result<Option<T>, SerialiseError> {
+        let data = match std::fs::read(&self.path) {
+            Ok(data) => data,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+                log::info!("no budget found at {:?}", self.path);
+                return Ok(None);
+            }
+            Err(e) => return Err(SerialiseError::Io(e)),
+        };
+
+        if crypto::is_encrypted(&data) {
+            log::info!("decrypting budget {:?}", self.path);
+            let passphrase = crypto::passphrase(self.keyfile.as_deref())?;
+            let plaintext = crypto::decrypt(&data, &passphrase)?;
+            self.passphrase = Some(passphrase);
+            serde_yaml::from_slice(&plaintext)
+        } else {
+            serde_yaml::from_slice(&data)
+        }
+        .map(Some)
+        .map_err(SerialiseError::Parse)
+    }
+
+    /// Save the budget, encrypting it if required.
+    ///
+    /// The budget is written to a temporary file which then replaces the
+    /// original, so that a failure part way through can't corrupt the budget.
+    pub fn save<T: Serialize>(&self, t: &T) -> Result<(), SerialiseError> {
+        let plaintext = serde_yaml::to_vec(t).map_err(SerialiseError::Parse)?;
+        let data = match &self.passphrase {
+            Some(passphrase) => crypto::encrypt(&plaintext, passphrase)?,
+            None => plaintext,
+        };
+
+        let tmp = self.sibling("tmp");
+        std::fs::write(&tmp, data)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .map_err(SerialiseError::Io)
+    }
+
+    /// Switch to encrypted mode, with a new passphrase. The budget is
+    /// encrypted the next time it is saved.
+    pub fn encrypt(&mut self) -> Result<(), CryptoError> {
+        self.passphrase = Some(crypto::new_passphrase(self.keyfile.as_deref())?);
+        Ok(())
+    }
+
+    /// Switch to plaintext mode. The budget is decrypted the next time it is saved.
+    pub fn decrypt(&mut self) {
+        self.passphrase = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn siblings_keep_the_whole_file_name() {
+        let file = BudgetFile::new("budgets/home.yaml", None);
+        assert_eq!(file.sibling("lock"), Path::new("budgets/home.yaml.lock"));
+
+        // budgets whose names differ only after a dot don't share a lock
+        let other = BudgetFile::new("budgets/home.old", None);
+        assert_ne!(file.sibling("lock"), other.sibling("lock"));
     }
 }
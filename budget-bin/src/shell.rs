@@ -0,0 +1,528 @@
+//! An interactive shell which loads the budget once and runs commands against it
+//! until it is closed.
+//!
+//! Commands take the same arguments as they do on the command line. Commands
+//! report errors by calling [`exit`], which abandons the command in the shell
+//! rather than ending the process.
+
+use crate::app;
+use crate::config::Config;
+use crate::sd::BudgetFile;
+use budget_lib::Budget;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether commands are being run in the shell.
+static IN_SHELL: AtomicBool = AtomicBool::new(false);
+
+/// The commands which operate on the budget directory or the configuration, and
+/// so can't be run against the budget which the shell has open.
+const OUTSIDE_COMMANDS: [&str; 5] = ["shell", "new", "use", "list-budgets", "config"];
+
+/// The commands which only show the budget, so it has no unsaved changes after
+/// them, by the names of the command and its subcommands. Any other command may
+/// have changed it.
+const SHOWING_COMMANDS: [&str; 16] = [
+    "summary",
+    "forecast",
+    "report spending",
+    "report cashflow",
+    "report networth",
+    "report fx",
+    "account list",
+    "category list",
+    "category group list",
+    "payee list",
+    "rate list",
+    "rules list",
+    "rules test",
+    "schedule list",
+    "tag list",
+    "transaction list",
+];
+
+/// The payload of the panic which abandons a command in the shell.
+struct Abandoned;
+
+/// Exit with the given code after an error has been reported. In the shell, this
+/// abandons the command instead.
+pub fn exit(code: i32) -> ! {
+    if IN_SHELL.load(Ordering::Relaxed) {
+        panic::resume_unwind(Box::new(Abandoned))
+    }
+    std::process::exit(code)
+}
+
+/// Report an error from clap, such as an invalid value, and exit.
+pub fn exit_with(e: clap::Error) -> ! {
+    if !IN_SHELL.load(Ordering::Relaxed) {
+        e.exit()
+    }
+    if e.use_stderr() {
+        eprintln!("{}", e.message);
+    } else {
+        println!("{}", e.message);
+    }
+    exit(1)
+}
+
+/// Run the shell until it is closed. The budget is saved when the shell is
+/// closed with 'exit', and whenever the 'save' command is used.
+///
+/// Returns false if the budget shouldn't be saved when the shell closes, because
+/// it was left with 'quit!' or a command failed part way through.
+pub fn run(budget: &mut Budget, file: &mut BudgetFile, config: &Config) -> bool {
+    let mut editor: Editor<ShellHelper, DefaultHistory> = Editor::new().unwrap_or_else(|e| {
+        eprintln!("unable to start the shell: {}", e);
+        std::process::exit(1)
+    });
+    // the history of an encrypted budget would give away what's in it, so it's only kept in memory
+    let history = file.sibling("history");
+    if !file.is_encrypted() {
+        // there is no history the first time the shell is used
+        let _ = editor.load_history(&history);
+    }
+    editor.set_helper(Some(ShellHelper::new(budget)));
+
+    println!(
+        "type a command such as 'summary' or '4.50 coffee'. 'save' saves the budget, 'exit' \
+         saves it and leaves, and 'quit!' leaves without saving"
+    );
+    let mut unsaved = false;
+    let mut discarding = false;
+    let mut discard = false;
+    // a command which fails part way through can leave the budget half changed
+    let mut failed = false;
+    IN_SHELL.store(true, Ordering::Relaxed);
+    loop {
+        let prompt = if unsaved { "budget*> " } else { "budget> " };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                if unsaved {
+                    println!(
+                        "there are unsaved changes. use 'exit' to save them and leave, or \
+                         'quit!' to leave without saving"
+                    );
+                } else {
+                    println!("use 'exit' to leave");
+                }
+                continue;
+            }
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("unable to read the command: {}", e);
+                break;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        match line {
+            "exit" | "quit" => break,
+            "quit!" if unsaved && !discarding => {
+                println!(
+                    "there are unsaved changes. use 'quit!' again to leave without saving them"
+                );
+                discarding = true;
+                continue;
+            }
+            "quit!" => {
+                discard = true;
+                break;
+            }
+            "save" if failed => {
+                eprintln!("{}", FAILED);
+            }
+            "save" => {
+                unsaved &= !save(budget, file);
+            }
+            _ => match run_line(budget, file, config, line) {
+                Outcome::Unchanged => (),
+                Outcome::Changed => unsaved = true,
+                Outcome::Failed => {
+                    unsaved = true;
+                    failed = true;
+                }
+            },
+        }
+        discarding = false;
+        if let Some(helper) = editor.helper_mut() {
+            helper.refresh(budget);
+        }
+    }
+    IN_SHELL.store(false, Ordering::Relaxed);
+
+    if !file.is_encrypted() {
+        if let Err(e) = editor.save_history(&history) {
+            log::warn!("unable to save the shell history {:?}: {}", history, e);
+        }
+    }
+    if failed && !discard {
+        eprintln!("the budget wasn't saved, because a command failed part way through");
+    }
+    // otherwise the budget is saved when the command finishes
+    !discard && !failed
+}
+
+/// Why the budget isn't saved after a command has failed in the shell.
+const FAILED: &str = "a command failed part way through, so the budget won't be saved. use \
+                      'quit!' to leave without saving";
+
+/// What running a line of the shell did to the budget.
+enum Outcome {
+    /// The budget wasn't changed
+    Unchanged,
+    /// The budget may have been changed
+    Changed,
+    /// The command failed in a way which could have left the budget half changed
+    Failed,
+}
+
+/// Run a line of the shell as a command.
+fn run_line(budget: &mut Budget, file: &mut BudgetFile, config: &Config, line: &str) -> Outcome {
+    let words = match shell_words::split(line) {
+        Ok(words) => words,
+        Err(e) => {
+            eprintln!("unable to read the command: {}", e);
+            return Outcome::Unchanged;
+        }
+    };
+    let args = std::iter::once("budget".to_string()).chain(words);
+    let matches = match app::get_app().get_matches_from_safe(args) {
+        Ok(matches) => matches,
+        Err(e) => {
+            if e.use_stderr() {
+                eprintln!("{}", e.message);
+            } else {
+                println!("{}", e.message);
+            }
+            return Outcome::Unchanged;
+        }
+    };
+    let name = matches.subcommand_name().unwrap_or_default();
+    if OUTSIDE_COMMANDS.contains(&name) {
+        eprintln!("'{}' can't be used in the shell", name);
+        return Outcome::Unchanged;
+    }
+    // the full name of the command, such as 'payee list'
+    let mut path = vec![];
+    let mut command = &matches;
+    while let (name, Some(submatches)) = command.subcommand() {
+        path.push(name);
+        command = submatches;
+    }
+    let changed = !SHOWING_COMMANDS.contains(&path.join(" ").as_str());
+
+    // a copy of the budget, to put back if the command is abandoned part way through
+    let before = if changed {
+        serde_yaml::to_value(&*budget).ok()
+    } else {
+        None
+    };
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        app::run(budget, file, config, &matches)
+    }));
+    match result {
+        Err(payload) if !payload.is::<Abandoned>() => {
+            // the command has already said why it panicked; the log file is never
+            // encrypted, so it only gets the name of the command, and only when the
+            // budget itself isn't encrypted either
+            if !file.is_encrypted() {
+                log::error!("the command '{}' failed", path.join(" "));
+            }
+            Outcome::Failed
+        }
+        Err(_) if changed => undo(budget, before),
+        _ if changed => Outcome::Changed,
+        _ => Outcome::Unchanged,
+    }
+}
+
+/// Put the budget back as it was before a command which was abandoned after it
+/// had reported an error. If the budget can't be put back, the command is treated
+/// as having failed part way through.
+fn undo(budget: &mut Budget, before: Option<serde_yaml::Value>) -> Outcome {
+    let Some(before) = before else {
+        eprintln!("{}", FAILED);
+        return Outcome::Failed;
+    };
+    if serde_yaml::to_value(&*budget).ok().as_ref() == Some(&before) {
+        return Outcome::Unchanged;
+    }
+    match serde_yaml::from_value(before) {
+        Ok(b) => {
+            *budget = b;
+            eprintln!("the command failed, so its changes to the budget were undone");
+            Outcome::Unchanged
+        }
+        Err(e) => {
+            log::error!("unable to undo the changes of a failed command: {}", e);
+            eprintln!("{}", FAILED);
+            Outcome::Failed
+        }
+    }
+}
+
+/// Save the budget, returning whether it was saved.
+fn save(budget: &Budget, file: &BudgetFile) -> bool {
+    match file.save(budget) {
+        Ok(()) => {
+            println!("saved {:?}", file.path());
+            true
+        }
+        Err(e) => {
+            eprintln!("unable to save budget {:?}: {}", file.path(), e);
+            false
+        }
+    }
+}
+
+/// The subcommands and long options of a command, to complete them.
+#[derive(Default)]
+struct Commands {
+    longs: Vec<String>,
+    subcommands: Vec<(String, Commands)>,
+}
+
+impl Commands {
+    /// Read the subcommands and options of the command at `path`, and of all its
+    /// subcommands, from their help. Clap has no public way to list them.
+    fn of(path: &mut Vec<String>) -> Commands {
+        let args = std::iter::once("budget")
+            .chain(path.iter().map(String::as_str))
+            .chain(std::iter::once("--help"));
+        let help = match app::get_app().get_matches_from_safe(args) {
+            Err(e) if e.kind == clap::ErrorKind::HelpDisplayed => e.message,
+            _ => return Commands::default(),
+        };
+
+        let mut commands = Commands::default();
+        let mut section = "";
+        for line in help.lines() {
+            // the items of a section are indented by four spaces, and anything
+            // indented further is the rest of a description
+            let item = match line.strip_prefix("    ") {
+                Some(item) if !item.starts_with(' ') => item,
+                Some(_) => continue,
+                None => {
+                    section = line;
+                    continue;
+                }
+            };
+            let spec = item.split("  ").next().unwrap_or_default();
+            match section {
+                "FLAGS:" | "OPTIONS:" => commands.longs.extend(
+                    spec.split_whitespace()
+                        .filter(|w| w.starts_with("--"))
+                        .map(|w| w.trim_end_matches(',').to_string()),
+                ),
+                // 'help' is only the help of the other commands
+                "SUBCOMMANDS:" if spec == "help" => commands
+                    .subcommands
+                    .push((spec.to_string(), Commands::default())),
+                "SUBCOMMANDS:" => {
+                    path.push(spec.to_string());
+                    let subcommands = Commands::of(path);
+                    path.pop();
+                    commands.subcommands.push((spec.to_string(), subcommands));
+                }
+                _ => (),
+            }
+        }
+        commands
+    }
+}
+
+/// Completes the names of commands and arguments, and of the categories,
+/// payees, accounts and tags in the budget.
+struct ShellHelper {
+    commands: Commands,
+    names: Vec<String>,
+    payees: Vec<String>,
+    tags: Vec<String>,
+}
+
+impl ShellHelper {
+    fn new(budget: &Budget) -> Self {
+        let mut helper = ShellHelper {
+            commands: Commands::of(&mut Vec::new()),
+            names: Vec::new(),
+            payees: Vec::new(),
+            tags: Vec::new(),
+        };
+        helper.refresh(budget);
+        helper
+    }
+
+    /// Take the names to complete from the budget, after a command has changed it.
+    fn refresh(&mut self, budget: &Budget) {
+        self.payees = budget
+            .payee_stats()
+            .into_iter()
+            .map(|s| s.payee)
+            .chain(budget.payees().map(|p| p.name().to_string()))
+            .collect();
+        self.tags = budget.tag_stats().into_iter().map(|s| s.tag).collect();
+        self.names = budget
+            .categories()
+            .filter(|c| !c.hidden())
+            .map(|c| budget.category_path(c))
+            .chain(budget.accounts().map(|a| a.name().to_string()))
+            .chain(budget.master_categories().map(|m| m.name().to_string()))
+            .chain(self.payees.iter().cloned())
+            .collect();
+        for names in [&mut self.names, &mut self.payees, &mut self.tags] {
+            names.sort();
+            names.dedup();
+        }
+    }
+
+    /// The words which could complete `word`, given the words before it.
+    fn candidates(&self, before: &[&str], word: &str) -> Vec<String> {
+        // find the subcommand being typed
+        let mut commands = &self.commands;
+        let mut arguments = 0;
+        for w in before {
+            match commands.subcommands.iter().find(|(name, _)| name == w) {
+                Some((_, s)) if arguments == 0 => commands = s,
+                _ => arguments += 1,
+            }
+        }
+
+        let matching = |options: &mut dyn Iterator<Item = String>, prefix: &str| {
+            let prefix = prefix.to_lowercase();
+            options
+                .filter(|o| o.to_lowercase().starts_with(&prefix))
+                .collect::<Vec<_>>()
+        };
+        if let Some(long) = word.strip_prefix("--") {
+            matching(&mut commands.longs.iter().cloned(), &format!("--{}", long))
+        } else if let Some(payee) = word.strip_prefix('@') {
+            let mut payees = self.payees.iter().map(|p| format!("@{}", p));
+            matching(&mut payees, &format!("@{}", payee))
+        } else if let Some(tag) = word.strip_prefix('#') {
+            let mut tags = self.tags.iter().map(|t| format!("#{}", t));
+            matching(&mut tags, &format!("#{}", tag))
+        } else if arguments == 0 && !commands.subcommands.is_empty() {
+            let mut names = commands.subcommands.iter().map(|(name, _)| name.clone());
+            if before.is_empty() {
+                let shell = ["save", "exit", "quit!"].iter().map(|c| c.to_string());
+                matching(&mut names.chain(shell), word)
+            } else {
+                matching(&mut names, word)
+            }
+        } else {
+            matching(&mut self.names.iter().cloned(), word)
+                .into_iter()
+                .map(|n| match n.contains(' ') {
+                    true => format!("'{}'", n),
+                    false => n,
+                })
+                .collect()
+        }
+    }
+}
+
+impl Completer for ShellHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let before: Vec<&str> = line[..start].split_whitespace().collect();
+        let word = line[start..].trim_start_matches(['\'', '"']);
+        Ok((start, self.candidates(&before, word)))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completing() {
+        let mut budget = Budget::with_categories(vec!["groceries", "eating out"]);
+        budget
+            .add(budget_lib::Transaction::new(-5).with_payee(Some("Tesco")))
+            .unwrap();
+        let helper = ShellHelper::new(&budget);
+
+        assert_eq!(helper.candidates(&[], "sum"), vec!["summary"]);
+        assert_eq!(helper.candidates(&["category"], "ren"), vec!["rename"]);
+        assert_eq!(
+            helper.candidates(&["assign", "10"], "eat"),
+            vec!["'eating out'"]
+        );
+        assert_eq!(helper.candidates(&["5"], "@te"), vec!["@Tesco"]);
+        assert_eq!(
+            helper.candidates(&["transaction", "add"], "--acc"),
+            vec!["--account"]
+        );
+        // the options of the budget are accepted after any command
+        assert_eq!(helper.candidates(&["summary"], "--bud"), vec!["--budget"]);
+        assert_eq!(helper.candidates(&["schedule"], "sk"), vec!["skip"]);
+    }
+
+    #[test]
+    fn tracking_changes() {
+        let mut budget = Budget::with_categories(vec!["food"]);
+        let mut file = BudgetFile::new("unused", None);
+        let config = Config::default();
+        let mut run = |line| run_line(&mut budget, &mut file, &config, line);
+
+        assert!(matches!(run("report spending"), Outcome::Unchanged));
+        assert!(matches!(run("payee list"), Outcome::Unchanged));
+        assert!(matches!(run("category add fuel"), Outcome::Changed));
+
+        // every showing command is a command
+        for command in SHOWING_COMMANDS {
+            let args = std::iter::once("budget")
+                .chain(command.split(' '))
+                .chain(std::iter::once("--help"));
+            let e = app::get_app().get_matches_from_safe(args).unwrap_err();
+            assert_eq!(e.kind, clap::ErrorKind::HelpDisplayed, "{}", command);
+        }
+    }
+
+    #[test]
+    fn undoing_abandoned_commands() {
+        let mut budget = Budget::with_categories(vec!["food"]);
+        let before = serde_yaml::to_value(&budget).ok();
+        assert!(matches!(
+            undo(&mut budget, before.clone()),
+            Outcome::Unchanged
+        ));
+
+        budget.add_category("fuel").unwrap();
+        assert!(matches!(undo(&mut budget, before), Outcome::Unchanged));
+        assert!(budget.category("fuel").is_none());
+        assert!(budget.category("food").is_some());
+        assert!(matches!(undo(&mut budget, None), Outcome::Failed));
+    }
+}
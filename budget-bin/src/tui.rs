@@ -0,0 +1,398 @@
+//! A full-screen interface for budgeting a month at a time.
+//!
+//! The grid shows each category for the month, and money is assigned by editing
+//! the amount budgeted. The register lists the transactions in the month, which
+//! can be filtered and edited a cell at a time.
+
+mod grid;
+mod register;
+
+use crate::config::Config;
+use crate::names;
+use budget_lib::{Budget, CalendarMonth, Money};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, TableState};
+use ratatui::Frame;
+use std::io;
+use uuid::Uuid;
+
+/// The shortcuts shown at the bottom of the screen.
+const KEYS: &str = "tab switch pane · [ ] month · t this month · ↑↓ move · enter edit · \
+                    / filter · f fund goals · q quit";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Pane {
+    Grid,
+    Register,
+}
+
+/// What the text being entered at the bottom of the screen is for.
+#[derive(Clone, Debug, PartialEq)]
+enum Prompt {
+    /// The amount budgeted for the category with this path.
+    Budgeted(String),
+    /// A column of the transaction with this uuid.
+    Cell(Uuid, usize),
+    /// The filter of the register, which is applied as it is typed.
+    Filter,
+}
+
+/// The state of the interface, which changes the budget as keys are pressed.
+pub struct State<'a> {
+    budget: &'a mut Budget,
+    config: &'a Config,
+    month: CalendarMonth,
+    pane: Pane,
+    grid: TableState,
+    register: TableState,
+    filter: String,
+    prompt: Option<(Prompt, String)>,
+    message: Option<String>,
+    quit: bool,
+}
+
+/// Run the interface until it is quit, starting at the given month.
+pub fn run(budget: &mut Budget, config: &Config, month: CalendarMonth) -> io::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let mut state = State::new(budget, config, month);
+    let result = (|| {
+        while !state.quit {
+            terminal.draw(|frame| state.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    state.handle(key);
+                }
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    result
+}
+
+impl<'a> State<'a> {
+    fn new(budget: &'a mut Budget, config: &'a Config, month: CalendarMonth) -> Self {
+        let mut state = State {
+            budget,
+            config,
+            month,
+            pane: Pane::Grid,
+            grid: TableState::new().with_selected_column(grid::BUDGETED),
+            register: TableState::new().with_selected_column(0),
+            filter: String::new(),
+            prompt: None,
+            message: None,
+            quit: false,
+        };
+        state.move_selection(0);
+        state
+    }
+
+    /// Respond to a key being pressed.
+    fn handle(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        if self.prompt.is_some() {
+            self.handle_prompt(key);
+            return;
+        }
+
+        self.message = None;
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.pane = match self.pane {
+                    Pane::Grid => Pane::Register,
+                    Pane::Register => Pane::Grid,
+                };
+                self.move_selection(0);
+            }
+            KeyCode::Char('[') | KeyCode::PageUp => self.set_month(self.month.pred()),
+            KeyCode::Char(']') | KeyCode::PageDown => self.set_month(self.month.succ()),
+            KeyCode::Char('t') => self.set_month(self.budget.month_of(chrono::Local::now())),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Home => self.move_selection(isize::MIN),
+            KeyCode::End => self.move_selection(isize::MAX),
+            KeyCode::Left | KeyCode::Char('h') if self.pane == Pane::Register => {
+                self.register.select_previous_column()
+            }
+            KeyCode::Right | KeyCode::Char('l') if self.pane == Pane::Register => {
+                // the table doesn't know how many columns it has until it is drawn
+                let column = self.register.selected_column().unwrap_or(0);
+                let last = register::COLUMNS.len() - 1;
+                self.register.select_column(Some((column + 1).min(last)));
+            }
+            KeyCode::Enter | KeyCode::Char('e') => self.edit(),
+            KeyCode::Char('/') => {
+                self.pane = Pane::Register;
+                self.prompt = Some((Prompt::Filter, self.filter.clone()));
+            }
+            KeyCode::Esc if !self.filter.is_empty() => self.set_filter(String::new()),
+            KeyCode::Char('f') => self.fund_goals(),
+            _ => {}
+        }
+    }
+
+    fn handle_prompt(&mut self, key: KeyEvent) {
+        let (prompt, input) = self.prompt.as_mut().unwrap();
+        match key.code {
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Esc => {
+                if *prompt == Prompt::Filter {
+                    self.set_filter(String::new());
+                }
+                self.prompt = None;
+                return;
+            }
+            KeyCode::Enter => {
+                let (prompt, input) = self.prompt.take().unwrap();
+                self.submit(prompt, &input);
+                return;
+            }
+            _ => {}
+        }
+        if *prompt == Prompt::Filter {
+            let filter = input.clone();
+            self.set_filter(filter);
+        }
+    }
+
+    /// Start editing the selected cell.
+    fn edit(&mut self) {
+        match self.pane {
+            Pane::Grid => {
+                let rows = grid::rows(self.budget, self.month);
+                let row = self.grid.selected().and_then(|i| rows.get(i));
+                if let Some(grid::GridRow {
+                    category: Some(category),
+                    budgeted,
+                    ..
+                }) = row
+                {
+                    let amount = self.config.format_amount(*budgeted, self.budget.currency());
+                    self.prompt = Some((Prompt::Budgeted(category.clone()), amount));
+                }
+            }
+            Pane::Register => {
+                let transactions = register::transactions(self.budget, self.month, &self.filter);
+                let column = self.register.selected_column().unwrap_or(0);
+                if let Some(t) = self.register.selected().and_then(|i| transactions.get(i)) {
+                    let text = register::text(self.budget, t, column);
+                    self.prompt = Some((Prompt::Cell(*t.uuid(), column), text));
+                }
+            }
+        }
+    }
+
+    /// Apply the text entered at a prompt.
+    fn submit(&mut self, prompt: Prompt, input: &str) {
+        let result = match prompt {
+            Prompt::Budgeted(category) => self.set_budgeted(&category, input),
+            Prompt::Cell(uuid, column) => {
+                register::edit(self.budget, self.config, &uuid, column, input)
+            }
+            Prompt::Filter => Ok(()),
+        };
+        if let Err(e) = result {
+            self.message = Some(e);
+        }
+        self.move_selection(0);
+    }
+
+    /// Assign money to a category, so that the amount budgeted for the month is
+    /// the amount entered.
+    fn set_budgeted(&mut self, category: &str, input: &str) -> Result<(), String> {
+        let currency = self.budget.currency();
+        let amount = Money::<decimal::d128>::parse(input.trim(), currency, &self.config.locale())
+            .map_err(|e| e.to_string())?
+            .amount();
+        let category = names::category(self.budget, category).map_err(|e| e.to_string())?;
+        let difference = amount - self.budget.allocation(&category, self.month);
+        self.budget
+            .assign(category.as_str(), difference, self.month)
+            .map_err(|e| e.to_string())
+    }
+
+    fn fund_goals(&mut self) {
+        let funded = self.budget.fund_goals(self.month);
+        let total = funded
+            .iter()
+            .fold(decimal::d128::zero(), |total, (_, amount)| total + *amount);
+        self.message = Some(match funded.len() {
+            0 => format!("no goals need funding in {}", self.month),
+            n => format!(
+                "assigned {} to {} goals",
+                self.config.format_amount(total, self.budget.currency()),
+                n
+            ),
+        });
+    }
+
+    fn set_month(&mut self, month: CalendarMonth) {
+        self.month = month;
+        self.register.select(None);
+        self.move_selection(0);
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.register.select(None);
+        self.move_selection(0);
+    }
+
+    /// Move the selection of the current pane by a number of rows, keeping it on
+    /// a row which can be edited.
+    fn move_selection(&mut self, by: isize) {
+        let (state, editable) = match self.pane {
+            Pane::Grid => {
+                let rows = grid::rows(self.budget, self.month);
+                let editable: Vec<bool> = rows.iter().map(|r| r.category.is_some()).collect();
+                (&mut self.grid, editable)
+            }
+            Pane::Register => {
+                let count = register::transactions(self.budget, self.month, &self.filter).len();
+                (&mut self.register, vec![true; count])
+            }
+        };
+        state.select(select(&editable, state.selected(), by));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [title, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+
+        let available = self.budget.available(self.month);
+        let available = self.config.format_amount(available, self.budget.currency());
+        frame.render_widget(
+            Line::from(format!(
+                " {}    available to assign: {}",
+                self.month, available
+            ))
+            .bold(),
+            title,
+        );
+
+        let focused = Style::new().cyan();
+        match self.pane {
+            Pane::Grid => {
+                let rows = grid::rows(self.budget, self.month);
+                let block = Block::bordered().title(" budget ").border_style(focused);
+                let table = grid::table(&rows, self.budget, self.config, block);
+                frame.render_stateful_widget(table, body, &mut self.grid);
+            }
+            Pane::Register => {
+                let transactions = register::transactions(self.budget, self.month, &self.filter);
+                let title = match self.filter.as_str() {
+                    "" => " transactions ".to_string(),
+                    filter => format!(" transactions matching '{}' ", filter),
+                };
+                let block = Block::bordered().title(title).border_style(focused);
+                let table = register::table(&transactions, self.budget, self.config, block);
+                frame.render_stateful_widget(table, body, &mut self.register);
+            }
+        }
+
+        let status = match (&self.prompt, &self.message) {
+            (Some((prompt, input)), _) => {
+                let label = match prompt {
+                    Prompt::Budgeted(category) => format!("budgeted for {}", category),
+                    Prompt::Cell(_, column) => register::COLUMNS[*column].to_string(),
+                    Prompt::Filter => "filter".to_string(),
+                };
+                Line::from(format!("{}: {}█", label, input))
+            }
+            (None, Some(message)) => Line::from(message.as_str()).yellow(),
+            (None, None) => Line::from(""),
+        };
+        frame.render_widget(Paragraph::new(vec![status, Line::from(KEYS).dim()]), footer);
+    }
+}
+
+/// The row to select after moving the selection by a number of rows, skipping
+/// the rows which can't be edited. Moves beyond the first or last row stop there.
+fn select(editable: &[bool], selected: Option<usize>, by: isize) -> Option<usize> {
+    let rows: Vec<usize> = (0..editable.len()).filter(|&i| editable[i]).collect();
+    let current = match selected {
+        // the nearest editable row at or after the selection
+        Some(s) => rows
+            .iter()
+            .position(|&r| r >= s)
+            .unwrap_or(rows.len().saturating_sub(1)),
+        None => 0,
+    };
+    let last = rows.len().checked_sub(1)? as isize;
+    let index = (current as isize).saturating_add(by).clamp(0, last);
+    Some(rows[index as usize])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(state: &mut State, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\t' => KeyCode::Tab,
+                '\u{8}' => KeyCode::Backspace,
+                c => KeyCode::Char(c),
+            };
+            state.handle(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn selecting_rows() {
+        let editable = [false, true, true, false, true];
+        assert_eq!(select(&editable, None, 0), Some(1));
+        assert_eq!(select(&editable, Some(2), 1), Some(4));
+        assert_eq!(select(&editable, Some(4), 1), Some(4));
+        assert_eq!(select(&editable, Some(4), isize::MIN), Some(1));
+        assert_eq!(select(&[], None, 1), None);
+    }
+
+    #[test]
+    fn assigning_and_editing() {
+        let mut budget = Budget::with_categories(vec!["food", "rent"]);
+        let month = CalendarMonth::from(chrono::Local::now());
+        let config = Config::default();
+        let mut state = State::new(&mut budget, &config, month);
+
+        // set the amount budgeted for rent, replacing the amount shown
+        press(&mut state, "j\n");
+        assert!(matches!(state.prompt, Some((Prompt::Budgeted(_), _))));
+        state.prompt.as_mut().unwrap().1.clear();
+        press(&mut state, "250\n");
+        assert_eq!(state.budget.allocation("rent", month).to_string(), "250");
+        assert_eq!(state.message, None);
+
+        // move a transaction into a category named by its start
+        state.budget.add(budget_lib::Transaction::new(-20)).unwrap();
+        press(&mut state, "\tlll\n");
+        press(&mut state, "fo\n");
+        let t = state.budget.ledger().into_iter().next().unwrap();
+        assert_eq!(state.budget.category_name(t).as_deref(), Some("food"));
+
+        // a misspelt category is refused
+        press(&mut state, "\n");
+        state.prompt.as_mut().unwrap().1.clear();
+        press(&mut state, "rnt\n");
+        assert_eq!(
+            state.message.as_deref(),
+            Some("no category named 'rnt'. did you mean 'rent'?")
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! The grid of categories for a month, showing the money rolled over from the
+//! month before, the money budgeted, the money spent and the money remaining.
+
+use crate::config::Config;
+use budget_lib::{Budget, CalendarMonth};
+use decimal::d128;
+use ratatui::layout::Constraint;
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Text;
+use ratatui::widgets::{Block, Cell, Row, Table};
+
+/// The column of the amount budgeted, which is edited to assign money.
+pub const BUDGETED: usize = 2;
+
+/// A row of the grid, which is either the heading of a group or a category.
+pub struct GridRow {
+    pub label: String,
+    /// The path of the category, or None for the heading of a group.
+    pub category: Option<String>,
+    pub rollover: d128,
+    pub budgeted: d128,
+    pub spent: d128,
+    pub remaining: d128,
+}
+
+/// The rows of the grid for a month, in the order of the summary.
+pub fn rows(budget: &Budget, month: CalendarMonth) -> Vec<GridRow> {
+    let groups = budget.grouped_categories();
+    // only show the groups if the budget has any
+    let headings = budget.master_categories().next().is_some();
+
+    let mut rows = Vec::new();
+    for (master, categories) in groups {
+        // hidden categories are left out, along with groups of only hidden categories
+        let visible: Vec<_> = categories.iter().filter(|c| !c.hidden()).collect();
+        if visible.is_empty() && !categories.is_empty() {
+            continue;
+        }
+        let mut category_rows: Vec<GridRow> = visible
+            .iter()
+            .map(|c| {
+                let path = budget.category_path(c);
+                let indent = budget.category_depth(c) + usize::from(headings);
+                let label = format!("{}{}", "  ".repeat(indent), c.name());
+                row(budget, label, path, month)
+            })
+            .collect();
+
+        if headings {
+            // subcategories are already counted in their parents
+            let mut heading = GridRow {
+                label: master.map_or("(no group)", |m| m.name()).to_string(),
+                category: None,
+                rollover: d128::zero(),
+                budgeted: d128::zero(),
+                spent: d128::zero(),
+                remaining: d128::zero(),
+            };
+            for (c, r) in visible.iter().zip(&category_rows) {
+                if budget.category_depth(c) == 0 {
+                    heading.rollover += r.rollover;
+                    heading.budgeted += r.budgeted;
+                    heading.spent += r.spent;
+                    heading.remaining += r.remaining;
+                }
+            }
+            rows.push(heading);
+        }
+        rows.append(&mut category_rows);
+    }
+    rows
+}
+
+fn row(budget: &Budget, label: String, category: String, month: CalendarMonth) -> GridRow {
+    let rollover = budget.category_balance(&category, month.pred());
+    let budgeted = budget.allocation(&category, month);
+    let remaining = budget.category_balance(&category, month);
+    GridRow {
+        label,
+        rollover,
+        budgeted,
+        // whatever isn't rolled over or budgeted was spent
+        spent: rollover + budgeted - remaining,
+        remaining,
+        category: Some(category),
+    }
+}
+
+/// The table which draws the grid.
+pub fn table<'a>(
+    rows: &[GridRow],
+    budget: &Budget,
+    config: &Config,
+    block: Block<'a>,
+) -> Table<'a> {
+    let amount = |x: d128| {
+        let cell =
+            Cell::from(Text::from(config.format_amount(x, budget.currency())).right_aligned());
+        if x.is_negative() {
+            cell.red()
+        } else {
+            cell
+        }
+    };
+    let body = rows.iter().map(|r| {
+        let row = Row::new(vec![
+            Cell::from(r.label.clone()),
+            amount(r.rollover),
+            amount(r.budgeted),
+            amount(r.spent),
+            amount(r.remaining),
+        ]);
+        match r.category {
+            Some(_) => row,
+            None => row.add_modifier(Modifier::BOLD),
+        }
+    });
+    let header = ["category", "rollover", "budgeted", "spent", "remaining"]
+        .iter()
+        .enumerate()
+        .map(|(i, h)| match i {
+            0 => Cell::from(*h),
+            _ => Cell::from(Text::from(*h).right_aligned()),
+        });
+
+    Table::new(
+        body,
+        [
+            Constraint::Min(20),
+            Constraint::Length(14),
+            Constraint::Length(14),
+            Constraint::Length(14),
+            Constraint::Length(14),
+        ],
+    )
+    .header(Row::new(header).underlined())
+    .block(block)
+    .row_highlight_style(Style::new().reversed())
+    // the budgeted amount is the cell which can be edited
+    .cell_highlight_style(Style::new().bold().underlined())
+}
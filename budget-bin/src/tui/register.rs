@@ -0,0 +1,178 @@
+//! The register of the transactions in a month, which can be filtered and
+//! edited a cell at a time.
+
+use crate::config::Config;
+use crate::dates;
+use crate::names;
+use budget_lib::{Budget, CalendarMonth, Money, Transaction};
+use decimal::d128;
+use ratatui::layout::Constraint;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Text;
+use ratatui::widgets::{Block, Cell, Row, Table};
+use uuid::Uuid;
+
+/// The columns of the register, each of which can be edited.
+pub const COLUMNS: [&str; 7] = [
+    "date",
+    "amount",
+    "account",
+    "category",
+    "payee",
+    "description",
+    "tags",
+];
+const DATE: usize = 0;
+const AMOUNT: usize = 1;
+const ACCOUNT: usize = 2;
+const CATEGORY: usize = 3;
+const PAYEE: usize = 4;
+const DESCRIPTION: usize = 5;
+const TAGS: usize = 6;
+
+/// The transactions in a month which match a filter, in order of date.
+///
+/// A transaction matches if the filter, ignoring case, is part of its account,
+/// category, payee, description or one of its tags.
+pub fn transactions<'a>(
+    budget: &'a Budget,
+    month: CalendarMonth,
+    filter: &str,
+) -> Vec<&'a Transaction<d128>> {
+    let filter = filter.to_lowercase();
+    budget
+        .ledger()
+        .into_iter()
+        .filter(|t| budget.month_of(t.date().with_timezone(&chrono::Local)) == month)
+        .filter(|t| {
+            filter.is_empty()
+                || [ACCOUNT, CATEGORY, PAYEE, DESCRIPTION, TAGS]
+                    .iter()
+                    .any(|&c| text(budget, t, c).to_lowercase().contains(&filter))
+        })
+        .collect()
+}
+
+/// The text of a cell, as it is edited.
+pub fn text(budget: &Budget, t: &Transaction<d128>, column: usize) -> String {
+    match column {
+        DATE => t
+            .date()
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d")
+            .to_string(),
+        AMOUNT => t.amount().to_string(),
+        ACCOUNT => t.account().clone().unwrap_or_default(),
+        CATEGORY => budget.category_name(t).unwrap_or_default(),
+        PAYEE => t.payee().clone().unwrap_or_default(),
+        DESCRIPTION => t.description().clone().unwrap_or_default(),
+        _ => t.tags().cloned().collect::<Vec<_>>().join(" "),
+    }
+}
+
+/// Change a cell of the transaction with the given uuid to the text which was
+/// entered. An empty cell clears everything but the date and the amount.
+///
+/// Returns an error describing why the text was refused.
+pub fn edit(
+    budget: &mut Budget,
+    config: &Config,
+    uuid: &Uuid,
+    column: usize,
+    input: &str,
+) -> Result<(), String> {
+    let input = input.trim();
+    let t = budget
+        .ledger()
+        .into_iter()
+        .find(|t| t.uuid() == uuid)
+        .ok_or("the transaction no longer exists")?;
+    if t.is_transfer() && (column == DATE || column == AMOUNT || column == ACCOUNT) {
+        return Err("use 'budget account transfer' to change a transfer".to_string());
+    }
+    let optional = Some(input).filter(|s| !s.is_empty());
+
+    let mut edited = t.clone();
+    match column {
+        DATE => {
+            let today = chrono::Local::now().date_naive();
+            let date =
+                dates::parse_date(input, today, &config.calendar()).map_err(|e| e.to_string())?;
+            edited.set_date_transaction(Some(dates::midday(date)));
+        }
+        AMOUNT => {
+            let currency = budget.account_currency(t.account().as_deref());
+            let amount = Money::<d128>::parse(input, currency, &config.locale())
+                .map_err(|e| e.to_string())?;
+            edited.set_amount(amount.amount());
+        }
+        ACCOUNT => {
+            let account = optional.map(|a| names::account(budget, a)).transpose();
+            edited.set_account(account.map_err(|e| e.to_string())?);
+        }
+        CATEGORY => {
+            let category = optional.map(|c| names::category(budget, c)).transpose();
+            edited.set_category(category.map_err(|e| e.to_string())?);
+        }
+        PAYEE => edited.set_payee(optional),
+        DESCRIPTION => edited.set_description(optional),
+        _ => edited.set_tags(input.split_whitespace().collect()),
+    }
+    budget
+        .edit_transaction(uuid, |t| *t = edited)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// The table which draws the register.
+pub fn table<'a>(
+    transactions: &[&Transaction<d128>],
+    budget: &Budget,
+    config: &Config,
+    block: Block<'a>,
+) -> Table<'a> {
+    let body = transactions.iter().map(|t| {
+        let currency = budget.account_currency(t.account().as_deref());
+        let amount =
+            Cell::from(Text::from(config.format_amount(*t.amount(), currency)).right_aligned());
+        Row::new(vec![
+            Cell::from(
+                t.date()
+                    .with_timezone(&chrono::Local)
+                    .format(config.display_date_format())
+                    .to_string(),
+            ),
+            if t.amount().is_negative() {
+                amount.red()
+            } else {
+                amount.green()
+            },
+            Cell::from(text(budget, t, ACCOUNT)),
+            Cell::from(text(budget, t, CATEGORY)),
+            Cell::from(text(budget, t, PAYEE)),
+            Cell::from(text(budget, t, DESCRIPTION)),
+            Cell::from(text(budget, t, TAGS)),
+        ])
+    });
+    let header = COLUMNS.iter().enumerate().map(|(i, h)| match i {
+        AMOUNT => Cell::from(Text::from(*h).right_aligned()),
+        _ => Cell::from(*h),
+    });
+
+    Table::new(
+        body,
+        [
+            Constraint::Length(10),
+            Constraint::Length(12),
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+            Constraint::Fill(3),
+            Constraint::Fill(2),
+        ],
+    )
+    .header(Row::new(header).underlined())
+    .block(block)
+    .row_highlight_style(Style::new().reversed())
+    .cell_highlight_style(Style::new().bold().underlined())
+}
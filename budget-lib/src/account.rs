@@ -0,0 +1,91 @@
+//! # Account.
+
+use crate::{Currency, CurrencyCode};
+use chrono::NaiveDate;
+use decimal::d128;
+use serde_derive::{Deserialize, Serialize};
+
+/// An account which holds money, such as a bank account or a credit card.
+///
+/// Transactions refer to their account by name. The amounts of transactions in
+/// an account are in the currency of the account, which is the currency of the
+/// budget unless otherwise set.
+///
+/// An account may be 'off-budget', for tracking assets and debts such as
+/// investments or a mortgage. Off-budget accounts count towards net worth, but
+/// their transactions aren't income or spending.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<CurrencyCode>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    off_budget: bool,
+}
+
+impl Account {
+    /// Create a new account, in the currency of the budget.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Account {
+            name: name.into(),
+            currency: None,
+            off_budget: false,
+        }
+    }
+
+    /// Inline method for setting the currency of an account.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::Account;
+    ///
+    /// let a = Account::new("euro savings").with_currency("EUR".parse().unwrap());
+    ///
+    /// assert_eq!(a.currency().unwrap().as_str(), "EUR");
+    /// ```
+    pub fn with_currency(mut self, currency: CurrencyCode) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Inline method for making the account an off-budget, tracking account.
+    pub fn off_budget(mut self) -> Self {
+        self.off_budget = true;
+        self
+    }
+
+    /// Returns true if the account is off-budget.
+    pub fn is_off_budget(&self) -> bool {
+        self.off_budget
+    }
+
+    /// Returns the name of the account.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the currency of the account, if it differs from the budget currency.
+    pub fn currency(&self) -> Option<CurrencyCode> {
+        self.currency
+    }
+}
+
+/// The value of an account on a date, for assets whose value isn't driven by
+/// transactions, such as a house or an investment fund.
+///
+/// The balance of an account is its most recent valuation, plus the transactions
+/// since then.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Valuation<C = d128>
+where
+    C: Currency,
+{
+    /// The name of the account
+    pub account: String,
+    /// The date of the valuation
+    pub date: NaiveDate,
+    /// The value of the account, in the currency of the account
+    pub value: C,
+}
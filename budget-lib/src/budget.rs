@@ -1,15 +1,32 @@
+use crate::payee::{fold_case, same_name};
+use crate::transaction::{
+    is_within_tag, rename_tag, tag_ancestors, CategoryRef, Source, TAG_SEPARATOR,
+};
 use crate::Currency;
-use crate::{CalendarMonth, Ledger, Transaction};
-use chrono::Datelike;
+use crate::{
+    Account, CalendarMonth, CurrencyCode, ExchangeRate, ExchangeRates, Goal, GoalKind, GoalStatus,
+    InvalidSplitError, Ledger, Pattern, Payee, PayeeError, PayeeStats, Rule, ScheduledTransaction,
+    Transaction, Valuation,
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use decimal::d128;
 use serde_derive::{Deserialize, Serialize};
 use std::borrow::Cow;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
 use uuid::Uuid;
 
 type CategoryID = Uuid;
 
+/// An envelope budget.
+///
+/// A Budget holds a [`Ledger`] of transactions, along with the categories
+/// those transactions are assigned to and the amounts allocated to each
+/// category, month by month.
 #[derive(Default, Debug, Serialize, Deserialize)]
+// the budget is upgraded as it is loaded, so see the impl of Deserialize below
+#[serde(remote = "Self")]
 pub struct Budget<C = d128>
 where
     C: Currency,
@@ -25,102 +42,2228 @@ where
     allocations: BTreeMap<(CalendarMonth, CategoryID), Allocation<C>>,
 
     /// A map of transactions summaries. The key is a tuple of Calendar Month, and a category ID.
+    /// Summaries are in the currency of the budget.
     summaries: BTreeMap<(CalendarMonth, CategoryID), Summary<C>>,
     uncategorised_summaries: BTreeMap<CalendarMonth, Summary<C>>,
+
+    /// Transactions which are left out of the summaries, because there is no
+    /// exchange rate to convert them into the currency of the budget
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    unconverted: BTreeSet<Uuid>,
+
+    /// The currency that the budget is kept in
+    #[serde(default)]
+    currency: CurrencyCode,
+
+    /// The day of the month that budget months start on, if not the 1st
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    first_day_of_month: Option<u32>,
+
+    /// The accounts that transactions are drawn from, keyed by name
+    #[serde(default)]
+    accounts: BTreeMap<String, Account>,
+
+    /// Exchange rates for converting transactions in foreign currency accounts into the budget currency
+    #[serde(default)]
+    rates: ExchangeRates<C>,
+
+    /// Valuations of accounts, in order of account and date
+    #[serde(default)]
+    valuations: Vec<Valuation<C>>,
+
+    /// Transactions which recur on a schedule
+    #[serde(default)]
+    schedules: Vec<ScheduledTransaction<C>>,
+
+    /// The goals of each category
+    #[serde(default)]
+    goals: BTreeMap<CategoryID, Goal<C>>,
+
+    /// Rules for categorising transactions, in the order they are applied
+    #[serde(default)]
+    rules: Vec<Rule<C>>,
+
+    /// The registered payees, keyed by name
+    #[serde(default)]
+    payees: BTreeMap<String, Payee>,
 }
 
 impl<C> Budget<C>
 where
     C: Currency,
 {
+    /// Create a new, empty budget with the given categories.
+    ///
+    /// A budget created with `Budget::default()` has a handful of common categories.
+    /// This method can be used to start with a different set.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::Budget;
+    ///
+    /// let budget: Budget = Budget::with_categories(vec!["rent", "groceries"]);
+    ///
+    /// assert_eq!(budget.categories().count(), 2);
+    /// ```
+    pub fn with_categories<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut categories = Categories {
+            categories: HashMap::new(),
+        };
+        for name in names {
+            categories.get_or_create_id(name.into());
+        }
+
+        Budget {
+            categories,
+            ..Budget::default()
+        }
+    }
+
+    /// Returns an iterator over the master categories of the budget.
     pub fn master_categories(&self) -> impl Iterator<Item = &MasterCategory> {
         self.master_categories.values()
     }
+
+    /// Returns an iterator over the categories of the budget.
     pub fn categories(&self) -> impl Iterator<Item = &Category> {
         self.categories.values()
     }
 
+    /// Returns the category with the given path. See [`Budget::category_path`].
+    pub fn category(&self, path: &str) -> Option<&Category> {
+        self.categories.get_id(path).map(|id| &self.categories[id])
+    }
+
+    /// Returns the path of a category, made of the names of its parents and then
+    /// its own name, separated by ':', such as "transport:car:fuel".
+    ///
+    /// Wherever a category is looked up by name, its path can be given. The
+    /// first parents can be left out of the path if only one category matches
+    /// what is left, so "fuel" or "car:fuel" find the same category as
+    /// "transport:car:fuel" if there is no other category named "fuel".
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::Budget;
+    ///
+    /// let mut budget: Budget = Budget::with_categories(vec!["transport:car:fuel"]);
+    /// let fuel = budget.category("fuel").unwrap();
+    ///
+    /// assert_eq!(fuel.name(), "fuel");
+    /// assert_eq!(budget.category_path(fuel), "transport:car:fuel");
+    /// assert_eq!(budget.category_depth(fuel), 2);
+    /// assert!(budget.category("transport:car").is_some());
+    /// ```
+    pub fn category_path(&self, category: &Category) -> String {
+        self.categories.path(category)
+    }
+
+    /// Returns the number of parents that a category has.
+    pub fn category_depth(&self, category: &Category) -> usize {
+        let mut depth = 0;
+        let mut parent = category.parent;
+        while let Some(c) = parent.and_then(|id| self.categories.get(&id)) {
+            depth += 1;
+            parent = c.parent;
+        }
+        depth
+    }
+
+    /// Returns the master category that the named category belongs to, if any.
+    ///
+    /// Subcategories belong to the master category of their top-level category.
+    pub fn master_category_of(&self, category: &str) -> Option<&MasterCategory> {
+        let id = self.categories.get_id(category)?;
+        let root = *self.categories.ancestors(*id).last().unwrap();
+        self.categories[&root]
+            .master_category
+            .and_then(|m| self.master_categories.get(&m))
+    }
+
+    /// Returns the categories in order, grouped by master category.
+    ///
+    /// Master categories and the categories within them are in order of their
+    /// position, then name. Each category is followed by its subcategories.
+    /// Categories without a master category come last.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::Budget;
+    ///
+    /// let mut budget: Budget = Budget::with_categories(vec!["rent", "bills:water", "fun"]);
+    /// budget.add_master_category("home").unwrap();
+    /// budget.move_category("rent", Some("home"));
+    /// budget.move_category("bills", Some("home"));
+    ///
+    /// let groups = budget.grouped_categories();
+    /// let home: Vec<&str> = groups[0].1.iter().map(|c| c.name()).collect();
+    /// assert_eq!(groups[0].0.unwrap().name(), "home");
+    /// assert_eq!(home, vec!["rent", "bills", "water"]);
+    /// assert!(groups[1].0.is_none());
+    /// ```
+    pub fn grouped_categories(&self) -> Vec<(Option<&MasterCategory>, Vec<&Category>)> {
+        let mut masters: Vec<(&CategoryID, &MasterCategory)> =
+            self.master_categories.iter().collect();
+        masters.sort_by(|(_, a), (_, b)| (a.sort, &a.name).cmp(&(b.sort, &b.name)));
+
+        let in_group = |master: Option<&CategoryID>| {
+            let mut categories = Vec::new();
+            let roots = self
+                .sorted_children(None)
+                .into_iter()
+                .filter(|id| self.categories[id].master_category.as_ref() == master);
+            for root in roots {
+                self.push_subtree(root, &mut categories);
+            }
+            categories
+        };
+
+        let mut groups: Vec<(Option<&MasterCategory>, Vec<&Category>)> = masters
+            .into_iter()
+            .map(|(id, m)| (Some(m), in_group(Some(id))))
+            .collect();
+        let ungrouped = in_group(None);
+        if !ungrouped.is_empty() {
+            groups.push((None, ungrouped));
+        }
+        groups
+    }
+
+    /// Returns the ids of the subcategories of `parent`, or the top-level
+    /// categories, in order of their position then name.
+    fn sorted_children(&self, parent: Option<CategoryID>) -> Vec<CategoryID> {
+        let mut children: Vec<(&CategoryID, &Category)> = self
+            .categories
+            .iter()
+            .filter(|(_, c)| c.parent == parent)
+            .collect();
+        children.sort_by(|(_, a), (_, b)| (a.sort, &a.name).cmp(&(b.sort, &b.name)));
+        children.into_iter().map(|(id, _)| *id).collect()
+    }
+
+    /// Push a category and then its subcategories, in order.
+    fn push_subtree<'a>(&'a self, id: CategoryID, categories: &mut Vec<&'a Category>) {
+        categories.push(&self.categories[&id]);
+        for child in self.sorted_children(Some(id)) {
+            self.push_subtree(child, categories);
+        }
+    }
+
+    fn master_category_id(&self, name: &str) -> Option<CategoryID> {
+        self.master_categories
+            .iter()
+            .find(|(_, m)| m.name == name)
+            .map(|(id, _)| *id)
+    }
+
+    /// Add a new master category, after the existing ones.
+    ///
+    /// Returns the name of the new master category, or an error containing the
+    /// name of the existing master category if one with the same name is already present.
+    pub fn add_master_category(&mut self, name: &str) -> Result<&str, &str> {
+        if let Some(id) = self.master_category_id(name) {
+            return Err(&self.master_categories[&id].name);
+        }
+        let sort = self
+            .master_categories
+            .values()
+            .map(|m| m.sort + 1)
+            .max()
+            .unwrap_or(0);
+        let id = CategoryID::new_v4();
+        self.master_categories.insert(
+            id,
+            MasterCategory {
+                name: name.to_string(),
+                sort,
+            },
+        );
+        Ok(&self.master_categories[&id].name)
+    }
+
+    /// Rename a master category.
+    ///
+    /// Returns an error containing the name of the existing master category if
+    /// another one already has the new name.
+    pub fn rename_master_category(&mut self, old_name: &str, new_name: &str) -> Result<(), &str> {
+        let existing = self.master_category_id(new_name);
+        if let Some(id) = self.master_category_id(old_name) {
+            if let Some(existing) = existing.filter(|e| *e != id) {
+                return Err(&self.master_categories[&existing].name);
+            }
+            self.master_categories.get_mut(&id).unwrap().name = new_name.to_string();
+        }
+        Ok(())
+    }
+
+    /// Remove a master category, returning it if it exists. Its categories are
+    /// left without a master category, after the other ungrouped categories.
+    pub fn remove_master_category(&mut self, name: &str) -> Option<MasterCategory> {
+        let id = self.master_category_id(name)?;
+        let categories: Vec<CategoryID> = self
+            .sorted_children(None)
+            .into_iter()
+            .filter(|c| self.categories[c].master_category == Some(id))
+            .collect();
+        for category in categories {
+            self.move_category_id(category, None);
+        }
+        self.master_categories.remove(&id)
+    }
+
+    /// Move a category into a master category, or out of its master category if
+    /// `master` is `None`. The category is placed after the others in its new group.
+    ///
+    /// Only top-level categories belong to master categories directly, so
+    /// subcategories aren't moved.
+    pub fn move_category(&mut self, category: &str, master: Option<&str>) {
+        let master = master.and_then(|m| self.master_category_id(m));
+        if let Some(id) = self.categories.get_id(category) {
+            if self.categories[id].parent.is_none() {
+                self.move_category_id(*id, master);
+            }
+        }
+    }
+
+    fn move_category_id(&mut self, id: CategoryID, master: Option<CategoryID>) {
+        let sort = self
+            .categories
+            .iter()
+            .filter(|(other, c)| c.parent.is_none() && c.master_category == master && **other != id)
+            .map(|(_, c)| c.sort + 1)
+            .max()
+            .unwrap_or(0);
+
+        let c = self.categories.get_mut(&id).unwrap();
+        c.master_category = master;
+        c.sort = sort;
+    }
+
+    /// Move a category to a position among the categories in its master category,
+    /// or among the other subcategories of its parent, counting from zero.
+    pub fn set_category_position(&mut self, category: &str, position: usize) {
+        let id = match self.categories.get_id(category) {
+            Some(id) => *id,
+            None => return,
+        };
+        let (parent, master) = {
+            let c = &self.categories[&id];
+            (c.parent, c.master_category)
+        };
+        let mut siblings: Vec<CategoryID> = self
+            .sorted_children(parent)
+            .into_iter()
+            .filter(|s| parent.is_some() || self.categories[s].master_category == master)
+            .collect();
+
+        if let Some(i) = siblings.iter().position(|s| *s == id) {
+            siblings.remove(i);
+            siblings.insert(position.min(siblings.len()), id);
+        }
+        for (i, s) in siblings.iter().enumerate() {
+            self.categories.get_mut(s).unwrap().sort = i as i32;
+        }
+    }
+
+    /// Move a master category to a position among the master categories,
+    /// counting from zero.
+    pub fn set_master_category_position(&mut self, name: &str, position: usize) {
+        let mut names: Vec<String> = self
+            .grouped_categories()
+            .into_iter()
+            .filter_map(|(m, _)| Some(m?.name.clone()))
+            .collect();
+
+        reposition(&mut names, name, position);
+        for m in self.master_categories.values_mut() {
+            if let Some(i) = names.iter().position(|n| *n == m.name) {
+                m.sort = i as i32;
+            }
+        }
+    }
+
+    /// Make a category, along with its subcategories, a subcategory of another,
+    /// or a top-level category if `parent` is `None`.
+    ///
+    /// The category is placed after the other subcategories of its new parent.
+    /// Rules and payees which refer to the category by its path are changed to
+    /// refer to its new path.
+    pub fn set_category_parent(
+        &mut self,
+        category: &str,
+        parent: Option<&str>,
+    ) -> Result<(), MoveCategoryError> {
+        let id = *self
+            .categories
+            .get_id(category)
+            .ok_or(MoveCategoryError::NoCategory)?;
+        let parent = match parent {
+            Some(p) => Some(
+                *self
+                    .categories
+                    .get_id(p)
+                    .ok_or(MoveCategoryError::NoParent)?,
+            ),
+            None => None,
+        };
+        if parent.is_some_and(|p| self.categories.subtree(id).contains(&p)) {
+            return Err(MoveCategoryError::IntoItself);
+        }
+        let name = self.categories[&id].name.clone();
+        if self
+            .categories
+            .child(parent, &name)
+            .is_some_and(|other| other != id)
+        {
+            return Err(MoveCategoryError::NameTaken);
+        }
+
+        let references = self.category_references();
+        let sort = self
+            .sorted_children(parent)
+            .iter()
+            .filter(|c| **c != id)
+            .map(|c| self.categories[c].sort + 1)
+            .max()
+            .unwrap_or(0);
+        let c = self.categories.get_mut(&id).unwrap();
+        c.parent = parent;
+        c.sort = sort;
+        if parent.is_some() {
+            c.master_category = None;
+        }
+        self.update_category_references(references, &HashMap::new());
+        Ok(())
+    }
+
+    /// Add a new category to the budget. The name may be a path, such as
+    /// "transport:car", in which case any parents which don't already exist are
+    /// created too.
+    ///
+    /// Rules and payees which refer to a category by a name which the new category
+    /// makes ambiguous are changed to use the full path.
+    ///
+    /// Returns the name of the new category, or an error containing the name of
+    /// the existing category if one with the same path is already present.
     pub fn add_category<'a, S>(&mut self, name: S) -> Result<&str, &str>
     where
         S: Into<Cow<'a, str>>,
     {
         let n = name.into();
-        match self.categories.get_id(&n) {
+        match self.categories.find_path(&n) {
             Some(id) => Err(&self.categories.get(&id).unwrap().name),
             None => {
-                let id = self.categories.get_or_create_id(n);
+                let references = self.category_references();
+                let id = self.categories.create_path(&n);
+                self.update_category_references(references, &HashMap::new());
                 Ok(&self.categories.get(&id).unwrap().name)
             }
         }
     }
 
-    pub fn ledger(&self) -> &Ledger<C> {
-        &self.transactions
-    }
+    /// Returns a reference to the underlying [`Ledger`] of transactions.
+    pub fn ledger(&self) -> &Ledger<C> {
+        &self.transactions
+    }
+
+    /// Add a transaction to the budget.
+    ///
+    /// Returns an error if the transaction has a category which doesn't exist.
+    /// Categories are created with [`Budget::add_category`].
+    pub fn add(&mut self, mut t: Transaction<C>) -> Result<(), UnknownCategoryError> {
+        self.resolve_category(&mut t)?;
+        self.summarise(&t);
+        self.transactions.add(t);
+        Ok(())
+    }
+
+    /// Returns the name of the category of a transaction, if it has one.
+    ///
+    /// This is the path of the category, such as "transport:car:fuel". See
+    /// [`Budget::category_path`].
+    pub fn category_name(&self, t: &Transaction<C>) -> Option<String> {
+        match t.category()? {
+            CategoryRef::Id(id) => self.categories.get(id).map(|c| self.categories.path(c)),
+            CategoryRef::Name(name) => Some(name.clone()),
+        }
+    }
+
+    /// Returns the paths of the category of a transaction and each of its
+    /// parents, starting with the category itself.
+    ///
+    /// Reports use this to roll the spending in a category up into its parents.
+    pub fn category_paths(&self, t: &Transaction<C>) -> Vec<String> {
+        let id = match t.category() {
+            Some(CategoryRef::Id(id)) => Some(*id),
+            Some(CategoryRef::Name(name)) => self.categories.get_id(name).copied(),
+            None => None,
+        };
+        match id {
+            Some(id) => self
+                .categories
+                .ancestors(id)
+                .iter()
+                .filter_map(|id| self.categories.get(id))
+                .map(|c| self.categories.path(c))
+                .collect(),
+            None => self.category_name(t).into_iter().collect(),
+        }
+    }
+
+    /// Returns the id of the category with the given name, or an error if there
+    /// is no such category.
+    fn category_id(&self, name: &str) -> Result<CategoryID, UnknownCategoryError> {
+        self.categories
+            .get_id(name)
+            .copied()
+            .ok_or_else(|| UnknownCategoryError(name.to_string()))
+    }
+
+    /// Make a transaction which refers to its category by name refer to it by id
+    /// instead.
+    ///
+    /// Returns true if the transaction was changed, or an error if there is no
+    /// category with the name.
+    fn resolve_category(&self, t: &mut Transaction<C>) -> Result<bool, UnknownCategoryError> {
+        match t.category_name() {
+            Some(name) => {
+                let id = self.category_id(name)?;
+                t.set_category_ref(Some(CategoryRef::Id(id)));
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Returns a copy of a transaction which refers to its category by name, for
+    /// use outside the budget.
+    fn with_category_name(&self, mut t: Transaction<C>) -> Transaction<C> {
+        if let Some(CategoryRef::Id(_)) = t.category() {
+            let name = self.category_name(&t);
+            t.set_category(name);
+        }
+        t
+    }
+
+    /// Bring a budget saved by an earlier version up to date, by making the
+    /// transactions and schedules which refer to their category by name refer to
+    /// it by id.
+    ///
+    /// The summaries are recalculated too, since earlier versions counted
+    /// transactions which had no exchange rate without converting them.
+    ///
+    /// A budget is upgraded when it is loaded, so this only needs to be called
+    /// for transactions which have been changed directly.
+    ///
+    /// Returns the number of transactions and schedules which were changed.
+    pub fn upgrade(&mut self) -> usize {
+        let renamed = self.rename_separators();
+        let mut ledger = std::mem::take(&mut self.transactions);
+        let mut schedules = std::mem::take(&mut self.schedules);
+        let mut changed = 0;
+        let templates = schedules.iter_mut().map(|s| s.template_mut());
+        for t in (&mut ledger).into_iter().chain(templates) {
+            if let Some(new) = t.category_name().and_then(|n| renamed.get(n)) {
+                t.set_category(Some(new.clone()));
+            }
+            if let Some(name) = t.category_name() {
+                let id = match self.categories.get_id(name) {
+                    Some(id) => *id,
+                    // earlier versions created categories as transactions were added
+                    None => self.categories.create_path(name),
+                };
+                t.set_category_ref(Some(CategoryRef::Id(id)));
+                changed += 1;
+            }
+        }
+        self.transactions = ledger;
+        self.schedules = schedules;
+        self.resummarise();
+        changed
+    }
+
+    /// Rename the categories whose names contain the separator between the parts
+    /// of a path, which earlier versions allowed, so that they can be found
+    /// again. The separator is replaced with '/', and rules and payees are
+    /// changed to use the new names.
+    ///
+    /// Returns the new names, keyed by the old ones.
+    fn rename_separators(&mut self) -> HashMap<String, String> {
+        let ids: Vec<CategoryID> = self
+            .categories
+            .iter()
+            .filter(|(_, c)| c.name.contains(SEPARATOR))
+            .map(|(id, _)| *id)
+            .collect();
+        let mut renamed = HashMap::new();
+        for id in ids {
+            let category = &self.categories[&id];
+            let old = category.name.clone();
+            let base = old.replace(SEPARATOR, "/");
+            let mut new = base.clone();
+            let mut n = 1;
+            while self.categories.child(category.parent, &new).is_some() {
+                n += 1;
+                new = format!("{} {}", base, n);
+            }
+            self.categories.get_mut(&id).unwrap().name = new.clone();
+            renamed.insert(old, new);
+        }
+        if renamed.is_empty() {
+            return renamed;
+        }
+
+        for rule in &mut self.rules {
+            rule.map_categories(|name| {
+                if let Some(new) = renamed.get(name) {
+                    *name = new.clone();
+                }
+            });
+        }
+        for payee in self.payees.values_mut() {
+            if let Some(new) = payee.category().and_then(|c| renamed.get(c)) {
+                let new = new.clone();
+                payee.set_category(Some(new));
+            }
+        }
+        renamed
+    }
+
+    fn summarise(&mut self, t: &Transaction<C>) {
+        if !self.is_on_budget(t) {
+            return;
+        }
+
+        let date = self.month_of(t.date());
+        let amount = match self.to_budget_currency(t) {
+            Some(amount) => amount,
+            None => {
+                log::warn!(
+                    "no exchange rate from {} to {}. transaction {} is left out",
+                    self.account_currency(t.account().as_deref()),
+                    self.currency,
+                    t.uuid()
+                );
+                self.unconverted.insert(*t.uuid());
+                return;
+            }
+        };
+
+        let id = match t.category() {
+            Some(CategoryRef::Id(id)) => Some(*id),
+            Some(CategoryRef::Name(name)) => self.categories.get_id(name).copied(),
+            None => None,
+        };
+        if let Some(id) = id {
+            self.summaries.entry((date, id)).or_default().add(amount);
+        } else {
+            self.uncategorised_summaries
+                .entry(date)
+                .or_default()
+                .add(amount);
+        }
+    }
+
+    /// Returns the transactions which are left out of the budget, because they are
+    /// in a foreign currency and there is no exchange rate to convert them.
+    pub fn unconverted(&self) -> impl Iterator<Item = &Transaction<C>> {
+        (&self.transactions)
+            .into_iter()
+            .filter(move |t| self.unconverted.contains(t.uuid()))
+    }
+
+    /// Recalculate the transaction summaries, such as after a change to the exchange rates.
+    fn resummarise(&mut self) {
+        self.summaries.clear();
+        self.uncategorised_summaries.clear();
+        self.unconverted.clear();
+
+        let ledger = std::mem::take(&mut self.transactions);
+        for t in &ledger {
+            self.summarise(t);
+        }
+        self.transactions = ledger;
+    }
+
+    /// Returns the currency of the budget.
+    pub fn currency(&self) -> CurrencyCode {
+        self.currency
+    }
+
+    /// Set the currency of the budget.
+    ///
+    /// Amounts in the budget aren't converted. Transactions in accounts with a
+    /// different currency are converted using the exchange rates.
+    pub fn set_currency(&mut self, currency: CurrencyCode) {
+        self.currency = currency;
+        self.resummarise();
+    }
+
+    /// Returns the day of the month that budget months start on.
+    ///
+    /// Transactions, allocations and reports are grouped by budget month. When
+    /// months start on a day other than the 1st, a budget month is named after the
+    /// calendar month that it starts in. See [`CalendarMonth::containing`].
+    pub fn first_day_of_month(&self) -> u32 {
+        self.first_day_of_month.unwrap_or(1)
+    }
+
+    /// Set the day of the month that budget months start on, from 1 to 28.
+    ///
+    /// The transactions are grouped into the new months. Money which has already
+    /// been assigned stays in the month it was assigned to.
+    ///
+    /// # Panics
+    /// Panics if the day is not from 1 to 28.
+    pub fn set_first_day_of_month(&mut self, day: u32) {
+        assert!(
+            (1..=28).contains(&day),
+            "budget months must start on a day from 1 to 28"
+        );
+        if day != self.first_day_of_month() {
+            self.first_day_of_month = Some(day).filter(|&d| d != 1);
+            self.resummarise();
+        }
+    }
+
+    /// Returns the budget month containing `date`.
+    pub fn month_of(&self, date: impl Datelike) -> CalendarMonth {
+        CalendarMonth::containing(date, self.first_day_of_month())
+    }
+
+    /// Returns an iterator over the accounts of the budget, in order of name.
+    pub fn accounts(&self) -> impl Iterator<Item = &Account> {
+        self.accounts.values()
+    }
+
+    /// Returns the account with the given name, if it exists.
+    pub fn account(&self, name: &str) -> Option<&Account> {
+        self.accounts.get(name)
+    }
+
+    /// Add a new account to the budget.
+    ///
+    /// Returns the new account, or an error containing the existing account if
+    /// one with the same name is already present.
+    pub fn add_account(&mut self, account: Account) -> Result<&Account, &Account> {
+        if self.accounts.contains_key(account.name()) {
+            return Err(&self.accounts[account.name()]);
+        }
+
+        let name = account.name().to_string();
+        self.accounts.insert(name.clone(), account);
+        self.resummarise();
+        Ok(&self.accounts[&name])
+    }
+
+    /// Returns the currency of the named account.
+    ///
+    /// Transactions without an account, or with an account which isn't known to
+    /// the budget, are in the currency of the budget.
+    pub fn account_currency(&self, account: Option<&str>) -> CurrencyCode {
+        account
+            .and_then(|a| self.accounts.get(a))
+            .and_then(Account::currency)
+            .unwrap_or(self.currency)
+    }
+
+    /// Returns true if the transaction is in an account which is on-budget, or has no account.
+    pub fn is_on_budget(&self, t: &Transaction<C>) -> bool {
+        t.account()
+            .as_ref()
+            .and_then(|a| self.accounts.get(a))
+            .is_none_or(|a| !a.is_off_budget())
+    }
+
+    /// Returns true if the transaction is one half of a transfer between two
+    /// on-budget accounts, or two off-budget accounts.
+    ///
+    /// A transfer from an on-budget account to an off-budget account, such as a
+    /// mortgage payment, is spending as far as the budget is concerned.
+    ///
+    /// The other half of the transfer is looked up in `transfers`, which is made
+    /// once by [`Budget::transfers`] for all of the transactions in a report.
+    pub(crate) fn is_internal_transfer(
+        &self,
+        t: &Transaction<C>,
+        transfers: &HashMap<Uuid, &Transaction<C>>,
+    ) -> bool {
+        let other = match t.transfer() {
+            Some(uuid) => transfers.get(uuid),
+            None => return false,
+        };
+        other.is_none_or(|other| self.is_on_budget(t) == self.is_on_budget(other))
+    }
+
+    /// Returns the transactions which are half of a transfer, keyed by uuid.
+    pub(crate) fn transfers(&self) -> HashMap<Uuid, &Transaction<C>> {
+        (&self.transactions)
+            .into_iter()
+            .filter(|t| t.is_transfer())
+            .map(|t| (*t.uuid(), t))
+            .collect()
+    }
+
+    /// Returns the valuations of accounts, in order of account and date.
+    pub fn valuations(&self) -> &[Valuation<C>] {
+        &self.valuations
+    }
+
+    /// Set the value of an account on a date, replacing any existing valuation on
+    /// the same date. The value is in the currency of the account.
+    pub fn add_valuation<S: Into<String>>(&mut self, account: S, date: NaiveDate, value: C) {
+        let account = account.into();
+        self.valuations
+            .retain(|v| !(v.account == account && v.date == date));
+        self.valuations.push(Valuation {
+            account,
+            date,
+            value,
+        });
+        self.valuations
+            .sort_by(|a, b| (&a.account, a.date).cmp(&(&b.account, b.date)));
+    }
+
+    /// Returns the balance of an account at the end of the given date, in the
+    /// currency of the account.
+    ///
+    /// This is the most recent valuation of the account on or before the date,
+    /// plus the transactions after the valuation. Without a valuation, it is the
+    /// sum of all the transactions in the account up to the date.
+    pub fn balance(&self, account: Option<&str>, date: NaiveDate) -> C {
+        let valuation = account.and_then(|a| {
+            self.valuations
+                .iter()
+                .filter(|v| v.account == a && v.date <= date)
+                .last()
+        });
+
+        let mut balance = valuation.map_or(C::zero(), |v| v.value);
+        for t in &self.transactions {
+            let d = t.date().date_naive();
+            if t.account().as_deref() == account
+                && d <= date
+                && valuation.is_none_or(|v| d > v.date)
+            {
+                balance += *t.amount();
+            }
+        }
+        balance
+    }
+
+    /// Returns the scheduled transactions of the budget.
+    pub fn schedules(&self) -> &[ScheduledTransaction<C>] {
+        &self.schedules
+    }
+
+    /// Add a scheduled transaction to the budget. Nothing is added to the ledger
+    /// until [`Budget::add_due_transactions`] is called.
+    ///
+    /// Returns an error if the transaction has a category which doesn't exist.
+    pub fn add_schedule(
+        &mut self,
+        mut schedule: ScheduledTransaction<C>,
+    ) -> Result<(), UnknownCategoryError> {
+        self.resolve_category(schedule.template_mut())?;
+        self.schedules.push(schedule);
+        Ok(())
+    }
+
+    /// Remove a scheduled transaction. Transactions which have already been added
+    /// to the ledger are kept.
+    pub fn remove_schedule(&mut self, uuid: &Uuid) -> Option<ScheduledTransaction<C>> {
+        let i = self.schedules.iter().position(|s| s.uuid() == uuid)?;
+        Some(self.schedules.remove(i))
+    }
+
+    /// Skip the next occurrence of a scheduled transaction, returning the date that
+    /// was skipped. Returns `None` if there is no such schedule, or it has ended.
+    pub fn skip_schedule(&mut self, uuid: &Uuid) -> Option<NaiveDate> {
+        self.schedules
+            .iter_mut()
+            .find(|s| s.uuid() == uuid)
+            .and_then(ScheduledTransaction::skip)
+    }
+
+    /// Add the occurrences of scheduled transactions which have fallen due by
+    /// `today` to the ledger. Returns the number of transactions added.
+    ///
+    /// A schedule whose category doesn't exist, such as one edited by hand, is
+    /// left as it is so that it can be fixed, and its transactions are added later.
+    pub fn add_due_transactions(&mut self, today: NaiveDate) -> usize {
+        let mut due = vec![];
+        for s in &mut self.schedules {
+            if let Some(name) = s.template().category_name() {
+                if self.categories.get_id(name).is_none() {
+                    log::warn!("no category '{}'. schedule {} is skipped", name, s.uuid());
+                    continue;
+                }
+            }
+            due.extend(s.due(today));
+        }
+        let mut added = 0;
+        for t in due {
+            if self.add(t).is_ok() {
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Returns the rules of the budget, in the order they are applied.
+    pub fn rules(&self) -> &[Rule<C>] {
+        &self.rules
+    }
+
+    /// Add a rule, to be applied after the existing rules.
+    ///
+    /// Returns an error if the rule splits transactions into shares which don't
+    /// add up to 100 percent.
+    pub fn add_rule(&mut self, rule: Rule<C>) -> Result<(), InvalidSplitError> {
+        rule.check()?;
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    /// Remove the rule with the given id, returning it if it exists.
+    pub fn remove_rule(&mut self, uuid: &Uuid) -> Option<Rule<C>> {
+        let i = self.rules.iter().position(|r| r.uuid() == uuid)?;
+        Some(self.rules.remove(i))
+    }
+
+    /// Run each of the rules over a transaction in turn.
+    ///
+    /// Returns the transaction, or the parts of the transaction if a rule splits
+    /// it, referring to their categories by name. Transfers between accounts and
+    /// reconciled transactions are returned unchanged.
+    pub fn run_rules(&self, t: Transaction<C>) -> Vec<Transaction<C>> {
+        if t.is_transfer() || t.reconciled() {
+            return vec![t];
+        }
+        let t = self.with_category_name(t);
+        let decimal_places = self.account_currency(t.account().as_deref()).minor_units();
+        self.rules.iter().fold(vec![t], |transactions, rule| {
+            transactions
+                .into_iter()
+                .flat_map(|t| rule.apply(t, decimal_places))
+                .collect()
+        })
+    }
+
+    /// Add a transaction from an import.
+    ///
+    /// The payee is replaced by its canonical name, then the rules are run over
+    /// the transaction, and finally it is given the default category of its payee
+    /// if it still has no category.
+    ///
+    /// Returns an error, and adds nothing, if the rules or the payee give the
+    /// transaction a category which doesn't exist.
+    pub fn import(&mut self, t: Transaction<C>) -> Result<(), UnknownCategoryError> {
+        let imported = self.prepare_import(t)?;
+        for t in imported {
+            self.add(t)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the transactions which importing a transaction adds, as
+    /// [`Budget::import`] describes.
+    fn prepare_import(
+        &self,
+        t: Transaction<C>,
+    ) -> Result<Vec<Transaction<C>>, UnknownCategoryError> {
+        let t = self.with_canonical_payee(t.with_source(Source::Import));
+        self.run_rules(t)
+            .into_iter()
+            .map(|t| {
+                let mut t = self.with_payee_category(t);
+                self.resolve_category(&mut t)?;
+                Ok(t)
+            })
+            .collect()
+    }
+
+    /// Import transactions as [`Budget::import`] does, leaving out any which are
+    /// already in the ledger, such as when the same file is imported twice.
+    ///
+    /// A transaction is already in the ledger if its account has one on the same
+    /// date with the same amount and payee, ignoring the case of the payee.
+    /// Transactions which are the same as each other are all imported, as long
+    /// as the ledger didn't already have one.
+    ///
+    /// Returns the number of transactions which were imported, or an error, and
+    /// imports nothing, if the rules or a payee give a transaction a category
+    /// which doesn't exist.
+    pub fn import_all<I>(&mut self, transactions: I) -> Result<usize, UnknownCategoryError>
+    where
+        I: IntoIterator<Item = Transaction<C>>,
+    {
+        let key = |t: &Transaction<C>| {
+            (
+                t.account().clone(),
+                t.date().date_naive(),
+                t.payee().as_deref().map(fold_case),
+            )
+        };
+        let mut existing: HashMap<_, Vec<C>> = HashMap::new();
+        for t in &self.transactions {
+            existing.entry(key(t)).or_default().push(*t.amount());
+        }
+
+        let new: Vec<Transaction<C>> = transactions
+            .into_iter()
+            .map(|t| self.with_canonical_payee(t))
+            .filter(|t| {
+                existing
+                    .get(&key(t))
+                    .is_none_or(|amounts| !amounts.contains(t.amount()))
+            })
+            .collect();
+        let count = new.len();
+        let mut imported = Vec::new();
+        for t in new {
+            imported.extend(self.prepare_import(t)?);
+        }
+        for t in imported {
+            self.add(t)?;
+        }
+        Ok(count)
+    }
+
+    /// Add a transaction entered by hand.
+    ///
+    /// The payee is replaced by its canonical name. If the transaction has no
+    /// category it is given the default category of its payee, otherwise its
+    /// category is remembered as the payee's default.
+    ///
+    /// Returns an error if the transaction has a category which doesn't exist.
+    pub fn add_entry(&mut self, t: Transaction<C>) -> Result<(), UnknownCategoryError> {
+        let t = self.with_canonical_payee(t);
+        let mut t = self.with_payee_category(t);
+        self.resolve_category(&mut t)?;
+        if let (Some(payee), Some(category)) = (t.payee(), self.category_name(&t)) {
+            let payee = payee.clone();
+            self.set_payee_category(&payee, Some(category));
+        }
+        self.add(t)
+    }
+
+    /// Change the transaction with the given uuid, such as to correct its amount
+    /// or category. The transaction is kept in order by date.
+    ///
+    /// Returns false if there is no transaction with the uuid, or an error, and
+    /// leaves the transaction as it was, if its new category doesn't exist.
+    pub fn edit_transaction<F>(&mut self, uuid: &Uuid, f: F) -> Result<bool, UnknownCategoryError>
+    where
+        F: FnOnce(&mut Transaction<C>),
+    {
+        let original = match self.transactions.remove(uuid) {
+            Some(t) => t,
+            None => return Ok(false),
+        };
+        let mut t = original.clone();
+        f(&mut t);
+        if let Err(e) = self.resolve_category(&mut t) {
+            self.transactions.add(original);
+            return Err(e);
+        }
+        self.transactions.add(t);
+        self.resummarise();
+        Ok(true)
+    }
+
+    /// Returns the registered payees, in order of name.
+    pub fn payees(&self) -> impl Iterator<Item = &Payee> {
+        self.payees.values()
+    }
+
+    /// Returns the registered payee with the given name, or with an alias which
+    /// matches it.
+    pub fn payee(&self, name: &str) -> Option<&Payee> {
+        self.payees
+            .get(name)
+            .or_else(|| self.payees.values().find(|p| p.matches(name)))
+    }
+
+    fn with_canonical_payee(&self, mut t: Transaction<C>) -> Transaction<C> {
+        if let Some(payee) = t.payee().as_deref().and_then(|p| self.payee(p)) {
+            t.set_payee(Some(payee.name()));
+        }
+        t
+    }
+
+    fn with_payee_category(&self, mut t: Transaction<C>) -> Transaction<C> {
+        if t.category().is_none() && !t.is_transfer() {
+            let category = t
+                .payee()
+                .as_deref()
+                .and_then(|p| self.payee(p))
+                .and_then(|p| p.category());
+            t.set_category(category);
+        }
+        t
+    }
+
+    /// Returns the registered payee with the given name, registering it if needed.
+    fn payee_mut(&mut self, name: &str) -> &mut Payee {
+        self.payees
+            .entry(name.to_string())
+            .or_insert_with(|| Payee::new(name))
+    }
+
+    /// Set the payee of the transactions whose payee meets the condition.
+    ///
+    /// Returns the number of transactions which were changed.
+    fn set_payees<F>(&mut self, condition: F, payee: &str) -> usize
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut count = 0;
+        for t in &mut self.transactions {
+            if t.payee()
+                .as_deref()
+                .is_some_and(|p| p != payee && condition(p))
+            {
+                t.set_payee(Some(payee));
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Set the default category of a payee, registering the payee if needed.
+    pub fn set_payee_category(&mut self, payee: &str, category: Option<String>) {
+        self.payee_mut(payee).set_category(category);
+    }
+
+    /// Add an alias to a payee, registering the payee if needed, and give the
+    /// transactions with a payee which matches the alias the payee's name.
+    ///
+    /// Returns the number of transactions which were renamed.
+    pub fn add_payee_alias(&mut self, payee: &str, alias: Pattern) -> usize {
+        self.payee_mut(payee).add_alias(alias.clone());
+        self.set_payees(|p| alias.is_match(p), payee)
+    }
+
+    /// Rename a payee, and the transactions with that payee in any case.
+    ///
+    /// Returns the number of transactions which were renamed, or an error if
+    /// there is no payee with the old name or one is already registered with the
+    /// new name.
+    pub fn rename_payee(&mut self, old: &str, new: &str) -> Result<usize, PayeeError> {
+        if !self.has_payee(old) {
+            return Err(PayeeError::NoPayee(old.to_string()));
+        }
+        let key = self.payee_key(old);
+        if self
+            .payee_key(new)
+            .is_some_and(|k| Some(&k) != key.as_ref())
+        {
+            return Err(PayeeError::Exists(new.to_string()));
+        }
+        if let Some(mut payee) = key.and_then(|k| self.payees.remove(&k)) {
+            payee.set_name(new.to_string());
+            self.payees.insert(new.to_string(), payee);
+        }
+        Ok(self.set_payees(|p| same_name(p, old), new))
+    }
+
+    /// Merge one payee into another.
+    ///
+    /// The transactions with the payee `from`, in any case, are given the payee
+    /// `into`, which takes on the aliases of `from` and `from` itself as an alias.
+    /// It keeps its default category, or takes the default category of `from` if
+    /// it has none.
+    ///
+    /// Returns the number of transactions which were changed, or an error if
+    /// there is no payee with either name.
+    pub fn merge_payee(&mut self, from: &str, into: &str) -> Result<usize, PayeeError> {
+        for name in [from, into] {
+            if !self.has_payee(name) {
+                return Err(PayeeError::NoPayee(name.to_string()));
+            }
+        }
+        let old = self.payee_key(from).and_then(|k| self.payees.remove(&k));
+        let literal = format!("^{}$", regex::escape(from))
+            .parse()
+            .expect("an escaped name is a valid pattern");
+
+        let into = self.payee_key(into).unwrap_or_else(|| into.to_string());
+        let payee = self.payee_mut(&into);
+        payee.add_alias(literal);
+        if let Some(old) = old {
+            for alias in old.aliases() {
+                payee.add_alias(alias.clone());
+            }
+            if payee.category().is_none() {
+                payee.set_category(old.category().map(String::from));
+            }
+        }
+        Ok(self.set_payees(|p| same_name(p, from), &into))
+    }
+
+    /// Returns the name that a payee is registered under, preferring one in the
+    /// same case as `name`.
+    fn payee_key(&self, name: &str) -> Option<String> {
+        if self.payees.contains_key(name) {
+            return Some(name.to_string());
+        }
+        self.payees.keys().find(|p| same_name(p, name)).cloned()
+    }
+
+    /// Returns true if a payee with this name, ignoring case, is registered or is
+    /// the payee of a transaction.
+    fn has_payee(&self, name: &str) -> bool {
+        self.payees.keys().any(|p| same_name(p, name))
+            || (&self.transactions)
+                .into_iter()
+                .any(|t| t.payee().as_deref().is_some_and(|p| same_name(p, name)))
+    }
+
+    /// Returns the number, total and latest date of the transactions of each
+    /// payee, in order of name. Registered payees without any transactions are
+    /// included.
+    ///
+    /// Payees are the same whatever their case, as they are for
+    /// [`Budget::payee`]. Each is named as it is registered, or otherwise as it
+    /// first appears in the ledger.
+    pub fn payee_stats(&self) -> Vec<PayeeStats<C>> {
+        let mut stats: BTreeMap<String, PayeeStats<C>> = BTreeMap::new();
+        let entry = |name: &str| PayeeStats {
+            payee: name.to_string(),
+            count: 0,
+            total: C::zero(),
+            unconverted: 0,
+            last: None,
+        };
+        for name in self.payees.keys() {
+            stats.insert(fold_case(name), entry(name));
+        }
+        for t in &self.transactions {
+            if let Some(name) = t.payee() {
+                let s = stats.entry(fold_case(name)).or_insert_with(|| entry(name));
+                s.count += 1;
+                match self.to_budget_currency(t) {
+                    Some(amount) => s.total += amount,
+                    None => s.unconverted += 1,
+                }
+                let date = t.date().date_naive();
+                s.last = s.last.max(Some(date));
+            }
+        }
+        stats.into_values().collect()
+    }
+
+    /// Returns the number, total and latest date of the transactions with each
+    /// tag, in order of name. A transaction with a subtag, such as "holiday/2026",
+    /// is counted in its parent tags too.
+    pub fn tag_stats(&self) -> Vec<TagStats<C>> {
+        let mut stats: BTreeMap<Vec<&str>, TagStats<C>> = BTreeMap::new();
+        for t in &self.transactions {
+            let mut tags: Vec<&str> = t.tags().flat_map(|t| tag_ancestors(t)).collect();
+            tags.sort_unstable();
+            tags.dedup();
+            for tag in tags {
+                // ordered by the parts of the tag, so that subtags follow their parents
+                let s = stats
+                    .entry(tag.split(TAG_SEPARATOR).collect())
+                    .or_insert_with(|| TagStats {
+                        tag: tag.to_string(),
+                        count: 0,
+                        total: C::zero(),
+                        unconverted: 0,
+                        last: None,
+                    });
+                s.count += 1;
+                match self.to_budget_currency(t) {
+                    Some(amount) => s.total += amount,
+                    None => s.unconverted += 1,
+                }
+                s.last = s.last.max(Some(t.date().date_naive()));
+            }
+        }
+        stats.into_values().collect()
+    }
+
+    /// Rename a tag, and its subtags, in the transactions, schedules and rules of
+    /// the budget. Renaming "holiday" to "travel" renames "holiday/2026" to
+    /// "travel/2026" too.
+    ///
+    /// Returns the number of transactions which were changed, or an error if the
+    /// new name is empty or is a subtag of the old one.
+    pub fn rename_tag(&mut self, old: &str, new: &str) -> Result<usize, RenameTagError> {
+        if new.trim().is_empty() {
+            return Err(RenameTagError::EmptyName);
+        }
+        if new != old && is_within_tag(new, old) {
+            return Err(RenameTagError::IntoItself);
+        }
+        for schedule in &mut self.schedules {
+            schedule
+                .template_mut()
+                .map_tags(|t| Some(rename_tag(t, old, new)));
+        }
+        for rule in &mut self.rules {
+            rule.map_tags(|t| Some(rename_tag(t, old, new)));
+        }
+        Ok(self.transactions.rename_tag(old, new))
+    }
+
+    /// Remove a tag, and its subtags, from the transactions, schedules and rules of
+    /// the budget.
+    ///
+    /// Returns the number of transactions which were changed.
+    pub fn delete_tag(&mut self, tag: &str) -> usize {
+        let keep = |t: &str| Some(t.to_string()).filter(|t| !is_within_tag(t, tag));
+        for schedule in &mut self.schedules {
+            schedule.template_mut().map_tags(keep);
+        }
+        for rule in &mut self.rules {
+            rule.map_tags(keep);
+        }
+        // a rule without any actions left does nothing
+        self.rules.retain(|r| !r.actions().is_empty());
+        self.transactions.delete_tag(tag)
+    }
+
+    /// Returns the changes that running the rules would make to the transactions
+    /// in the ledger which `filter` selects.
+    pub fn rule_changes<F>(&self, filter: F) -> Vec<RuleChange<C>>
+    where
+        F: Fn(&Transaction<C>) -> bool,
+    {
+        (&self.transactions)
+            .into_iter()
+            .filter(|t| filter(t))
+            .filter_map(|t| {
+                let after = self.run_rules(t.clone());
+                let unchanged = after.len() == 1
+                    && self.category_name(&after[0]) == self.category_name(t)
+                    && after[0].payee() == t.payee()
+                    && after[0].tags().eq(t.tags());
+                if unchanged {
+                    None
+                } else {
+                    Some(RuleChange {
+                        before: t.clone(),
+                        after,
+                    })
+                }
+            })
+            .collect()
+    }
+
+    /// Run the rules over the transactions in the ledger which `filter` selects.
+    ///
+    /// Returns the changes that were made, or an error, and changes nothing, if a
+    /// rule gives a transaction a category which doesn't exist.
+    pub fn apply_rules<F>(&mut self, filter: F) -> Result<Vec<RuleChange<C>>, UnknownCategoryError>
+    where
+        F: Fn(&Transaction<C>) -> bool,
+    {
+        let changes = self.rule_changes(filter);
+        if changes.is_empty() {
+            return Ok(changes);
+        }
+
+        let changed: HashSet<Uuid> = changes.iter().map(|c| *c.before.uuid()).collect();
+        let mut after: Vec<Transaction<C>> = changes
+            .iter()
+            .flat_map(|c| c.after.iter().cloned())
+            .collect();
+        for t in &mut after {
+            self.resolve_category(t)?;
+        }
+        let ledger = std::mem::take(&mut self.transactions);
+        self.transactions = Ledger::from_transactions(
+            ledger
+                .into_iter()
+                .filter(|t| !changed.contains(t.uuid()))
+                .chain(after),
+        );
+        self.resummarise();
+        Ok(changes)
+    }
+
+    /// Returns the exchange rates used by the budget.
+    pub fn rates(&self) -> &ExchangeRates<C> {
+        &self.rates
+    }
+
+    /// Add an exchange rate, replacing any existing rate for the same currencies and date.
+    pub fn add_rate(&mut self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate, rate: C) {
+        self.rates.add(from, to, date, rate);
+        self.resummarise();
+    }
+
+    /// Add several exchange rates at once, such as when importing them from a file.
+    pub fn add_rates<I>(&mut self, rates: I)
+    where
+        I: IntoIterator<Item = ExchangeRate<C>>,
+    {
+        for r in rates {
+            self.rates.add(r.from, r.to, r.date, r.rate);
+        }
+        self.resummarise();
+    }
+
+    /// Returns the amount of a transaction in the currency of the budget, converted
+    /// at the exchange rate on the date of the transaction.
+    ///
+    /// Returns `None` if the transaction is in a foreign currency and there is no
+    /// exchange rate available.
+    pub fn to_budget_currency(&self, t: &Transaction<C>) -> Option<C> {
+        self.rates.convert(
+            *t.amount(),
+            self.account_currency(t.account().as_deref()),
+            self.currency,
+            t.date().date_naive(),
+        )
+    }
+
+    /// Returns the unrealised exchange gains and losses of the accounts in a foreign
+    /// currency, valued on the given date.
+    pub fn fx_positions(&self, date: NaiveDate) -> Vec<FxPosition<C>> {
+        let mut positions = Vec::new();
+
+        for account in self.accounts.values() {
+            let currency = match account.currency() {
+                Some(c) if c != self.currency => c,
+                _ => continue,
+            };
+
+            let mut balance = C::zero();
+            let mut book_value = Some(C::zero());
+            for t in &self.transactions {
+                if t.account().as_deref() != Some(account.name()) || t.date().date_naive() > date {
+                    continue;
+                }
+
+                balance += *t.amount();
+                book_value = match (book_value, self.to_budget_currency(t)) {
+                    (Some(mut b), Some(x)) => {
+                        b += x;
+                        Some(b)
+                    }
+                    _ => None,
+                };
+            }
+
+            positions.push(FxPosition {
+                account: account.name().to_string(),
+                currency,
+                balance,
+                book_value,
+                market_value: self.rates.convert(balance, currency, self.currency, date),
+            });
+        }
+
+        positions
+    }
+
+    /// Returns the balance of a category at the end of the given month, in the
+    /// currency of the budget.
+    ///
+    /// This is the money allocated to the category up to the end of the month,
+    /// plus the transactions in the category, which are negative for spending.
+    /// The balance of a category includes the balances of its subcategories.
+    /// The transactions in income categories are available to assign instead,
+    /// so they aren't part of the balance.
+    pub fn category_balance(&self, category: &str, month: CalendarMonth) -> C {
+        let mut balance = C::zero();
+        let ids = match self.categories.get_id(category) {
+            Some(id) => self.categories.subtree(*id),
+            None => return balance,
+        };
+        let income = self.categories.income();
+
+        let in_range = |(m, c): &(CalendarMonth, CategoryID)| ids.contains(c) && *m <= month;
+        for (_, a) in self.allocations.iter().filter(|(k, _)| in_range(k)) {
+            balance += a.amount;
+        }
+        for ((_, c), s) in self.summaries.iter().filter(|(k, _)| in_range(k)) {
+            if !income.contains(c) {
+                balance += s.sum;
+            }
+        }
+        balance
+    }
+
+    /// Returns the amount allocated to a category for the given month, including
+    /// the amounts allocated to its subcategories.
+    pub fn allocation(&self, category: &str, month: CalendarMonth) -> C {
+        let mut amount = C::zero();
+        if let Some(id) = self.categories.get_id(category) {
+            for id in self.categories.subtree(*id) {
+                if let Some(a) = self.allocations.get(&(month, id)) {
+                    amount += a.amount;
+                }
+            }
+        }
+        amount
+    }
+
+    /// Returns the money which is available to assign to categories at the end of
+    /// the given month, in the currency of the budget.
+    ///
+    /// This is the income up to the end of the month, less everything allocated to
+    /// categories up to the end of the month. Income is the transactions without a
+    /// category, and the transactions in categories marked as income with
+    /// [`Budget::set_category_income`].
+    pub fn available(&self, month: CalendarMonth) -> C {
+        let mut available = C::zero();
+        for (_, s) in self.uncategorised_summaries.range(..=month) {
+            available += s.sum;
+        }
+        let income = self.categories.income();
+        for ((m, c), s) in &self.summaries {
+            if *m <= month && income.contains(c) {
+                available += s.sum;
+            }
+        }
+        for (_, a) in self.allocations.iter().filter(|((m, _), _)| *m <= month) {
+            available -= a.amount;
+        }
+        available
+    }
+
+    /// Assign an amount of the available money to a category for the given month.
+    ///
+    /// Returns an error if the category doesn't exist.
+    pub fn assign<'a, S>(
+        &mut self,
+        category: S,
+        amount: C,
+        month: CalendarMonth,
+    ) -> Result<(), UnknownCategoryError>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let id = self.category_id(&category.into())?;
+        self.allocations.entry((month, id)).or_default().amount += amount;
+        Ok(())
+    }
+
+    /// Returns the goal of a category, if it has one.
+    pub fn goal(&self, category: &str) -> Option<&Goal<C>> {
+        self.categories
+            .get_id(category)
+            .and_then(|id| self.goals.get(id))
+    }
+
+    /// Set the goal of a category, returning the goal it replaces.
+    ///
+    /// Returns an error if the category doesn't exist.
+    pub fn set_goal<'a, S>(
+        &mut self,
+        category: S,
+        goal: Goal<C>,
+    ) -> Result<Option<Goal<C>>, UnknownCategoryError>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let id = self.category_id(&category.into())?;
+        Ok(self.goals.insert(id, goal))
+    }
+
+    /// Remove the goal of a category, returning it.
+    pub fn remove_goal(&mut self, category: &str) -> Option<Goal<C>> {
+        let id = *self.categories.get_id(category)?;
+        self.goals.remove(&id)
+    }
+
+    /// Returns the progress of a category towards its goal in the given month, or
+    /// `None` if the category has no goal.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::{Budget, CalendarMonth, Goal};
+    ///
+    /// let month: CalendarMonth = "2026-01".parse().unwrap();
+    /// let mut budget: Budget = Budget::with_categories(vec!["holiday"]);
+    /// budget.set_goal("holiday", Goal::target_balance(1200.into(), "2026-12".parse().unwrap()));
+    ///
+    /// let status = budget.goal_status("holiday", month).unwrap();
+    /// assert_eq!(status.needed, 100.into());
+    ///
+    /// budget.assign("holiday", 60.into(), month).unwrap();
+    /// let status = budget.goal_status("holiday", month).unwrap();
+    /// assert_eq!(status.needed, 40.into());
+    /// assert_eq!(status.percent(), 5.into());
+    /// ```
+    pub fn goal_status(&self, category: &str, month: CalendarMonth) -> Option<GoalStatus<C>> {
+        let goal = self.goal(category)?;
+        let allocated = self.allocation(category, month);
+        let at_least_zero = |x: C| if x.is_negative() { C::zero() } else { x };
+
+        let (progress, needed) = match *goal.kind() {
+            GoalKind::TargetBalance { amount, by } => {
+                // spread what is left to save over the months until the target date
+                let before = self.category_balance(category, month.pred());
+                let months = CalendarMonth::range(month, by).count().max(1);
+                let months = C::from_count(u32::try_from(months).unwrap_or(u32::MAX));
+                let needed = (amount - before) / months - allocated;
+                (
+                    self.category_balance(category, month),
+                    at_least_zero(needed),
+                )
+            }
+            GoalKind::MonthlyFunding { amount } => (allocated, at_least_zero(amount - allocated)),
+            GoalKind::SpendingCap { .. } => {
+                // the spending in the subcategories counts towards the cap too
+                let mut spent = C::zero();
+                if let Some(id) = self.categories.get_id(category) {
+                    for id in self.categories.subtree(*id) {
+                        if let Some(s) = self.summaries.get(&(month, id)) {
+                            spent -= s.sum;
+                        }
+                    }
+                }
+                (spent, C::zero())
+            }
+        };
+
+        Some(GoalStatus {
+            month,
+            target: goal.amount(),
+            progress,
+            needed,
+        })
+    }
+
+    /// Assign the available money to the categories whose goals need funding in
+    /// the given month, in order of priority, until the money runs out.
+    ///
+    /// Returns the name of each category that was funded, with the amount assigned.
+    pub fn fund_goals(&mut self, month: CalendarMonth) -> Vec<(String, C)> {
+        let mut goals: Vec<(u32, String, CategoryID)> = self
+            .goals
+            .iter()
+            .filter_map(|(id, g)| {
+                let path = self.categories.path(self.categories.get(id)?);
+                Some((g.priority(), path, *id))
+            })
+            .collect();
+        goals.sort_unstable();
+
+        let mut available = self.available(month);
+        let mut funded = Vec::new();
+        for (_, category, id) in goals {
+            if available <= C::zero() {
+                break;
+            }
+            let needed = match self.goal_status(&category, month) {
+                Some(status) if status.needed > C::zero() => status.needed,
+                _ => continue,
+            };
+            let amount = if needed < available {
+                needed
+            } else {
+                available
+            };
+            self.allocations.entry((month, id)).or_default().amount += amount;
+            available -= amount;
+            funded.push((category, amount));
+        }
+        funded
+    }
+
+    /// Move an amount of money from one account to another.
+    ///
+    /// This adds a pair of linked transactions, one out of `from` and one into `to`.
+    /// If the accounts are in different currencies, the amount is in the currency
+    /// of `from` and is converted at the exchange rate on the date of the transfer.
+    ///
+    /// Returns an error if `from` and `to` are the same account, if either isn't an
+    /// account in the budget, or if there is no exchange rate between their
+    /// currencies.
+    pub fn transfer_between_accounts(
+        &mut self,
+        amount: C,
+        from: &str,
+        to: &str,
+        date: DateTime<Utc>,
+    ) -> Result<(), TransferError> {
+        if from == to {
+            return Err(TransferError::SameAccount);
+        }
+        if let Some(unknown) = [from, to].iter().find(|a| self.account(a).is_none()) {
+            return Err(TransferError::UnknownAccount(unknown.to_string()));
+        }
+
+        let from_currency = self.account_currency(Some(from));
+        let to_currency = self.account_currency(Some(to));
+        let converted = self
+            .rates
+            .convert(amount, from_currency, to_currency, date.date_naive())
+            .ok_or(TransferError::NoExchangeRate {
+                from: from_currency,
+                to: to_currency,
+            })?;
+
+        let out_id = Uuid::new_v4();
+        let in_id = Uuid::new_v4();
+        let mut out = Transaction::with_currency(-amount)
+            .with_date_transaction(date)
+            .with_uuid(out_id)
+            .with_transfer(in_id);
+        out.set_account(Some(from));
+        let mut into = Transaction::with_currency(converted)
+            .with_date_transaction(date)
+            .with_uuid(in_id)
+            .with_transfer(out_id);
+        into.set_account(Some(to));
+
+        self.add(out).expect("transfers have no category");
+        self.add(into).expect("transfers have no category");
+        Ok(())
+    }
+
+    /// Move an amount of money from one category to another for the budget month
+    /// containing `date`.
+    ///
+    /// Returns an error if either category doesn't exist.
+    pub fn transfer<'a, S>(
+        &mut self,
+        amount: impl Into<C>,
+        from_category: S,
+        to_category: S,
+        date: impl Datelike,
+    ) -> Result<(), UnknownCategoryError>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let month = self.month_of(date);
+        let a = amount.into();
+        let from_id = self.category_id(&from_category.into())?;
+        let to_id = self.category_id(&to_category.into())?;
+        self.allocations.entry((month, from_id)).or_default().amount -= a;
+        self.allocations.entry((month, to_id)).or_default().amount += a;
+
+        Ok(())
+    }
+
+    /// Rename a category. The new name is the name of the category itself, not a
+    /// path; use [`Budget::set_category_parent`] to move a category.
+    ///
+    /// Transactions refer to their category by id, so they don't need to change.
+    /// Rules and payees which refer to the category, or to its subcategories, by a
+    /// name which no longer matches are changed to use the new path.
+    ///
+    /// Returns an error if there is no category with the old name, if the new name
+    /// is empty or contains ':', or if another category with the same parent
+    /// already has the new name.
+    pub fn rename_category<'a, S>(
+        &mut self,
+        old_name: S,
+        new_name: S,
+    ) -> Result<(), RenameCategoryError>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let old = old_name.into();
+        let new = new_name.into();
+
+        let id = *self
+            .categories
+            .get_id(&old)
+            .ok_or(RenameCategoryError::NoCategory)?;
+        if new.trim().is_empty() || new.contains(SEPARATOR) {
+            return Err(RenameCategoryError::InvalidName);
+        }
+        let parent = self.categories[&id].parent;
+        if self
+            .categories
+            .child(parent, &new)
+            .is_some_and(|other| other != id)
+        {
+            return Err(RenameCategoryError::NameTaken);
+        }
+        let references = self.category_references();
+        self.categories.get_mut(&id).unwrap().name = new.to_string();
+        self.update_category_references(references, &HashMap::new());
+        Ok(())
+    }
+
+    /// Returns the names that rules and payees use for categories, along with the
+    /// categories they refer to.
+    fn category_references(&self) -> Vec<(String, CategoryID)> {
+        let mut names: Vec<String> = self
+            .payees
+            .values()
+            .filter_map(|p| p.category().map(String::from))
+            .collect();
+        for rule in &self.rules {
+            let mut rule = rule.clone();
+            rule.map_categories(|c| names.push(c.clone()));
+        }
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let id = *self.categories.get_id(&name)?;
+                Some((name, id))
+            })
+            .collect()
+    }
+
+    /// Change the names that rules and payees use for categories which have been
+    /// renamed, moved or merged, so that they refer to the same categories as they
+    /// did before. `merged` maps the ids of merged categories to the categories
+    /// they were merged into.
+    fn update_category_references(
+        &mut self,
+        references: Vec<(String, CategoryID)>,
+        merged: &HashMap<CategoryID, CategoryID>,
+    ) {
+        let mut renames: HashMap<String, String> = HashMap::new();
+        for (name, mut id) in references {
+            while let Some(into) = merged.get(&id) {
+                id = *into;
+            }
+            let category = match self.categories.get(&id) {
+                Some(c) => c,
+                None => continue,
+            };
+            if self.categories.get_id(&name) != Some(&id) {
+                renames.insert(name, self.categories.path(category));
+            }
+        }
+        if renames.is_empty() {
+            return;
+        }
+
+        let rename = |name: &mut String| {
+            if let Some(new) = renames.get(name) {
+                *name = new.clone();
+            }
+        };
+        for rule in &mut self.rules {
+            rule.map_categories(rename);
+        }
+        for payee in self.payees.values_mut() {
+            if let Some(new) = payee.category().and_then(|c| renames.get(c)) {
+                let new = new.clone();
+                payee.set_category(Some(new));
+            }
+        }
+    }
+
+    /// Hide a category, or show it again. Hidden categories are left out of the
+    /// summary, but their transactions and allocations are kept.
+    ///
+    /// Returns false if there is no category with the name.
+    pub fn set_category_hidden(&mut self, category: &str, hidden: bool) -> bool {
+        match self.categories.get_id(category).copied() {
+            Some(id) => {
+                self.categories.get_mut(&id).unwrap().hidden = hidden;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Mark a category as income, or not. The money which comes into an income
+    /// category, and its subcategories, is available to assign to other
+    /// categories rather than kept in the category.
+    ///
+    /// Returns false if there is no category with the name.
+    pub fn set_category_income(&mut self, category: &str, income: bool) -> bool {
+        match self.categories.get_id(category).copied() {
+            Some(id) => {
+                self.categories.get_mut(&id).unwrap().income = income;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if a category is income, because it or one of its parents is
+    /// marked as income.
+    pub fn is_income(&self, category: &str) -> bool {
+        self.categories
+            .get_id(category)
+            .is_some_and(|id| self.categories.income().contains(id))
+    }
+
+    /// Merge one category into another, moving its transactions, allocations and
+    /// goal, and then removing it.
+    ///
+    /// The subcategories of `from` become subcategories of `into`, and are merged
+    /// with any subcategories of `into` which have the same name. The goal of
+    /// `into` is kept if both categories have one. Schedules, rules and payees
+    /// which refer to `from` are changed to refer to `into`. A category can't be
+    /// merged into one of its own subcategories.
+    ///
+    /// Returns the number of transactions which were moved, or an error if either
+    /// category doesn't exist.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::{Budget, CalendarMonth, Transaction};
+    ///
+    /// let mut budget = Budget::<f64>::with_categories(vec!["food", "groceries"]);
+    /// let month = CalendarMonth::from(chrono::Utc::now());
+    /// budget.assign("food", 100.0, month).unwrap();
+    /// budget.add(Transaction::with_currency(-30.0).with_category("food")).unwrap();
+    ///
+    /// assert_eq!(budget.merge_category("food", "groceries"), Ok(1));
+    /// assert!(budget.categories().all(|c| c.name() != "food"));
+    /// assert_eq!(budget.category_balance("groceries", month), 70.0);
+    /// ```
+    pub fn merge_category(&mut self, from: &str, into: &str) -> Result<usize, RemoveCategoryError> {
+        let from_id = *self
+            .categories
+            .get_id(from)
+            .ok_or(RemoveCategoryError::NoCategory)?;
+        let into_id = *self
+            .categories
+            .get_id(into)
+            .ok_or(RemoveCategoryError::NoTarget)?;
+        if self.categories.subtree(from_id).contains(&into_id) {
+            return Err(RemoveCategoryError::SameCategory);
+        }
+        let references = self.category_references();
+        let mut merged = HashMap::new();
+        let moved = self.merge_category_id(from_id, into_id, &mut merged);
+        self.update_category_references(references, &merged);
+        Ok(moved)
+    }
+
+    fn merge_category_id(
+        &mut self,
+        from_id: CategoryID,
+        into_id: CategoryID,
+        merged: &mut HashMap<CategoryID, CategoryID>,
+    ) -> usize {
+        let mut moved = 0;
+        for child in self.sorted_children(Some(from_id)) {
+            let name = self.categories[&child].name.clone();
+            match self.categories.child(Some(into_id), &name) {
+                Some(other) => moved += self.merge_category_id(child, other, merged),
+                None => {
+                    let sort = self
+                        .sorted_children(Some(into_id))
+                        .iter()
+                        .map(|c| self.categories[c].sort + 1)
+                        .max()
+                        .unwrap_or(0);
+                    let c = self.categories.get_mut(&child).unwrap();
+                    c.parent = Some(into_id);
+                    c.sort = sort;
+                }
+            }
+        }
+
+        let (from_ref, into_ref) = (CategoryRef::Id(from_id), CategoryRef::Id(into_id));
+        for t in &mut self.transactions {
+            if t.category() == Some(&from_ref) {
+                t.set_category_ref(Some(into_ref.clone()));
+                moved += 1;
+            }
+        }
+        for schedule in &mut self.schedules {
+            let t = schedule.template_mut();
+            if t.category() == Some(&from_ref) {
+                t.set_category_ref(Some(into_ref.clone()));
+            }
+        }
+
+        let allocations: Vec<_> = self
+            .allocations
+            .keys()
+            .filter(|(_, c)| *c == from_id)
+            .cloned()
+            .collect();
+        for (month, _) in allocations {
+            let a = self.allocations.remove(&(month, from_id)).unwrap();
+            self.allocations.entry((month, into_id)).or_default().amount += a.amount;
+        }
+        let summaries: Vec<_> = self
+            .summaries
+            .keys()
+            .filter(|(_, c)| *c == from_id)
+            .cloned()
+            .collect();
+        for (month, _) in summaries {
+            let s = self.summaries.remove(&(month, from_id)).unwrap();
+            let summary = self.summaries.entry((month, into_id)).or_default();
+            summary.n += s.n;
+            summary.sum += s.sum;
+        }
+
+        if let Some(goal) = self.goals.remove(&from_id) {
+            self.goals.entry(into_id).or_insert(goal);
+        }
+        self.categories.remove(&from_id);
+        merged.insert(from_id, into_id);
+        moved
+    }
+
+    /// Remove a category.
+    ///
+    /// If any transactions, allocations or schedules refer to the category, they
+    /// are moved to the category `reassign_to`, as with
+    /// [`merge_category`](Budget::merge_category), along with its subcategories. Without somewhere to move them the category
+    /// isn't removed, and the error says how many there are.
+    pub fn remove_category(
+        &mut self,
+        name: &str,
+        reassign_to: Option<&str>,
+    ) -> Result<Category, RemoveCategoryError> {
+        let id = *self
+            .categories
+            .get_id(name)
+            .ok_or(RemoveCategoryError::NoCategory)?;
+
+        if let Some(into) = reassign_to {
+            let category = self.categories[&id].clone();
+            self.merge_category(name, into)?;
+            return Ok(category);
+        }
+
+        let subcategories = self.categories.subtree(id).len() - 1;
+        if subcategories > 0 {
+            return Err(RemoveCategoryError::HasSubcategories(subcategories));
+        }
+        let category = CategoryRef::Id(id);
+        let transactions = (&self.transactions)
+            .into_iter()
+            .filter(|t| t.category() == Some(&category))
+            .count();
+        let allocations = self
+            .allocations
+            .iter()
+            .filter(|((_, c), a)| *c == id && a.amount != C::zero())
+            .count();
+        let schedules = self
+            .schedules
+            .iter()
+            .filter(|s| s.template().category() == Some(&category))
+            .count();
+        if transactions > 0 || allocations > 0 || schedules > 0 {
+            return Err(RemoveCategoryError::InUse {
+                transactions,
+                allocations,
+                schedules,
+            });
+        }
+
+        let path = self.categories.path(&self.categories[&id]);
+        let names: HashSet<String> = self
+            .category_references()
+            .into_iter()
+            .filter(|(_, c)| *c == id)
+            .map(|(name, _)| name)
+            .collect();
+        for rule in &mut self.rules {
+            rule.remove_categories(|c| names.contains(c));
+        }
+        // a rule without any actions left does nothing
+        self.rules.retain(|r| !r.actions().is_empty());
+        self.allocations.retain(|(_, c), _| *c != id);
+        self.summaries.retain(|(_, c), _| *c != id);
+        self.goals.remove(&id);
+        for payee in self.payees.values_mut() {
+            if payee.category() == Some(name) || payee.category() == Some(&path) {
+                payee.set_category(None);
+            }
+        }
+        Ok(self.categories.remove(&id).unwrap())
+    }
+}
+
+/// The error returned when a transaction, allocation or goal names a category
+/// which doesn't exist. Categories are created with [`Budget::add_category`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownCategoryError(String);
+
+impl UnknownCategoryError {
+    /// Returns the name of the category which doesn't exist.
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for UnknownCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no category named '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownCategoryError {}
+
+/// An error which can be returned when removing or merging a category.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoveCategoryError {
+    /// There is no category with the name
+    NoCategory,
+    /// There is no category with the name to move the transactions to
+    NoTarget,
+    /// The category to move the transactions to is the category being removed,
+    /// or one of its subcategories
+    SameCategory,
+    /// The category has subcategories, and there is nowhere to move them
+    HasSubcategories(usize),
+    /// Transactions, allocations or schedules refer to the category, and there is
+    /// nowhere to move them
+    InUse {
+        /// The number of transactions in the category
+        transactions: usize,
+        /// The number of months with money allocated to the category
+        allocations: usize,
+        /// The number of scheduled transactions in the category
+        schedules: usize,
+    },
+}
+
+impl fmt::Display for RemoveCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RemoveCategoryError::NoCategory => write!(f, "no such category"),
+            RemoveCategoryError::NoTarget => {
+                write!(f, "the category to move the transactions to doesn't exist")
+            }
+            RemoveCategoryError::SameCategory => {
+                write!(
+                    f,
+                    "can't move a category's transactions to itself or its subcategories"
+                )
+            }
+            RemoveCategoryError::HasSubcategories(n) => {
+                write!(f, "the category has {} subcategories", n)
+            }
+            RemoveCategoryError::InUse {
+                transactions,
+                allocations,
+                schedules,
+            } => write!(
+                f,
+                "the category has {} transactions, {} months of allocations and {} \
+                 scheduled transactions",
+                transactions, allocations, schedules
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RemoveCategoryError {}
 
-    pub fn add(&mut self, t: Transaction<C>) {
-        let date: CalendarMonth = t.date().into();
+/// An error which can be returned when renaming a tag.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameTagError {
+    /// The new name is empty
+    EmptyName,
+    /// The new name is a subtag of the old one
+    IntoItself,
+}
 
-        if let Some(name) = t.category() {
-            let id = self.categories.get_or_create_id(name);
-            self.summaries.entry((date, id)).or_default().add(&t);
-        } else {
-            self.uncategorised_summaries
-                .entry(date)
-                .or_default()
-                .add(&t);
+impl fmt::Display for RenameTagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenameTagError::EmptyName => write!(f, "the new name is empty"),
+            RenameTagError::IntoItself => write!(f, "can't rename a tag to one of its subtags"),
         }
-
-        self.transactions.add(t);
     }
+}
 
-    pub fn transfer<'a, S>(
-        &mut self,
-        amount: impl Into<C>,
-        from_category: S,
-        to_category: S,
-        date: impl Datelike,
-    ) -> Result<(), ()>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        let month: CalendarMonth = date.into();
-        let a = amount.into();
-        let from_id = self.categories.get_or_create_id(from_category);
-        let to_id = self.categories.get_or_create_id(to_category);
-        self.allocations
-            .entry((month.clone(), from_id))
-            .or_default()
-            .amount -= a;
-        self.allocations.entry((month, to_id)).or_default().amount += a;
+impl std::error::Error for RenameTagError {}
 
-        Ok(())
+/// An error which can be returned when renaming a category.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameCategoryError {
+    /// There is no category with the name
+    NoCategory,
+    /// Another category with the same parent already has the new name. Use
+    /// [`Budget::merge_category`] to combine the two categories instead.
+    NameTaken,
+    /// The new name is empty, or contains the ':' which separates the parts of a path
+    InvalidName,
+}
+
+impl fmt::Display for RenameCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenameCategoryError::NoCategory => write!(f, "no such category"),
+            RenameCategoryError::NameTaken => {
+                write!(f, "there is already a category with the new name")
+            }
+            RenameCategoryError::InvalidName => {
+                write!(
+                    f,
+                    "a category name can't be empty or contain '{}'",
+                    SEPARATOR
+                )
+            }
+        }
     }
+}
 
-    pub fn rename_category<'a, S>(&mut self, old_name: S, new_name: S)
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        let old = old_name.into();
-        let new = new_name.into();
+impl std::error::Error for RenameCategoryError {}
 
-        if let Some(x) = self
-            .categories
-            .values_mut()
-            .find(|x| x.name == old.as_ref())
-        {
-            x.name = new.to_string();
+/// An error which can be returned when moving a category to another parent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveCategoryError {
+    /// There is no category with the name
+    NoCategory,
+    /// There is no category with the name of the new parent
+    NoParent,
+    /// The new parent is the category itself, or one of its subcategories
+    IntoItself,
+    /// The new parent already has a subcategory with the same name
+    NameTaken,
+}
+
+impl fmt::Display for MoveCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveCategoryError::NoCategory => write!(f, "no such category"),
+            MoveCategoryError::NoParent => write!(f, "no such parent category"),
+            MoveCategoryError::IntoItself => {
+                write!(f, "can't move a category into itself or its subcategories")
+            }
+            MoveCategoryError::NameTaken => {
+                write!(f, "the parent already has a subcategory with the same name")
+            }
         }
+    }
+}
 
-        if let Some(t) = (&mut self.transactions)
-            .into_iter()
-            .find(|t| t.category() == &Some(old.to_string()))
-        {
-            t.set_category(Some(new))
-        };
+impl std::error::Error for MoveCategoryError {}
+
+/// An error which can be returned when transferring money between accounts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferError {
+    /// The accounts are the same
+    SameAccount,
+    /// There is no account with the name
+    UnknownAccount(String),
+    /// There is no exchange rate between the currencies of the accounts
+    NoExchangeRate {
+        /// The currency of the account the money is moved out of
+        from: CurrencyCode,
+        /// The currency of the account the money is moved into
+        to: CurrencyCode,
+    },
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransferError::SameAccount => write!(f, "can't transfer from an account to itself"),
+            TransferError::UnknownAccount(name) => write!(f, "no such account '{}'", name),
+            TransferError::NoExchangeRate { from, to } => {
+                write!(f, "no exchange rate from {} to {}", from, to)
+            }
+        }
     }
 }
 
+impl std::error::Error for TransferError {}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct Categories {
     #[serde(flatten)]
@@ -161,51 +2304,218 @@ impl std::ops::DerefMut for Categories {
     }
 }
 
+/// The separator between the names of the categories in a path, such as "transport:car:fuel".
+const SEPARATOR: char = ':';
+
+fn path_segments(path: &str) -> impl Iterator<Item = &str> {
+    path.split(SEPARATOR).map(str::trim)
+}
+
 impl Categories {
+    /// Returns the id of the category with the given path, creating it and any
+    /// of its parents which don't already exist.
     fn get_or_create_id<'a, S>(&mut self, name: S) -> CategoryID
     where
         S: Into<Cow<'a, str>>,
     {
         let n = name.into();
-        if let Some(id) = self.get_id(&n) {
-            *id
-        } else {
-            let id = CategoryID::new_v4();
-            self.insert(id, Category::new(n));
-            id
+        match self.get_id(&n) {
+            Some(id) => *id,
+            None => self.create_path(&n),
+        }
+    }
+
+    /// Returns the id of the category with exactly the given path, creating it
+    /// and any of its parents which don't already exist.
+    fn create_path(&mut self, path: &str) -> CategoryID {
+        let mut parent = None;
+        for segment in path_segments(path) {
+            let id = match self.child(parent, segment) {
+                Some(id) => id,
+                None => {
+                    let id = CategoryID::new_v4();
+                    let mut category = Category::new(segment);
+                    category.parent = parent;
+                    self.insert(id, category);
+                    id
+                }
+            };
+            parent = Some(id);
         }
+        parent.expect("a path has at least one segment")
     }
 
+    /// Returns the id of the category with the given path.
+    ///
+    /// If no category has the path, the path may leave out the first parents of
+    /// the category, as long as only one category matches. So "fuel" finds
+    /// "transport:car:fuel" if there is no other category named "fuel".
     fn get_id<S: AsRef<str>>(&self, name: S) -> Option<&CategoryID> {
-        self.iter().find(|x| x.1.name == name.as_ref()).map(|x| x.0)
+        let id = self.find_path(name.as_ref()).or_else(|| {
+            let segments: Vec<&str> = path_segments(name.as_ref()).collect();
+            let mut matches = self.keys().filter(|id| self.ends_with(**id, &segments));
+            match (matches.next(), matches.next()) {
+                (Some(id), None) => Some(*id),
+                _ => None,
+            }
+        })?;
+        self.get_key_value(&id).map(|(id, _)| id)
+    }
+
+    /// Returns the id of the category with exactly the given path.
+    fn find_path(&self, path: &str) -> Option<CategoryID> {
+        path_segments(path).try_fold(None, |parent, segment| {
+            self.child(parent, segment).map(Some)
+        })?
+    }
+
+    /// Returns the id of the subcategory of `parent` with the given name, or of
+    /// the top-level category if `parent` is `None`.
+    fn child(&self, parent: Option<CategoryID>, name: &str) -> Option<CategoryID> {
+        self.iter()
+            .find(|(_, c)| c.parent == parent && c.name == name)
+            .map(|(id, _)| *id)
+    }
+
+    /// Returns true if the path of a category ends with the segments.
+    fn ends_with(&self, id: CategoryID, segments: &[&str]) -> bool {
+        let mut current = Some(id);
+        for segment in segments.iter().rev() {
+            match current.and_then(|id| self.get(&id)) {
+                Some(c) if c.name == *segment => current = c.parent,
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Returns the ids of a category and each of its parents, starting with the category.
+    fn ancestors(&self, id: CategoryID) -> Vec<CategoryID> {
+        let mut ids = vec![id];
+        while let Some(parent) = self.get(ids.last().unwrap()).and_then(|c| c.parent) {
+            ids.push(parent);
+        }
+        ids
+    }
+
+    /// Returns the ids of a category and all of its subcategories.
+    fn subtree(&self, id: CategoryID) -> Vec<CategoryID> {
+        let mut ids = vec![id];
+        let mut i = 0;
+        while i < ids.len() {
+            let parent = ids[i];
+            ids.extend(
+                self.iter()
+                    .filter(|(_, c)| c.parent == Some(parent))
+                    .map(|(id, _)| *id),
+            );
+            i += 1;
+        }
+        ids
+    }
+
+    /// Returns the ids of the income categories and their subcategories.
+    fn income(&self) -> BTreeSet<CategoryID> {
+        self.iter()
+            .filter(|(_, c)| c.income)
+            .flat_map(|(id, _)| self.subtree(*id))
+            .collect()
+    }
+
+    /// Returns the path of a category, such as "transport:car:fuel".
+    fn path(&self, category: &Category) -> String {
+        let mut names = vec![category.name.as_str()];
+        let mut parent = category.parent;
+        while let Some(c) = parent.and_then(|id| self.get(&id)) {
+            names.push(&c.name);
+            parent = c.parent;
+        }
+        names.reverse();
+        names.join(&SEPARATOR.to_string())
+    }
+}
+
+/// Move a name to a position in a list of names, if it is in the list.
+fn reposition(names: &mut Vec<String>, name: &str, position: usize) {
+    if let Some(i) = names.iter().position(|n| n == name) {
+        let name = names.remove(i);
+        names.insert(position.min(names.len()), name);
     }
 }
 
+/// A group of categories, used for organising the budget.
 #[derive(Serialize, Deserialize, Debug)]
-struct MasterCategory {
+pub struct MasterCategory {
     name: String,
     sort: i32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl MasterCategory {
+    /// Returns the name of the master category.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the position of the master category among the others.
+    pub fn sort(&self) -> i32 {
+        self.sort
+    }
+}
+
+/// A spending category, or 'envelope'.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Category {
     name: String,
     sort: i32,
     hidden: bool,
+
+    /// Whether the money which comes into the category is income, which is
+    /// available to assign rather than kept in the category
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    income: bool,
+
+    /// The master category that this category is grouped under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    master_category: Option<CategoryID>,
+
+    /// The category that this category is a subcategory of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<CategoryID>,
 }
 
 impl Category {
+    /// Create a new, visible category with the given name.
     pub fn new<S: Into<String>>(name: S) -> Self {
         Category {
             name: name.into(),
             sort: 0,
             hidden: false,
+            income: false,
+            master_category: None,
+            parent: None,
         }
     }
 
+    /// Returns the name of the category.
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Returns the position of the category among the others in its master category.
+    pub fn sort(&self) -> i32 {
+        self.sort
+    }
+
+    /// Returns true if the category is hidden from the summary.
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Returns true if the category is marked as income. Its subcategories are
+    /// income too.
+    pub fn income(&self) -> bool {
+        self.income
+    }
 }
 
 #[derive(Default, Debug, Serialize, Deserialize)]
@@ -229,9 +2539,71 @@ impl<C> Summary<C>
 where
     C: Currency,
 {
-    fn add(&mut self, t: &Transaction<C>) {
+    fn add(&mut self, amount: C) {
         self.n += 1;
-        self.sum += *t.amount();
+        self.sum += amount;
+    }
+}
+
+/// The transactions with a tag.
+#[derive(Debug, Clone)]
+pub struct TagStats<C = d128>
+where
+    C: Currency,
+{
+    /// The tag
+    pub tag: String,
+    /// The number of transactions with the tag or one of its subtags
+    pub count: usize,
+    /// The total of the transactions, in the currency of the budget
+    pub total: C,
+    /// The number of transactions left out of the total, because there is no
+    /// exchange rate for their currency
+    pub unconverted: usize,
+    /// The date of the latest transaction
+    pub last: Option<NaiveDate>,
+}
+
+/// A change made to a transaction by the rules of a budget.
+#[derive(Debug, Clone)]
+pub struct RuleChange<C = d128>
+where
+    C: Currency,
+{
+    /// The transaction before the rules were run
+    pub before: Transaction<C>,
+    /// The transaction after the rules were run, or its parts if it was split
+    pub after: Vec<Transaction<C>>,
+}
+
+/// The value of a foreign currency account in the currency of the budget.
+#[derive(Debug, Clone)]
+pub struct FxPosition<C = d128>
+where
+    C: Currency,
+{
+    /// The name of the account
+    pub account: String,
+    /// The currency of the account
+    pub currency: CurrencyCode,
+    /// The balance of the account, in the currency of the account
+    pub balance: C,
+    /// The sum of the transactions in the account, each converted at the exchange rate on the
+    /// date of the transaction. This is `None` if any of the transactions couldn't be converted.
+    pub book_value: Option<C>,
+    /// The balance of the account converted at the current exchange rate
+    pub market_value: Option<C>,
+}
+
+impl<C> FxPosition<C>
+where
+    C: Currency,
+{
+    /// Returns the unrealised gain (or loss, if negative) due to changes in the exchange rate.
+    pub fn gain(&self) -> Option<C> {
+        let mut gain = self.market_value?;
+        gain -= self.book_value?;
+        Some(gain)
     }
 }
 
@@ -243,18 +2615,729 @@ where
         let mut budget = Budget::default();
 
         for transaction in ledger {
-            budget.add(transaction);
+            // the categories come from the transactions
+            if let Some(name) = transaction.category_name() {
+                if budget.categories.get_id(name).is_none() {
+                    budget.categories.create_path(name);
+                }
+            }
+            budget.add(transaction).expect("the category exists");
         }
 
         budget
     }
 }
 
+impl<C> serde::Serialize for Budget<C>
+where
+    C: Currency + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Budget::serialize(self, serializer)
+    }
+}
+
+impl<'de, C> serde::Deserialize<'de> for Budget<C>
+where
+    C: Currency + serde::Deserialize<'de>,
+{
+    /// Load a budget, and bring it up to date if it was saved by an earlier
+    /// version. See [`Budget::upgrade`].
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut budget = Budget::deserialize(deserializer)?;
+        let upgraded = budget.upgrade();
+        if upgraded > 0 {
+            log::info!("upgraded {} transactions and schedules", upgraded);
+        }
+        Ok(budget)
+    }
+}
+
 impl<C> From<Budget<C>> for Ledger<C>
 where
     C: Currency,
 {
-    fn from(budget: Budget<C>) -> Ledger<C> {
-        budget.transactions
+    fn from(mut budget: Budget<C>) -> Ledger<C> {
+        let ledger = std::mem::take(&mut budget.transactions);
+        Ledger::from_transactions(ledger.into_iter().map(|t| budget.with_category_name(t)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Action, Share};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn edit_transaction() {
+        let mut budget = Budget::<f64>::with_categories(vec!["food", "rent"]);
+        let month = CalendarMonth::from(Utc.with_ymd_and_hms(2026, 3, 5, 0, 0, 0).unwrap());
+        let t = Transaction::with_currency(-10.0)
+            .with_category("food")
+            .with_date_transaction(Utc.with_ymd_and_hms(2026, 3, 5, 0, 0, 0).unwrap());
+        let uuid = *t.uuid();
+        budget.add(t).unwrap();
+
+        assert_eq!(
+            budget.edit_transaction(&uuid, |t| {
+                t.set_amount(-25.0);
+                t.set_category(Some("rent"));
+            }),
+            Ok(true)
+        );
+        assert_eq!(budget.category_balance("food", month), 0.0);
+        assert_eq!(budget.category_balance("rent", month), -25.0);
+        assert_eq!(budget.edit_transaction(&Uuid::new_v4(), |_| {}), Ok(false));
+
+        // a category has to exist before a transaction can be moved into it
+        assert_eq!(
+            budget.edit_transaction(&uuid, |t| t.set_category(Some("fuel"))),
+            Err(UnknownCategoryError("fuel".to_string()))
+        );
+        assert_eq!(budget.category_balance("rent", month), -25.0);
+        assert!(budget.category("fuel").is_none());
+        assert!(budget
+            .assign("fuel", 10.0, month)
+            .is_err_and(|e| e.name() == "fuel"));
+    }
+
+    #[test]
+    fn foreign_currency_account() {
+        let gbp: CurrencyCode = "GBP".parse().unwrap();
+        let eur: CurrencyCode = "EUR".parse().unwrap();
+        let jan = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+
+        let mut budget = Budget::<f64>::default();
+        budget.set_currency(gbp);
+        budget
+            .add_account(Account::new("euro").with_currency(eur))
+            .unwrap();
+        assert!(budget.add_account(Account::new("euro")).is_err());
+
+        let t = Transaction::with_currency(100.0)
+            .with_account("euro")
+            .with_date_transaction(Utc.with_ymd_and_hms(2026, 1, 5, 0, 0, 0).unwrap());
+        assert_eq!(budget.to_budget_currency(&t), None);
+
+        // without an exchange rate the transaction is left out, rather than
+        // counted in the wrong currency
+        budget.add(t).unwrap();
+        assert_eq!(budget.unconverted().count(), 1);
+        assert!(budget.uncategorised_summaries.is_empty());
+
+        budget.add_rate(eur, gbp, jan(1), 0.5);
+        budget.add_rate(eur, gbp, jan(10), 0.8);
+        assert_eq!(
+            budget.uncategorised_summaries.values().next().unwrap().sum,
+            50.0
+        );
+        assert_eq!(budget.unconverted().count(), 0);
+
+        let position = &budget.fx_positions(jan(20))[0];
+        assert_eq!(position.balance, 100.0);
+        assert_eq!(position.book_value, Some(50.0));
+        assert_eq!(position.market_value, Some(80.0));
+        assert_eq!(position.gain(), Some(30.0));
+    }
+
+    #[test]
+    fn income_categories() {
+        let month: CalendarMonth = "2026-03".parse().unwrap();
+        let date = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+        let mut budget = Budget::<f64>::with_categories(vec!["income", "food"]);
+        budget
+            .add(
+                Transaction::with_currency(1000.0)
+                    .with_category("income")
+                    .with_date_transaction(date),
+            )
+            .unwrap();
+        budget
+            .add(Transaction::with_currency(50.0).with_date_transaction(date))
+            .unwrap();
+        budget.assign("food", 200.0, month).unwrap();
+
+        // until it is marked as income, the salary is kept in its category
+        assert_eq!(budget.available(month), -150.0);
+        assert_eq!(budget.category_balance("income", month), 1000.0);
+
+        assert!(budget.set_category_income("income", true));
+        budget.add_category("income:bonus").unwrap();
+        budget
+            .add(
+                Transaction::with_currency(100.0)
+                    .with_category("bonus")
+                    .with_date_transaction(date),
+            )
+            .unwrap();
+        assert!(budget.is_income("bonus"));
+        assert!(!budget.is_income("food"));
+        assert_eq!(budget.available(month), 950.0);
+        assert_eq!(budget.category_balance("income", month), 0.0);
+        assert_eq!(budget.category_balance("food", month), 200.0);
+        assert!(!budget.set_category_income("missing", true));
+    }
+
+    #[test]
+    fn budget_months() {
+        let feb: CalendarMonth = "2026-02".parse().unwrap();
+        let date = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let mut budget = Budget::<f64>::with_categories(vec!["food", "rent"]);
+        budget
+            .add(
+                Transaction::with_currency(-30.0)
+                    .with_category("food")
+                    .with_date_transaction(date),
+            )
+            .unwrap();
+        assert_eq!(budget.category_balance("food", feb), 0.0);
+
+        // with months starting on the 25th, the 10th of March is in February
+        budget.set_first_day_of_month(25);
+        assert_eq!(budget.month_of(date), feb);
+        assert_eq!(budget.category_balance("food", feb), -30.0);
+        budget.transfer(50.0, "rent", "food", date).unwrap();
+        assert_eq!(budget.allocation("food", feb), 50.0);
+    }
+
+    #[test]
+    fn transfer_between_accounts() {
+        let mut budget = Budget::<f64>::default();
+        budget.add_account(Account::new("current")).unwrap();
+        budget.add_account(Account::new("savings")).unwrap();
+        let date = Utc.with_ymd_and_hms(2026, 1, 5, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            budget.transfer_between_accounts(100.0, "current", "current", date),
+            Err(TransferError::SameAccount)
+        );
+        assert_eq!(
+            budget.transfer_between_accounts(100.0, "current", "pension", date),
+            Err(TransferError::UnknownAccount("pension".to_string()))
+        );
+        assert_eq!(budget.ledger().into_iter().count(), 0);
+
+        budget
+            .transfer_between_accounts(100.0, "current", "savings", date)
+            .unwrap();
+        let amounts: Vec<_> = budget.ledger().into_iter().map(|t| *t.amount()).collect();
+        assert_eq!(amounts, vec![-100.0, 100.0]);
+
+        // money in one currency isn't added to an account in another
+        let (gbp, eur) = ("GBP".parse().unwrap(), "EUR".parse().unwrap());
+        budget.set_currency(gbp);
+        budget
+            .add_account(Account::new("euro").with_currency(eur))
+            .unwrap();
+        assert_eq!(
+            budget.transfer_between_accounts(100.0, "euro", "current", date),
+            Err(TransferError::NoExchangeRate { from: eur, to: gbp })
+        );
+        assert_eq!(budget.ledger().into_iter().count(), 2);
+        budget.add_rate(eur, gbp, date.date_naive(), 0.8);
+        budget
+            .transfer_between_accounts(100.0, "euro", "current", date)
+            .unwrap();
+        assert_eq!(budget.balance(Some("current"), date.date_naive()), -20.0);
+    }
+
+    #[test]
+    fn fund_goals_in_priority_order() {
+        let month: CalendarMonth = "2026-03".parse().unwrap();
+        let mut budget = Budget::<f64>::with_categories(vec!["rent", "food", "fun"]);
+        budget
+            .add(
+                Transaction::with_currency(1000.0)
+                    .with_date_transaction(Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()),
+            )
+            .unwrap();
+        budget
+            .set_goal("fun", Goal::monthly_funding(500.0).with_priority(2))
+            .unwrap();
+        budget
+            .set_goal("rent", Goal::monthly_funding(800.0).with_priority(1))
+            .unwrap();
+        budget.set_goal("food", Goal::spending_cap(300.0)).unwrap();
+        budget.assign("rent", 100.0, month).unwrap();
+
+        let funded = budget.fund_goals(month);
+        assert_eq!(
+            funded,
+            vec![("rent".to_string(), 700.0), ("fun".to_string(), 200.0)]
+        );
+        assert_eq!(budget.available(month), 0.0);
+        assert_eq!(budget.goal_status("fun", month).unwrap().needed, 300.0);
+        assert_eq!(budget.goal_status("food", month).unwrap().needed, 0.0);
+    }
+
+    #[test]
+    fn rules_on_import_and_apply() {
+        let mut budget = Budget::<f64>::default();
+        budget
+            .add(Transaction::with_currency(-5.0).with_payee(Some("Corner Cafe")))
+            .unwrap();
+        budget
+            .add_rule(
+                Rule::new()
+                    .with_payee("cafe".parse().unwrap())
+                    .with_action(Action::SetCategory("restaurants".to_string())),
+            )
+            .unwrap();
+
+        budget
+            .import(Transaction::with_currency(-3.0).with_payee(Some("CAFE NERO")))
+            .unwrap();
+        let imported = budget
+            .ledger()
+            .into_iter()
+            .find(|t| t.payee().as_deref() == Some("CAFE NERO"))
+            .unwrap();
+        assert_eq!(
+            budget.category_name(imported).as_deref(),
+            Some("restaurants")
+        );
+
+        // importing the same transactions again adds nothing
+        let date = Utc.with_ymd_and_hms(2026, 1, 5, 12, 0, 0).unwrap();
+        let rows = || {
+            vec![
+                Transaction::with_currency(-2.5)
+                    .with_payee(Some("CAFE NERO"))
+                    .with_date_transaction(date),
+                Transaction::with_currency(-2.5)
+                    .with_payee(Some("CAFE NERO"))
+                    .with_date_transaction(date),
+            ]
+        };
+        assert_eq!(budget.import_all(rows()), Ok(2));
+        assert_eq!(budget.import_all(rows()), Ok(0));
+        assert_eq!(budget.ledger().into_iter().count(), 4);
+
+        let changes = budget.apply_rules(|t| t.category().is_none()).unwrap();
+        assert_eq!(changes.len(), 1);
+        assert!(budget.ledger().into_iter().all(|t| t.category().is_some()));
+        assert!(budget.rule_changes(|_| true).is_empty());
+    }
+
+    #[test]
+    fn payee_aliases_and_defaults() {
+        let mut budget = Budget::<f64>::default();
+        budget.add_category("shopping").unwrap();
+        let payee = |p: &str| Transaction::with_currency(-10.0).with_payee(Some(p));
+        budget.add(payee("AMZN Mktp")).unwrap();
+        budget.add(payee("Amazon.com")).unwrap();
+        budget
+            .add_entry(payee("amazon").with_category("shopping"))
+            .unwrap();
+
+        // 'amazon' is remembered with its category
+        assert_eq!(budget.payee("Amazon").unwrap().category(), Some("shopping"));
+        assert_eq!(budget.add_payee_alias("amazon", "amzn".parse().unwrap()), 1);
+        assert_eq!(budget.merge_payee("Amazon.com", "amazon"), Ok(1));
+        assert_eq!(
+            budget.merge_payee("Amazon.co.uk", "amazon"),
+            Err(PayeeError::NoPayee("Amazon.co.uk".to_string()))
+        );
+
+        budget.import(payee("AMAZON.COM")).unwrap();
+        // a payee which differs only in case is the same payee
+        budget.add(payee("AMAZON")).unwrap();
+        let stats = budget.payee_stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].payee, "amazon");
+        assert_eq!(stats[0].count, 5);
+        assert_eq!(stats[0].total, -50.0);
+        assert!(budget
+            .ledger()
+            .into_iter()
+            .filter(|t| matches!(t.source(), Source::Import))
+            .all(|t| budget.category_name(t).as_deref() == Some("shopping")));
+
+        assert!(budget.rename_payee("amazon", "Amazon").is_ok());
+        assert!(budget.rename_payee("Amazon.co.uk", "Amazon UK").is_err());
+        assert!(budget
+            .payee("amzn uk")
+            .is_some_and(|p| p.name() == "Amazon"));
+    }
+
+    #[test]
+    fn master_category_order() {
+        let mut budget = Budget::<f64>::default();
+        for name in &["rent", "fuel", "bills", "food"] {
+            budget.add_category(*name).unwrap();
+        }
+        budget.add_master_category("housing").unwrap();
+        budget.add_master_category("living").unwrap();
+        assert!(budget.add_master_category("housing").is_err());
+
+        budget.move_category("rent", Some("housing"));
+        budget.move_category("bills", Some("housing"));
+        budget.move_category("food", Some("living"));
+        budget.set_category_position("bills", 0);
+        budget.set_master_category_position("living", 0);
+
+        let names = |budget: &Budget<f64>| -> Vec<(Option<String>, Vec<String>)> {
+            budget
+                .grouped_categories()
+                .into_iter()
+                .map(|(m, cs)| {
+                    (
+                        m.map(|m| m.name().to_string()),
+                        cs.iter().map(|c| c.name().to_string()).collect(),
+                    )
+                })
+                .collect()
+        };
+        let groups = names(&budget);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            groups[0],
+            (Some("living".to_string()), vec!["food".to_string()])
+        );
+        assert_eq!(groups[1].1, vec!["bills", "rent"]);
+        assert!(groups[2].0.is_none() && groups[2].1.contains(&"fuel".to_string()));
+
+        // removing a group leaves its categories ungrouped
+        assert_eq!(
+            budget.rename_master_category("living", "housing"),
+            Err("housing")
+        );
+        budget.rename_master_category("living", "everyday").unwrap();
+        assert!(budget.remove_master_category("housing").is_some());
+        let groups = names(&budget);
+        assert_eq!(groups[0].0.as_deref(), Some("everyday"));
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].1[groups[1].1.len() - 2..], ["bills", "rent"]);
+    }
+
+    #[test]
+    fn remove_scheduled_category() {
+        let mut budget = Budget::<f64>::with_categories(vec!["rent", "bills"]);
+        let rent = Transaction::with_currency(-800.0).with_category("rent");
+        let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let every_month = crate::Recurrence::Months { every: 1, day: 1 };
+        budget
+            .add_schedule(ScheduledTransaction::new(rent, start, every_month).unwrap())
+            .unwrap();
+
+        // the schedule would otherwise add its transactions without a category
+        assert_eq!(
+            budget.remove_category("rent", None).unwrap_err(),
+            RemoveCategoryError::InUse {
+                transactions: 0,
+                allocations: 0,
+                schedules: 1
+            }
+        );
+        budget.remove_category("rent", Some("bills")).unwrap();
+        let template = budget.schedules()[0].template();
+        assert_eq!(budget.category_name(template).as_deref(), Some("bills"));
+    }
+
+    #[test]
+    fn schedule_with_missing_category() {
+        let mut budget = Budget::<f64>::with_categories(vec!["bills"]);
+        let start = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let every_month = crate::Recurrence::Months { every: 1, day: 1 };
+        let bills = Transaction::with_currency(-50.0).with_category("bills");
+        budget
+            .add_schedule(ScheduledTransaction::new(bills, start, every_month).unwrap())
+            .unwrap();
+
+        // a schedule edited by hand can name a category which doesn't exist
+        let rent = Transaction::with_currency(-800.0).with_category("rent");
+        budget
+            .schedules
+            .push(ScheduledTransaction::new(rent, start, every_month).unwrap());
+        let today = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+        assert_eq!(budget.add_due_transactions(today), 3);
+
+        // its transactions are added once the category is back
+        budget.add_category("rent").unwrap();
+        assert_eq!(budget.add_due_transactions(today), 3);
+        assert_eq!(budget.add_due_transactions(today), 0);
+    }
+
+    #[test]
+    fn remove_category_with_reassignment() {
+        let mut budget = Budget::<f64>::with_categories(vec!["eating out", "restaurants", "spare"]);
+        let month = CalendarMonth::from(Utc::now());
+        budget.assign("eating out", 50.0, month).unwrap();
+        budget
+            .add(Transaction::with_currency(-20.0).with_category("eating out"))
+            .unwrap();
+        budget
+            .add(Transaction::with_currency(-5.0).with_category("restaurants"))
+            .unwrap();
+        budget.set_payee_category("cafe", Some("eating out".to_string()));
+
+        assert_eq!(
+            budget.remove_category("eating out", None).unwrap_err(),
+            RemoveCategoryError::InUse {
+                transactions: 1,
+                allocations: 1,
+                schedules: 0
+            }
+        );
+        assert_eq!(
+            budget.remove_category("missing", None).unwrap_err(),
+            RemoveCategoryError::NoCategory
+        );
+        budget
+            .add_rule(Rule::new().with_action(Action::SetCategory("spare".to_string())))
+            .unwrap();
+        let split = Action::split(vec![
+            Share {
+                category: "spare".to_string(),
+                percent: 50.0,
+            },
+            Share {
+                category: "restaurants".to_string(),
+                percent: 50.0,
+            },
+        ]);
+        let tag = Action::AddTags(vec!["out".to_string()]);
+        budget
+            .add_rule(
+                Rule::new()
+                    .with_action(split.unwrap())
+                    .with_action(tag.clone()),
+            )
+            .unwrap();
+        assert!(budget.remove_category("spare", None).is_ok());
+        // rules which only set the removed category go, and splits into it are dropped
+        assert_eq!(budget.rules().len(), 1);
+        assert_eq!(budget.rules()[0].actions(), [tag]);
+
+        assert_eq!(
+            budget.merge_category("eating out", "missing"),
+            Err(RemoveCategoryError::NoTarget)
+        );
+        assert_eq!(
+            budget
+                .remove_category("eating out", Some("missing"))
+                .unwrap_err(),
+            RemoveCategoryError::NoTarget
+        );
+        let removed = budget
+            .remove_category("eating out", Some("restaurants"))
+            .unwrap();
+        assert_eq!(removed.name(), "eating out");
+        assert_eq!(budget.categories().count(), 1);
+        assert_eq!(budget.allocation("restaurants", month), 50.0);
+        assert_eq!(budget.category_balance("restaurants", month), 25.0);
+        assert!(budget
+            .ledger()
+            .into_iter()
+            .all(|t| budget.category_name(t).as_deref() == Some("restaurants")));
+
+        assert!(budget.set_category_hidden("restaurants", true));
+        assert!(budget.categories().all(|c| c.hidden()));
+        assert!(!budget.set_category_hidden("missing", true));
+    }
+
+    #[test]
+    fn transactions_refer_to_categories_by_id() {
+        let mut budget = Budget::<f64>::with_categories(vec!["food"]);
+        let month = CalendarMonth::from(Utc::now());
+        for _ in 0..3 {
+            budget
+                .add(Transaction::with_currency(-10.0).with_category("food"))
+                .unwrap();
+        }
+        assert!(budget
+            .ledger()
+            .into_iter()
+            .all(|t| matches!(t.category(), Some(CategoryRef::Id(_)))));
+
+        // every transaction follows the category when it is renamed
+        budget.rename_category("food", "groceries").unwrap();
+        assert!(budget
+            .ledger()
+            .into_iter()
+            .all(|t| budget.category_name(t).as_deref() == Some("groceries")));
+        assert_eq!(budget.category_balance("groceries", month), -30.0);
+
+        // transactions from an earlier version refer to their category by name
+        let mut old = Transaction::with_currency(-5.0);
+        old.set_category_ref(Some(CategoryRef::Name("groceries".to_string())));
+        budget.transactions.add(old);
+        assert_eq!(budget.upgrade(), 1);
+        assert_eq!(budget.upgrade(), 0);
+
+        // renaming onto another category would leave two with the same name
+        budget.add_category("food").unwrap();
+        assert_eq!(
+            budget.rename_category("groceries", "food"),
+            Err(RenameCategoryError::NameTaken)
+        );
+        assert_eq!(
+            budget.rename_category("missing", "food"),
+            Err(RenameCategoryError::NoCategory)
+        );
+
+        let ledger = Ledger::from(budget);
+        assert_eq!(
+            ledger
+                .into_iter()
+                .filter(|t| t.category_name() == Some("groceries"))
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn nested_tags() {
+        let mut budget = Budget::<f64>::with_categories(Vec::<String>::new());
+        budget
+            .add(Transaction::with_currency(-300.0).with_tag("holiday/2026/flights"))
+            .unwrap();
+        budget
+            .add(
+                Transaction::with_currency(-80.0)
+                    .with_tag("holiday/2026")
+                    .with_tag("food"),
+            )
+            .unwrap();
+        budget
+            .add(Transaction::with_currency(-20.0).with_tag("holidays"))
+            .unwrap();
+        budget
+            .add_rule(Rule::new().with_action(Action::AddTags(vec!["holiday/2026".to_string()])))
+            .unwrap();
+
+        // a transaction is counted once in each of its tags and their parents
+        let stats = budget.tag_stats();
+        assert_eq!(
+            stats
+                .iter()
+                .map(|s| (s.tag.as_str(), s.count, s.total))
+                .collect::<Vec<_>>(),
+            vec![
+                ("food", 1, -80.0),
+                ("holiday", 2, -380.0),
+                ("holiday/2026", 2, -380.0),
+                ("holiday/2026/flights", 1, -300.0),
+                ("holidays", 1, -20.0),
+            ]
+        );
+
+        assert_eq!(
+            budget.rename_tag("holiday", ""),
+            Err(RenameTagError::EmptyName)
+        );
+        assert_eq!(
+            budget.rename_tag("holiday", "holiday/old"),
+            Err(RenameTagError::IntoItself)
+        );
+        assert_eq!(budget.rename_tag("holiday", "travel"), Ok(2));
+        assert!(budget.ledger().into_iter().all(|t| !t.has_tag("holiday")));
+        assert!(budget
+            .ledger()
+            .into_iter()
+            .any(|t| t.has_tag("travel/2026/flights")));
+        assert_eq!(
+            budget.rules()[0].actions(),
+            &[Action::AddTags(vec!["travel/2026".to_string()])]
+        );
+
+        // deleting a tag deletes its subtags, and rules which only added them
+        assert_eq!(budget.delete_tag("travel/2026"), 2);
+        assert_eq!(
+            budget.ledger().tags().collect::<Vec<_>>(),
+            vec!["food", "holidays"]
+        );
+        assert!(budget.rules().is_empty());
+    }
+
+    #[test]
+    fn category_tree() {
+        let mut budget = Budget::<f64>::with_categories(vec!["transport:car:fuel", "bike"]);
+        let month = CalendarMonth::from(Utc::now());
+        budget.assign("transport:car", 100.0, month).unwrap();
+        budget.assign("fuel", 50.0, month).unwrap();
+        budget
+            .add(Transaction::with_currency(-30.0).with_category("car:fuel"))
+            .unwrap();
+        budget
+            .add_rule(Rule::new().with_action(Action::SetCategory("fuel".to_string())))
+            .unwrap();
+
+        // a parent includes its subcategories
+        assert_eq!(budget.allocation("transport", month), 150.0);
+        assert_eq!(budget.category_balance("transport", month), 120.0);
+        assert_eq!(budget.category_balance("fuel", month), 20.0);
+        let t = budget.ledger().into_iter().next().unwrap();
+        assert_eq!(
+            budget.category_paths(t),
+            vec!["transport:car:fuel", "transport:car", "transport"]
+        );
+
+        // moving a subtree
+        assert_eq!(
+            budget.set_category_parent("transport", Some("fuel")),
+            Err(MoveCategoryError::IntoItself)
+        );
+        budget.add_category("bike:fuel").unwrap();
+        assert!(budget.set_category_parent("car", Some("bike")).is_ok());
+        let t = budget.ledger().into_iter().next().unwrap();
+        assert_eq!(budget.category_name(t).as_deref(), Some("bike:car:fuel"));
+        // "fuel" became ambiguous, so the rule was changed to use the path
+        assert_eq!(
+            budget.rules()[0].actions()[0],
+            Action::SetCategory("bike:car:fuel".to_string())
+        );
+
+        // merging a subtree merges subcategories with the same name
+        assert_eq!(budget.merge_category("bike:car", "bike"), Ok(1));
+        assert!(budget.category("bike:car").is_none());
+        let t = budget.ledger().into_iter().next().unwrap();
+        assert_eq!(budget.category_name(t).as_deref(), Some("bike:fuel"));
+        assert_eq!(budget.category_balance("bike", month), 120.0);
+        assert_eq!(
+            budget.remove_category("bike", None).unwrap_err(),
+            RemoveCategoryError::HasSubcategories(1)
+        );
+
+        // renaming can't move a category, or give it the name of a sibling
+        assert_eq!(
+            budget.rename_category("bike:fuel", "car:fuel"),
+            Err(RenameCategoryError::InvalidName)
+        );
+        assert_eq!(
+            budget.rename_category("bike", "transport"),
+            Err(RenameCategoryError::NameTaken)
+        );
+        assert!(budget.rename_category("bike:fuel", "transport").is_ok());
+    }
+
+    #[test]
+    fn separators_in_old_names() {
+        // earlier versions allowed ':' in the name of a category
+        let mut budget = Budget::<f64>::with_categories(vec!["home", "home/garden"]);
+        budget
+            .categories
+            .insert(CategoryID::new_v4(), Category::new("home:garden"));
+        budget
+            .rules
+            .push(Rule::new().with_action(Action::SetCategory("home:garden".to_string())));
+        budget.set_payee_category("nursery", Some("home:garden".to_string()));
+        let mut old = Transaction::with_currency(-5.0);
+        old.set_category_ref(Some(CategoryRef::Name("home:garden".to_string())));
+        budget.transactions.add(old);
+
+        assert_eq!(budget.upgrade(), 1);
+        assert_eq!(budget.categories().count(), 3);
+        let t = budget.ledger().into_iter().next().unwrap();
+        assert_eq!(budget.category_name(t).as_deref(), Some("home/garden 2"));
+        assert_eq!(
+            budget.rules()[0].actions()[0],
+            Action::SetCategory("home/garden 2".to_string())
+        );
+        assert_eq!(
+            budget.payee("nursery").and_then(|p| p.category()),
+            Some("home/garden 2")
+        );
     }
 }
@@ -1,23 +1,174 @@
 use decimal::d128;
-use std::{cmp, ops};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::str::FromStr;
+use std::{cmp, fmt, ops};
 
+/// The operations required of a type used to represent an amount of money.
+///
+/// Amounts must be signed, since a negative amount represents money flowing out
+/// of an account.
 pub trait Currency:
-    Default + cmp::PartialEq + ops::AddAssign + ops::Mul + ops::SubAssign + Copy
+    Default
+    + cmp::PartialEq
+    + cmp::PartialOrd
+    + ops::AddAssign
+    + ops::Mul<Output = Self>
+    + ops::Div<Output = Self>
+    + ops::Sub<Output = Self>
+    + ops::SubAssign
+    + ops::Neg<Output = Self>
+    + Copy
 {
+    /// Returns an amount of zero.
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    /// Parse an amount from a plain decimal string, such as "-1234.5".
+    ///
+    /// Leading and trailing whitespace is ignored.
+    fn parse(s: &str) -> Result<Self, ParseAmountError>;
+
+    /// Format the amount as a plain decimal string, rounded to the given number
+    /// of decimal places, such as "-1234.50".
+    fn format(&self, decimal_places: u32) -> String;
+
+    /// Returns a whole number of units, such as a number of months to divide an
+    /// amount by. A count too large for the type gives its largest value.
+    fn from_count(n: u32) -> Self;
+
+    /// Returns true if the amount is less than zero.
+    fn is_negative(&self) -> bool {
+        *self < Self::zero()
+    }
+
+    /// Returns the absolute value of the amount.
+    fn abs(self) -> Self {
+        if Currency::is_negative(&self) {
+            -self
+        } else {
+            self
+        }
+    }
+}
+
+/// The error returned when a string can't be parsed as an amount of money.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseAmountError {
+    input: String,
 }
 
-impl Currency for d128 {}
-impl Currency for i8 {}
-impl Currency for i16 {}
-impl Currency for i32 {}
-impl Currency for i64 {}
-impl Currency for i128 {}
+impl ParseAmountError {
+    pub(crate) fn new<S: Into<String>>(input: S) -> Self {
+        ParseAmountError {
+            input: input.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseAmountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a valid amount", self.input)
+    }
+}
+
+impl Error for ParseAmountError {}
 
-impl Currency for u8 {}
-impl Currency for u16 {}
-impl Currency for u32 {}
-impl Currency for u64 {}
-impl Currency for u128 {}
+impl Currency for d128 {
+    fn parse(s: &str) -> Result<Self, ParseAmountError> {
+        // d128 parses invalid input as NaN rather than returning an error
+        match d128::from_str(s.trim()) {
+            Ok(x) if x.is_finite() => Ok(x),
+            _ => Err(ParseAmountError::new(s)),
+        }
+    }
+
+    fn format(&self, decimal_places: u32) -> String {
+        let exponent = d128::from_str(&format!("1E-{}", decimal_places)).unwrap();
+        self.quantize(exponent).to_string()
+    }
+
+    fn from_count(n: u32) -> Self {
+        d128::from(n)
+    }
+}
 
-impl Currency for f32 {}
-impl Currency for f64 {}
+macro_rules! impl_currency_for_int {
+    ($($t:ty)*) => ($(
+        impl Currency for $t {
+            fn parse(s: &str) -> Result<Self, ParseAmountError> {
+                s.trim().parse().map_err(|_| ParseAmountError::new(s))
+            }
+
+            fn format(&self, decimal_places: u32) -> String {
+                if decimal_places == 0 {
+                    self.to_string()
+                } else {
+                    format!("{}.{:0<width$}", self, "", width = decimal_places as usize)
+                }
+            }
+
+            fn from_count(n: u32) -> Self {
+                <$t>::try_from(n).unwrap_or(<$t>::MAX)
+            }
+        }
+    )*)
+}
+
+macro_rules! impl_currency_for_float {
+    ($($t:ty)*) => ($(
+        impl Currency for $t {
+            fn parse(s: &str) -> Result<Self, ParseAmountError> {
+                match s.trim().parse::<$t>() {
+                    Ok(x) if x.is_finite() => Ok(x),
+                    _ => Err(ParseAmountError::new(s)),
+                }
+            }
+
+            fn format(&self, decimal_places: u32) -> String {
+                format!("{:.*}", decimal_places as usize, self)
+            }
+
+            fn from_count(n: u32) -> Self {
+                n as $t
+            }
+        }
+    )*)
+}
+
+impl_currency_for_int! { i8 i16 i32 i64 i128 }
+impl_currency_for_float! { f32 f64 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            <d128 as Currency>::parse(" -12.5 "),
+            Ok(d128::from(-125) / d128::from(10))
+        );
+        assert!(<d128 as Currency>::parse("twelve").is_err());
+        assert_eq!(<i32 as Currency>::parse("12"), Ok(12));
+        assert!(<f64 as Currency>::parse("NaN").is_err());
+    }
+
+    #[test]
+    fn format() {
+        let d = <d128 as Currency>::parse("1234.565").unwrap();
+        assert_eq!(Currency::format(&d, 2), "1234.56");
+        assert_eq!(Currency::format(&d, 0), "1235");
+        assert_eq!(Currency::format(&-12i32, 2), "-12.00");
+        assert_eq!(Currency::format(&1.5f64, 1), "1.5");
+    }
+
+    #[test]
+    fn from_count() {
+        assert_eq!(<d128 as Currency>::from_count(12), d128::from(12));
+        assert_eq!(<i64 as Currency>::from_count(12), 12);
+        assert_eq!(<i8 as Currency>::from_count(1000), i8::MAX);
+        assert_eq!(<f64 as Currency>::from_count(12), 12.0);
+    }
+}
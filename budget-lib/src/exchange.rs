@@ -0,0 +1,164 @@
+//! # Exchange rates.
+
+use crate::{Currency, CurrencyCode};
+use chrono::NaiveDate;
+use decimal::d128;
+use serde_derive::{Deserialize, Serialize};
+
+/// The value of one unit of the `from` currency in the `to` currency, on a given date.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRate<C = d128>
+where
+    C: Currency,
+{
+    /// The date that the rate applies from
+    pub date: NaiveDate,
+    /// The currency being converted from
+    pub from: CurrencyCode,
+    /// The currency being converted to
+    pub to: CurrencyCode,
+    /// The number of units of `to` for each unit of `from`
+    pub rate: C,
+}
+
+/// A table of dated exchange rates between currencies.
+///
+/// A rate applies from its date until the date of the next rate for the same
+/// pair of currencies. A rate for one direction is also used, inverted, for the
+/// opposite direction if no rate has been given for that.
+///
+/// # Example
+/// ```
+/// use budget_lib::ExchangeRates;
+/// use chrono::NaiveDate;
+/// use decimal::d128;
+///
+/// let eur = "EUR".parse().unwrap();
+/// let gbp = "GBP".parse().unwrap();
+/// let date = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+///
+/// let mut rates = ExchangeRates::default();
+/// rates.add(eur, gbp, date(1), d128::from(2));
+/// rates.add(eur, gbp, date(10), d128::from(4));
+///
+/// assert_eq!(rates.rate(eur, gbp, date(5)), Some(d128::from(2)));
+/// assert_eq!(rates.rate(eur, gbp, date(20)), Some(d128::from(4)));
+/// assert_eq!(rates.rate(gbp, eur, date(20)), Some(d128::from(1) / d128::from(4)));
+/// ```
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ExchangeRates<C = d128>
+where
+    C: Currency,
+{
+    rates: Vec<ExchangeRate<C>>,
+}
+
+impl<C> Default for ExchangeRates<C>
+where
+    C: Currency,
+{
+    fn default() -> Self {
+        ExchangeRates { rates: Vec::new() }
+    }
+}
+
+impl<C> ExchangeRates<C>
+where
+    C: Currency,
+{
+    /// Add a rate to the table, replacing any existing rate for the same currencies and date.
+    pub fn add(&mut self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate, rate: C) {
+        self.rates
+            .retain(|r| !(r.from == from && r.to == to && r.date == date));
+        self.rates.push(ExchangeRate {
+            date,
+            from,
+            to,
+            rate,
+        });
+        self.rates.sort_by_key(|r| (r.from, r.to, r.date));
+    }
+
+    /// Returns an iterator over all the rates in the table.
+    pub fn iter(&self) -> impl Iterator<Item = &ExchangeRate<C>> {
+        self.rates.iter()
+    }
+
+    /// Returns the rate for converting between two currencies on the given date.
+    ///
+    /// This is the most recent rate on or before the date. If the date is before
+    /// the first rate in the table, the first rate is used.
+    pub fn rate(&self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate) -> Option<C> {
+        if from == to {
+            return C::parse("1").ok();
+        }
+
+        if let Some(rate) = self.direct_rate(from, to, date) {
+            return Some(rate);
+        }
+
+        let inverse = self.direct_rate(to, from, date)?;
+        if inverse == C::zero() {
+            None
+        } else {
+            Some(C::parse("1").ok()? / inverse)
+        }
+    }
+
+    fn direct_rate(&self, from: CurrencyCode, to: CurrencyCode, date: NaiveDate) -> Option<C> {
+        let mut pair = self.rates.iter().filter(|r| r.from == from && r.to == to);
+        let first = pair.next()?;
+
+        let mut rate = first.rate;
+        for r in pair.take_while(|r| r.date <= date) {
+            rate = r.rate;
+        }
+        Some(rate)
+    }
+
+    /// Convert an amount between currencies, using the rate on the given date.
+    pub fn convert(
+        &self,
+        amount: C,
+        from: CurrencyCode,
+        to: CurrencyCode,
+        date: NaiveDate,
+    ) -> Option<C> {
+        self.rate(from, to, date).map(|r| amount * r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_rates() {
+        let eur = "EUR".parse().unwrap();
+        let gbp = "GBP".parse().unwrap();
+        let usd = "USD".parse().unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+
+        let mut rates = ExchangeRates::<f64>::default();
+        assert_eq!(rates.rate(eur, eur, date), Some(1.0));
+        assert_eq!(rates.rate(eur, gbp, date), None);
+
+        rates.add(eur, gbp, date.succ_opt().unwrap(), 0.5);
+        assert_eq!(rates.convert(10.0, gbp, eur, date), Some(20.0));
+        assert_eq!(rates.rate(eur, usd, date), None);
+    }
+
+    #[test]
+    fn replace_rate() {
+        let eur = "EUR".parse().unwrap();
+        let gbp = "GBP".parse().unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+
+        let mut rates = ExchangeRates::<f64>::default();
+        rates.add(eur, gbp, date, 0.5);
+        rates.add(eur, gbp, date, 0.8);
+        assert_eq!(rates.iter().count(), 1);
+        assert_eq!(rates.rate(eur, gbp, date), Some(0.8));
+    }
+}
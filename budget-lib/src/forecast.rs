@@ -0,0 +1,281 @@
+//! # Forecasts.
+
+use crate::report::account_names;
+use crate::{Budget, CalendarMonth, Currency};
+use chrono::{Duration, NaiveDate};
+use decimal::d128;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// A projection of the balances of a budget's accounts and categories into the future.
+///
+/// The projection is made up of the occurrences of scheduled transactions, and
+/// the average discretionary spending (the spending which isn't scheduled) of
+/// the recent past, spread evenly over each day.
+///
+/// # Example
+/// ```
+/// use budget_lib::{Account, Budget, Forecast, Recurrence, ScheduledTransaction, Transaction};
+/// use chrono::NaiveDate;
+///
+/// let date = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+///
+/// let mut budget: Budget = Budget::default();
+/// budget.add_account(Account::new("current")).unwrap();
+/// budget.add(
+///     Transaction::new(1000)
+///         .with_account("current")
+///         .with_date_transaction("2026-01-01T12:00:00Z".parse().unwrap()),
+/// ).unwrap();
+/// let rent = Transaction::new(-400).with_account("current");
+/// budget.add_schedule(ScheduledTransaction::new(
+///     rent,
+///     date(2, 1),
+///     Recurrence::Months { every: 1, day: 1 },
+/// ).unwrap()).unwrap();
+///
+/// let forecast = Forecast::new(&budget, date(1, 15), 4, 3);
+/// let current = &forecast.accounts()[0];
+///
+/// let balances: Vec<_> = [1000, 600, 200, -200].iter().map(|&b| b.into()).collect();
+/// assert_eq!(current.balances, balances);
+/// assert_eq!(current.first_negative, Some(date(4, 1)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Forecast<C = d128>
+where
+    C: Currency,
+{
+    months: Vec<CalendarMonth>,
+    accounts: Vec<AccountForecast<C>>,
+    categories: Vec<CategoryForecast<C>>,
+}
+
+/// The projected balance of an account in a [`Forecast`].
+#[derive(Debug, Clone)]
+pub struct AccountForecast<C = d128>
+where
+    C: Currency,
+{
+    /// The name of the account, or `None` for transactions without an account
+    pub account: Option<String>,
+    /// The balance today, in the currency of the account
+    pub opening: C,
+    /// The projected balance at the end of each month of the forecast
+    pub balances: Vec<C>,
+    /// The first date that the balance is projected to be below zero, if it
+    /// falls below zero within the forecast (or already has)
+    pub first_negative: Option<NaiveDate>,
+}
+
+/// The projected balance of a category in a [`Forecast`].
+#[derive(Debug, Clone)]
+pub struct CategoryForecast<C = d128>
+where
+    C: Currency,
+{
+    /// The name of the category
+    pub category: String,
+    /// The balance today, in the currency of the budget
+    pub opening: C,
+    /// The projected balance at the end of each month of the forecast
+    pub balances: Vec<C>,
+}
+
+impl<C> Forecast<C>
+where
+    C: Currency,
+{
+    /// Create a forecast from `today` until the end of the month `months - 1`
+    /// months later, so that the current month is the first month of the forecast.
+    ///
+    /// Discretionary spending is averaged over the `history` whole months before
+    /// the current month.
+    pub fn new(budget: &Budget<C>, today: NaiveDate, months: u32, history: u32) -> Self {
+        let first_day = budget.first_day_of_month();
+        let this_month = budget.month_of(today);
+        let last_month = (1..months.max(1)).fold(this_month, |m, _| m.succ());
+        let month_list: Vec<CalendarMonth> = CalendarMonth::range(this_month, last_month).collect();
+        let end = last_month.end(first_day);
+
+        // the discretionary spending per day, by account in the account currency
+        // and by category in the budget currency
+        let history_end = this_month.pred();
+        let history_start = (1..history.max(1)).fold(history_end, |m, _| m.pred());
+        let days = (history_end.end(first_day) - history_start.start(first_day)).num_days() + 1;
+        let days = C::from_count(u32::try_from(days).unwrap_or(u32::MAX));
+
+        let mut by_account: HashMap<Option<String>, C> = HashMap::new();
+        let mut by_category: HashMap<String, C> = HashMap::new();
+        if history > 0 {
+            for t in budget.ledger() {
+                let month = budget.month_of(t.date());
+                if month < history_start
+                    || month > history_end
+                    || t.is_scheduled()
+                    || t.is_transfer()
+                    || !budget.is_on_budget(t)
+                    || !t.amount().is_negative()
+                {
+                    continue;
+                }
+                *by_account
+                    .entry(t.account().clone())
+                    .or_insert_with(C::zero) += *t.amount() / days;
+                // spending without an exchange rate is left out of the categories
+                let Some(amount) = budget.to_budget_currency(t) else {
+                    continue;
+                };
+                // spending in a subcategory is spending in its parents too
+                for c in budget.category_paths(t) {
+                    *by_category.entry(c).or_insert_with(C::zero) += amount / days;
+                }
+            }
+        }
+
+        // the occurrences of scheduled transactions after today
+        let scheduled: Vec<_> = budget
+            .schedules()
+            .iter()
+            .flat_map(|s| s.upcoming(end).map(move |d| s.instance(d)))
+            .filter(|t| t.date().date_naive() > today)
+            .collect();
+
+        let accounts = account_names(budget)
+            .into_iter()
+            .map(|account| {
+                let opening = budget.balance(account.as_deref(), today);
+                let daily = by_account.get(&account).cloned().unwrap_or_else(C::zero);
+                let mut balances = Vec::new();
+                let mut first_negative = if opening.is_negative() {
+                    Some(today)
+                } else {
+                    None
+                };
+
+                let mut balance = opening;
+                let mut date = today;
+                while date < end {
+                    date += Duration::days(1);
+                    balance += daily;
+                    for t in scheduled.iter().filter(|t| t.date().date_naive() == date) {
+                        if t.account() == &account {
+                            balance += *t.amount();
+                        }
+                    }
+                    if first_negative.is_none() && balance.is_negative() {
+                        first_negative = Some(date);
+                    }
+                    if date == budget.month_of(date).end(first_day) {
+                        balances.push(balance);
+                    }
+                }
+                if balances.len() < month_list.len() {
+                    // today is the last day of the forecast
+                    balances.push(balance);
+                }
+
+                AccountForecast {
+                    account,
+                    opening,
+                    balances,
+                    first_negative,
+                }
+            })
+            .collect();
+
+        let categories = budget
+            .grouped_categories()
+            .into_iter()
+            .flat_map(|(_, categories)| categories)
+            .map(|c| budget.category_path(c))
+            .map(|category| {
+                let opening = budget.category_balance(&category, this_month);
+                let daily = by_category.get(&category).cloned().unwrap_or_else(C::zero);
+                let mut balance = opening;
+                let balances = month_list
+                    .iter()
+                    .map(|m| {
+                        let first = if *m == this_month {
+                            today + Duration::days(1)
+                        } else {
+                            m.start(first_day)
+                        };
+                        let mut date = first;
+                        while date <= m.end(first_day) {
+                            balance += daily;
+                            date += Duration::days(1);
+                        }
+                        for t in scheduled.iter().filter(|t| {
+                            budget.month_of(t.date()) == *m
+                                && budget.category_paths(t).contains(&category)
+                        }) {
+                            if let Some(amount) = budget.to_budget_currency(t) {
+                                balance += amount;
+                            }
+                        }
+                        balance
+                    })
+                    .collect();
+
+                CategoryForecast {
+                    category,
+                    opening,
+                    balances,
+                }
+            })
+            .collect();
+
+        Forecast {
+            months: month_list,
+            accounts,
+            categories,
+        }
+    }
+
+    /// Returns the months of the forecast, in order.
+    pub fn months(&self) -> &[CalendarMonth] {
+        &self.months
+    }
+
+    /// Returns the forecasts of each account, in order of name.
+    pub fn accounts(&self) -> &[AccountForecast<C>] {
+        &self.accounts
+    }
+
+    /// Returns the forecasts of each category, in the order of the budget.
+    pub fn categories(&self) -> &[CategoryForecast<C>] {
+        &self.categories
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, Recurrence, ScheduledTransaction, Transaction};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn scheduled_transactions_are_not_discretionary() {
+        let date = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        let mut budget = Budget::<f64>::default();
+        budget.add_account(Account::new("current")).unwrap();
+        budget
+            .add(
+                Transaction::with_currency(1000.0)
+                    .with_account("current")
+                    .with_date_transaction(Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap()),
+            )
+            .unwrap();
+        let rent = Transaction::with_currency(-300.0).with_account("current");
+        let every_month = Recurrence::Months { every: 1, day: 1 };
+        budget
+            .add_schedule(ScheduledTransaction::new(rent, date(1, 1), every_month).unwrap())
+            .unwrap();
+        assert_eq!(budget.add_due_transactions(date(3, 15)), 3);
+
+        // the rent is only forecast by its schedule, not as average spending too
+        let forecast = Forecast::new(&budget, date(3, 15), 2, 2);
+        assert_eq!(forecast.accounts()[0].balances, vec![100.0, -200.0]);
+    }
+}
@@ -0,0 +1,210 @@
+//! # Category goals.
+
+use crate::{CalendarMonth, Currency};
+use decimal::d128;
+use serde_derive::{Deserialize, Serialize};
+
+/// What a [`Goal`] aims for.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GoalKind<C = d128>
+where
+    C: Currency,
+{
+    /// Save up a balance in the category by the end of a month, such as for a holiday
+    TargetBalance {
+        /// The balance to save up
+        amount: C,
+        /// The month to save it by
+        by: CalendarMonth,
+    },
+    /// Assign the same amount to the category every month
+    MonthlyFunding {
+        /// The amount to assign each month
+        amount: C,
+    },
+    /// Spend no more than an amount from the category each month
+    SpendingCap {
+        /// The most to spend each month
+        amount: C,
+    },
+}
+
+/// A goal for a category, which determines how much money the category needs
+/// each month.
+///
+/// Goals with a lower priority number are funded first by [`Budget::fund_goals`].
+///
+/// [`Budget::fund_goals`]: crate::Budget::fund_goals
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Goal<C = d128>
+where
+    C: Currency,
+{
+    kind: GoalKind<C>,
+    #[serde(default)]
+    priority: u32,
+}
+
+impl<C> Goal<C>
+where
+    C: Currency,
+{
+    /// Create a goal to save up a balance by the end of the given month.
+    pub fn target_balance(amount: C, by: CalendarMonth) -> Self {
+        Goal::new(GoalKind::TargetBalance { amount, by })
+    }
+
+    /// Create a goal to assign the same amount every month.
+    pub fn monthly_funding(amount: C) -> Self {
+        Goal::new(GoalKind::MonthlyFunding { amount })
+    }
+
+    /// Create a goal to spend no more than an amount each month.
+    pub fn spending_cap(amount: C) -> Self {
+        Goal::new(GoalKind::SpendingCap { amount })
+    }
+
+    fn new(kind: GoalKind<C>) -> Self {
+        Goal { kind, priority: 0 }
+    }
+
+    /// Inline method for setting the priority of the goal.
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns what the goal aims for.
+    pub fn kind(&self) -> &GoalKind<C> {
+        &self.kind
+    }
+
+    /// Returns the priority of the goal. Lower numbers are funded first.
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Returns the amount that the goal aims for: the balance to save, the monthly
+    /// funding, or the spending cap.
+    pub fn amount(&self) -> C {
+        match self.kind {
+            GoalKind::TargetBalance { amount, .. }
+            | GoalKind::MonthlyFunding { amount }
+            | GoalKind::SpendingCap { amount } => amount,
+        }
+    }
+}
+
+/// The progress of a category towards its goal in a month.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GoalStatus<C = d128>
+where
+    C: Currency,
+{
+    /// The month
+    pub month: CalendarMonth,
+    /// The amount that the goal aims for
+    pub target: C,
+    /// The amount made towards the target: the balance of the category for a
+    /// target balance, the amount assigned this month for monthly funding, or the
+    /// amount spent this month for a spending cap
+    pub progress: C,
+    /// The amount which still needs to be assigned to the category this month to
+    /// stay on track. This is always zero for a spending cap.
+    pub needed: C,
+}
+
+impl<C> GoalStatus<C>
+where
+    C: Currency,
+{
+    /// Returns the progress as a percentage of the target. This can be over 100,
+    /// such as when more than the cap has been spent.
+    pub fn percent(&self) -> C {
+        if self.target == C::zero() {
+            return C::zero();
+        }
+        let hundred = C::parse("100").unwrap_or_else(|_| C::zero());
+        self.progress * hundred / self.target
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Budget, Transaction};
+    use chrono::{TimeZone, Utc};
+
+    fn month(s: &str) -> CalendarMonth {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn target_balance_status() {
+        let mut budget = Budget::<f64>::with_categories(vec!["holiday"]);
+        let goal = Goal::target_balance(1200.0, month("2026-12"));
+        budget.set_goal("holiday", goal).unwrap();
+        budget.assign("holiday", 300.0, month("2026-01")).unwrap();
+
+        // what is left is spread over the months until december
+        let status = budget.goal_status("holiday", month("2026-04")).unwrap();
+        assert_eq!(status.target, 1200.0);
+        assert_eq!(status.progress, 300.0);
+        assert_eq!(status.needed, 100.0);
+
+        budget.assign("holiday", 150.0, month("2026-04")).unwrap();
+        let status = budget.goal_status("holiday", month("2026-04")).unwrap();
+        assert_eq!(status.progress, 450.0);
+        assert_eq!(status.needed, 0.0);
+    }
+
+    #[test]
+    fn monthly_funding_status() {
+        let mut budget = Budget::<f64>::with_categories(vec!["rent"]);
+        budget
+            .set_goal("rent", Goal::monthly_funding(800.0))
+            .unwrap();
+        budget.assign("rent", 500.0, month("2026-03")).unwrap();
+
+        let status = budget.goal_status("rent", month("2026-03")).unwrap();
+        assert_eq!(status.progress, 500.0);
+        assert_eq!(status.needed, 300.0);
+        // each month is funded afresh
+        let status = budget.goal_status("rent", month("2026-04")).unwrap();
+        assert_eq!(status.needed, 800.0);
+    }
+
+    #[test]
+    fn spending_cap_status() {
+        let mut budget = Budget::<f64>::with_categories(vec!["food:groceries", "food:eating out"]);
+        budget.set_goal("food", Goal::spending_cap(200.0)).unwrap();
+        let date = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        for (amount, category) in [
+            (-50.0, "food"),
+            (-120.0, "groceries"),
+            (-80.0, "eating out"),
+        ] {
+            budget
+                .add(
+                    Transaction::with_currency(amount)
+                        .with_category(category)
+                        .with_date_transaction(date),
+                )
+                .unwrap();
+        }
+
+        // the spending in the subcategories counts towards the cap
+        let status = budget.goal_status("food", month("2026-03")).unwrap();
+        assert_eq!(status.progress, 250.0);
+        assert_eq!(status.needed, 0.0);
+        assert_eq!(status.percent(), 125.0);
+        let status = budget.goal_status("food", month("2026-04")).unwrap();
+        assert_eq!(status.progress, 0.0);
+    }
+
+    #[test]
+    fn no_goal() {
+        let budget = Budget::<f64>::with_categories(vec!["rent"]);
+        assert_eq!(budget.goal_status("rent", month("2026-03")), None);
+    }
+}
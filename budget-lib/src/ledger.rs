@@ -1,7 +1,9 @@
+use crate::transaction::{is_within_tag, rename_tag};
 use crate::Currency;
 use crate::Transaction;
 use decimal::d128;
 use serde_derive::{Deserialize, Serialize};
+use uuid::Uuid;
 
 // this is an intentially simplistic collection type. Once I've been using this for a while, and know what i actually need it to be able to do, then I
 // can start optimising it and getting clever about it.
@@ -54,14 +56,64 @@ where
         self.transactions.sort_by_key(Transaction::<C>::date)
     }
 
-    pub fn categories(&self) -> impl Iterator<Item = &String> {
-        self.transactions.iter().filter_map(|x| x.category().into())
+    /// Returns an iterator over the tags of the transactions in the ledger.
+    ///
+    /// Tags are not deduplicated.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.transactions
+            .iter()
+            .flat_map(|t| t.tags().map(String::as_str))
     }
 
+    /// Rename a tag, and its subtags, in every transaction in the ledger. Renaming
+    /// "holiday" to "travel" renames "holiday/2026" to "travel/2026" too.
+    ///
+    /// Returns the number of transactions which were changed.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::{Ledger, Transaction};
+    ///
+    /// let mut ledger = Ledger::from_transactions(vec![
+    ///     Transaction::new(-10).with_tag("holiday/2026"),
+    ///     Transaction::new(-20).with_tag("holidays"),
+    /// ]);
+    ///
+    /// assert_eq!(ledger.rename_tag("holiday", "travel"), 1);
+    /// assert_eq!(ledger.tags().collect::<Vec<_>>(), vec!["travel/2026", "holidays"]);
+    /// ```
+    pub fn rename_tag(&mut self, old: &str, new: &str) -> usize {
+        self.map_tags(|t| Some(rename_tag(t, old, new)))
+    }
+
+    /// Remove a tag, and its subtags, from every transaction in the ledger.
+    ///
+    /// Returns the number of transactions which were changed.
+    pub fn delete_tag(&mut self, tag: &str) -> usize {
+        self.map_tags(|t| Some(t.to_string()).filter(|t| !is_within_tag(t, tag)))
+    }
+
+    fn map_tags<F: Fn(&str) -> Option<String>>(&mut self, f: F) -> usize {
+        let mut count = 0;
+        for t in &mut self.transactions {
+            if t.map_tags(&f) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Add a transaction to the ledger, maintaining the order by date.
     pub fn add(&mut self, t: Transaction<C>) {
         self.transactions.push(t);
         self.sort_by_date();
     }
+
+    /// Remove the transaction with the given uuid from the ledger.
+    pub(crate) fn remove(&mut self, uuid: &Uuid) -> Option<Transaction<C>> {
+        let index = self.transactions.iter().position(|t| t.uuid() == uuid)?;
+        Some(self.transactions.remove(index))
+    }
 }
 
 impl<C> IntoIterator for Ledger<C>
@@ -1,17 +1,55 @@
+//! A library for envelope budgeting.
+//!
+//! The main entry point is [`Budget`], which holds a [`Ledger`] of
+//! [`Transaction`]s and tracks the money allocated to each spending category.
+
 #![warn(clippy::all)]
 #![warn(missing_docs)]
 
 mod transaction;
-pub use crate::transaction::Transaction;
+pub use crate::transaction::{midday, CategoryRef, Transaction};
 
 mod ledger;
 pub use crate::ledger::Ledger;
 
 mod currency;
-pub use crate::currency::Currency;
+pub use crate::currency::{Currency, ParseAmountError};
+
+mod money;
+pub use crate::money::{CurrencyCode, Locale, Money, ParseCurrencyCodeError};
 
 mod month;
-use crate::month::CalendarMonth;
+pub use crate::month::{CalendarMonth, Month, ParseMonthError};
+
+mod account;
+pub use crate::account::{Account, Valuation};
+
+mod exchange;
+pub use crate::exchange::{ExchangeRate, ExchangeRates};
+
+mod goal;
+pub use crate::goal::{Goal, GoalKind, GoalStatus};
+
+mod schedule;
+pub use crate::schedule::{InvalidRecurrenceError, Recurrence, ScheduledTransaction};
+
+mod rule;
+pub use crate::rule::{Action, InvalidSplitError, ParsePatternError, Pattern, Rule, Share};
+
+mod payee;
+pub use crate::payee::{Payee, PayeeError, PayeeStats};
 
 mod budget;
-pub use crate::budget::Budget;
+pub use crate::budget::{
+    Budget, FxPosition, MoveCategoryError, RemoveCategoryError, RenameCategoryError,
+    RenameTagError, RuleChange, TagStats, TransferError, UnknownCategoryError,
+};
+
+mod report;
+pub use crate::report::{
+    AccountFlow, CashFlowReport, Grouping, MonthFlow, NetWorthReport, NetWorthRow,
+    ParseGroupingError, SpendingReport, SpendingRow,
+};
+
+mod forecast;
+pub use crate::forecast::{AccountForecast, CategoryForecast, Forecast};
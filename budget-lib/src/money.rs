@@ -0,0 +1,599 @@
+//! # Money.
+//!
+//! An amount of money in a specific currency, along with the formatting
+//! conventions used to display it.
+
+use crate::currency::ParseAmountError;
+use crate::Currency;
+use decimal::d128;
+use serde_derive::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::str::FromStr;
+use std::{cmp, fmt, ops};
+
+/// ISO 4217 code, number of minor units, and symbol of common currencies.
+///
+/// Currencies which aren't listed here can still be used. They are assumed to
+/// have two minor units, and are displayed with their code.
+const CURRENCIES: &[(&str, u32, &str)] = &[
+    ("AUD", 2, "A$"),
+    ("BHD", 3, "BHD"),
+    ("BRL", 2, "R$"),
+    ("CAD", 2, "CA$"),
+    ("CHF", 2, "CHF"),
+    ("CLP", 0, "CLP"),
+    ("CNY", 2, "CN¥"),
+    ("CZK", 2, "Kč"),
+    ("DKK", 2, "kr."),
+    ("EUR", 2, "€"),
+    ("GBP", 2, "£"),
+    ("HKD", 2, "HK$"),
+    ("HUF", 2, "Ft"),
+    ("IDR", 2, "Rp"),
+    ("ILS", 2, "₪"),
+    ("INR", 2, "₹"),
+    ("ISK", 0, "kr"),
+    ("JOD", 3, "JOD"),
+    ("JPY", 0, "¥"),
+    ("KRW", 0, "₩"),
+    ("KWD", 3, "KWD"),
+    ("MXN", 2, "MX$"),
+    ("NOK", 2, "kr"),
+    ("NZD", 2, "NZ$"),
+    ("OMR", 3, "OMR"),
+    ("PLN", 2, "zł"),
+    ("RUB", 2, "₽"),
+    ("SEK", 2, "kr"),
+    ("SGD", 2, "S$"),
+    ("THB", 2, "฿"),
+    ("TRY", 2, "₺"),
+    ("TWD", 2, "NT$"),
+    ("USD", 2, "$"),
+    ("VND", 0, "₫"),
+    ("XXX", 2, ""),
+    ("ZAR", 2, "R"),
+];
+
+/// An ISO 4217 currency code, such as "USD".
+///
+/// # Example
+/// ```
+/// use budget_lib::CurrencyCode;
+///
+/// let gbp: CurrencyCode = "gbp".parse().unwrap();
+///
+/// assert_eq!(gbp.as_str(), "GBP");
+/// assert_eq!(gbp.symbol(), "£");
+/// assert_eq!(gbp.minor_units(), 2);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CurrencyCode([u8; 3]);
+
+impl CurrencyCode {
+    /// The code reserved by ISO 4217 for transactions where no currency is involved.
+    ///
+    /// This is displayed without a symbol, so it is useful when the currency of a
+    /// budget is unknown.
+    pub const NONE: CurrencyCode = CurrencyCode(*b"XXX");
+
+    /// Returns the three letter code.
+    pub fn as_str(&self) -> &str {
+        // only ever constructed from ascii letters
+        std::str::from_utf8(&self.0).unwrap()
+    }
+
+    /// Returns the number of digits after the decimal point used by the currency.
+    pub fn minor_units(&self) -> u32 {
+        self.info().map_or(2, |x| x.1)
+    }
+
+    /// Returns the symbol of the currency, such as "$". If the symbol isn't
+    /// known, this is the currency code.
+    pub fn symbol(&self) -> &str {
+        match self.info() {
+            Some(x) => x.2,
+            None => self.as_str(),
+        }
+    }
+
+    fn info(&self) -> Option<&'static (&'static str, u32, &'static str)> {
+        CURRENCIES.iter().find(|x| x.0 == self.as_str())
+    }
+}
+
+impl Default for CurrencyCode {
+    fn default() -> Self {
+        CurrencyCode::NONE
+    }
+}
+
+impl FromStr for CurrencyCode {
+    type Err = ParseCurrencyCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.len() == 3 && s.chars().all(|c| c.is_ascii_alphabetic()) {
+            let mut code = [0u8; 3];
+            code.copy_from_slice(s.to_ascii_uppercase().as_bytes());
+            Ok(CurrencyCode(code))
+        } else {
+            Err(ParseCurrencyCodeError(s.to_string()))
+        }
+    }
+}
+
+impl TryFrom<String> for CurrencyCode {
+    type Error = ParseCurrencyCodeError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<CurrencyCode> for String {
+    fn from(c: CurrencyCode) -> String {
+        c.as_str().to_string()
+    }
+}
+
+impl fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CurrencyCode({})", self.as_str())
+    }
+}
+
+/// The error returned when a string isn't a valid ISO 4217 currency code.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseCurrencyCodeError(String);
+
+impl fmt::Display for ParseCurrencyCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a currency code. expected three letters, such as 'USD'",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseCurrencyCodeError {}
+
+/// The conventions for displaying amounts of money in a particular locale.
+///
+/// # Example
+/// ```
+/// use budget_lib::{CurrencyCode, Locale, Money};
+///
+/// let eur: CurrencyCode = "EUR".parse().unwrap();
+/// let m = Money::new(-1234567, eur);
+///
+/// assert_eq!(m.format(&Locale::new("en-US")), "-€1,234,567.00");
+/// assert_eq!(m.format(&Locale::new("de_DE.UTF-8")), "-1.234.567,00\u{a0}€");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locale {
+    decimal_separator: char,
+    group_separator: char,
+    symbol_first: bool,
+    symbol_space: bool,
+}
+
+impl Locale {
+    /// Returns the conventions for a locale tag, such as "en-GB", "de_DE.UTF-8" or "fr".
+    ///
+    /// The region is used if it's known, otherwise the language. Unknown locales
+    /// use the conventions of "en-US".
+    pub fn new(tag: &str) -> Locale {
+        let tag = tag
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_lowercase();
+        let language = tag.split('-').next().unwrap_or_default();
+
+        Locale::for_tag(&tag)
+            .or_else(|| Locale::for_tag(language))
+            .unwrap_or_default()
+    }
+
+    fn for_tag(tag: &str) -> Option<Locale> {
+        const NBSP: char = '\u{a0}';
+        let locale = |decimal_separator, group_separator, symbol_first, symbol_space| Locale {
+            decimal_separator,
+            group_separator,
+            symbol_first,
+            symbol_space,
+        };
+
+        let l = match tag {
+            "en" | "ja" | "zh" | "ko" | "th" | "he" => locale('.', ',', true, false),
+            "de-ch" | "it-ch" | "fr-ch" => locale('.', '’', true, true),
+            "de-at" | "nl" | "pt-br" => locale(',', '.', true, true),
+            "de" | "es" | "it" | "pt" | "id" | "tr" | "da" => locale(',', '.', false, true),
+            "fr" | "sv" | "nb" | "no" | "fi" | "pl" | "cs" | "ru" | "hu" | "uk" => {
+                locale(',', NBSP, false, true)
+            }
+            _ => return None,
+        };
+        Some(l)
+    }
+
+    /// Format a plain decimal string such as "-1234.50", as produced by
+    /// [`Currency::format`], with the given currency symbol.
+    fn format_decimal(&self, decimal: &str, symbol: &str) -> String {
+        let (negative, digits) = match decimal.strip_prefix('-') {
+            Some(d) => (true, d),
+            None => (false, decimal),
+        };
+        let (integer, fraction) = match digits.find('.') {
+            Some(i) => (&digits[..i], Some(&digits[i + 1..])),
+            None => (digits, None),
+        };
+
+        let mut number = String::new();
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                number.push(self.group_separator);
+            }
+            number.push(c);
+        }
+        if let Some(f) = fraction {
+            number.push(self.decimal_separator);
+            number.push_str(f);
+        }
+
+        // don't display negative zero, such as a small negative amount rounded to "-0.00"
+        let sign = if negative && digits.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            "-"
+        } else {
+            ""
+        };
+        let space = if self.symbol_space && !symbol.is_empty() {
+            "\u{a0}"
+        } else {
+            ""
+        };
+
+        if self.symbol_first {
+            format!("{}{}{}{}", sign, symbol, space, number)
+        } else {
+            format!("{}{}{}{}", sign, number, space, symbol)
+        }
+    }
+
+    /// Convert an amount formatted for this locale into a plain decimal string.
+    ///
+    /// Returns None if a group separator isn't between groups of three digits
+    /// before the decimal separator, such as "12.50" in a locale which groups
+    /// with '.', since the separator was probably meant to be the decimal one.
+    fn normalise(&self, s: &str) -> Option<String> {
+        let chars: Vec<char> = s.chars().collect();
+        let digit = |i: Option<usize>| {
+            i.and_then(|i| chars.get(i))
+                .is_some_and(char::is_ascii_digit)
+        };
+        // a locale which groups with a space accepts any space between digits, and
+        // a space anywhere else, such as before the symbol, is just a space
+        let is_group = |i: usize| {
+            if self.group_separator.is_whitespace() {
+                chars[i].is_whitespace() && digit(i.checked_sub(1)) && digit(Some(i + 1))
+            } else {
+                chars[i] == self.group_separator
+            }
+        };
+
+        let mut normalised = String::new();
+        // the digits since the last group separator, or since anything else
+        let mut digits = 0;
+        let mut grouped = false;
+        let mut fraction = false;
+        for (i, &c) in chars.iter().enumerate() {
+            if c.is_ascii_digit() {
+                digits += 1;
+                normalised.push(c);
+                continue;
+            }
+            if is_group(i) {
+                if fraction || digits == 0 || digits > 3 || (grouped && digits != 3) {
+                    return None;
+                }
+                grouped = true;
+                digits = 0;
+                continue;
+            }
+            // the end of a number which was grouped must be a whole group
+            if grouped && digits != 3 {
+                return None;
+            }
+            grouped = false;
+            digits = 0;
+            if c == self.decimal_separator {
+                fraction = true;
+                normalised.push('.');
+            } else if !c.is_whitespace() {
+                normalised.push(c);
+            }
+        }
+        if grouped && digits != 3 {
+            return None;
+        }
+        Some(normalised)
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale {
+            decimal_separator: '.',
+            group_separator: ',',
+            symbol_first: true,
+            symbol_space: false,
+        }
+    }
+}
+
+/// An amount of money in a specific currency.
+///
+/// # Example
+/// ```
+/// use budget_lib::{CurrencyCode, Money};
+///
+/// let usd: CurrencyCode = "USD".parse().unwrap();
+/// let m: Money = Money::parse("$1,234.5", usd, &Default::default()).unwrap();
+///
+/// assert_eq!(m.to_string(), "$1,234.50");
+/// assert_eq!((-m).to_string(), "-$1,234.50");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Money<C = d128>
+where
+    C: Currency,
+{
+    amount: C,
+    currency: CurrencyCode,
+}
+
+impl Money<d128> {
+    /// Create a new amount of money, represented as a d128.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::{CurrencyCode, Money};
+    ///
+    /// let m = Money::new(100, "USD".parse().unwrap());
+    ///
+    /// assert_eq!(m.to_string(), "$100.00");
+    /// ```
+    pub fn new<T: Into<d128>>(amount: T, currency: CurrencyCode) -> Self {
+        Money::from_amount(amount.into(), currency)
+    }
+}
+
+impl<C> Money<C>
+where
+    C: Currency,
+{
+    /// Create a new amount of money, with a custom type to represent the amount.
+    pub fn from_amount(amount: C, currency: CurrencyCode) -> Self {
+        Money { amount, currency }
+    }
+
+    /// Returns zero in the given currency.
+    pub fn zero(currency: CurrencyCode) -> Self {
+        Money::from_amount(C::zero(), currency)
+    }
+
+    /// Returns the amount, without the currency.
+    pub fn amount(&self) -> C {
+        self.amount
+    }
+
+    /// Returns the currency.
+    pub fn currency(&self) -> CurrencyCode {
+        self.currency
+    }
+
+    /// Returns true if the amount is less than zero.
+    pub fn is_negative(&self) -> bool {
+        Currency::is_negative(&self.amount)
+    }
+
+    /// Round the amount to the number of minor units of the currency, such as
+    /// whole cents.
+    pub fn round(&self) -> Self {
+        let decimal = self.amount.format(self.currency.minor_units());
+        Money::from_amount(C::parse(&decimal).unwrap_or(self.amount), self.currency)
+    }
+
+    /// Add another amount of money, if it's in the same currency.
+    pub fn checked_add(self, other: Money<C>) -> Option<Self> {
+        if self.currency == other.currency {
+            let mut amount = self.amount;
+            amount += other.amount;
+            Some(Money::from_amount(amount, self.currency))
+        } else {
+            None
+        }
+    }
+
+    /// Format the amount, rounded to the minor units of the currency, using the
+    /// conventions of the given locale.
+    pub fn format(&self, locale: &Locale) -> String {
+        let decimal = self.amount.format(self.currency.minor_units());
+        locale.format_decimal(&decimal, self.currency.symbol())
+    }
+
+    /// Parse an amount of money formatted for the given locale.
+    ///
+    /// The amount may include the symbol or code of the currency, and
+    /// thousands separators.
+    pub fn parse(
+        s: &str,
+        currency: CurrencyCode,
+        locale: &Locale,
+    ) -> Result<Self, ParseAmountError> {
+        // the symbol is removed first, since it can contain a separator, as "kr." does
+        let mut number = s.to_string();
+        for marker in &[currency.as_str(), currency.symbol()] {
+            if !marker.is_empty() {
+                number = number.replacen(marker, "", 1);
+            }
+        }
+        let normalised = locale
+            .normalise(&number)
+            .ok_or_else(|| ParseAmountError::new(s))?;
+
+        C::parse(&normalised)
+            .map(|amount| Money::from_amount(amount, currency))
+            .map_err(|_| ParseAmountError::new(s))
+    }
+}
+
+impl<C> ops::Neg for Money<C>
+where
+    C: Currency,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Money::from_amount(-self.amount, self.currency)
+    }
+}
+
+/// Amounts of money can only be compared if they are in the same currency.
+impl<C> cmp::PartialOrd for Money<C>
+where
+    C: Currency,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if self.currency == other.currency {
+            self.amount.partial_cmp(&other.amount)
+        } else {
+            None
+        }
+    }
+}
+
+/// Formats the amount using the conventions of the default locale.
+impl<C> fmt::Display for Money<C>
+where
+    C: Currency,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.format(&Locale::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn money(amount: &str, currency: &str) -> Money {
+        Money::new(
+            <d128 as Currency>::parse(amount).unwrap(),
+            currency.parse().unwrap(),
+        )
+    }
+
+    #[test]
+    fn format_locales() {
+        let m = money("1234567.891", "EUR");
+        assert_eq!(m.format(&Locale::new("en_GB")), "€1,234,567.89");
+        assert_eq!(
+            m.format(&Locale::new("fr-FR")),
+            "1\u{a0}234\u{a0}567,89\u{a0}€"
+        );
+        assert_eq!(m.format(&Locale::new("nl-NL")), "€\u{a0}1.234.567,89");
+        assert_eq!(
+            money("-1234.5", "CHF").format(&Locale::new("de-CH")),
+            "-CHF\u{a0}1’234.50"
+        );
+    }
+
+    #[test]
+    fn format_minor_units() {
+        assert_eq!(money("1234.6", "JPY").to_string(), "¥1,235");
+        assert_eq!(money("1.2345", "KWD").to_string(), "KWD1.234");
+        assert_eq!(money("12", "XXX").to_string(), "12.00");
+        assert_eq!(money("-0.001", "USD").to_string(), "$0.00");
+    }
+
+    #[test]
+    fn parse() {
+        let usd = "USD".parse().unwrap();
+        let de = Locale::new("de");
+        assert_eq!(
+            Money::parse("1.234,50 USD", usd, &de),
+            Ok(money("1234.50", "USD"))
+        );
+        assert_eq!(
+            Money::parse("-$12", usd, &Locale::default()),
+            Ok(money("-12", "USD"))
+        );
+        assert!(Money::<d128>::parse("£12", usd, &Locale::default()).is_err());
+    }
+
+    #[test]
+    fn parse_formatted() {
+        let locales = ["en", "de", "de-ch", "nl", "fr", "da"].map(Locale::new);
+        for (code, _, _) in CURRENCIES {
+            let m = money("-1234567.891", code).round();
+            for locale in &locales {
+                let formatted = m.format(locale);
+                assert_eq!(
+                    Money::parse(&formatted, m.currency(), locale),
+                    Ok(m),
+                    "{}",
+                    formatted
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn parse_group_separators() {
+        let eur = "EUR".parse().unwrap();
+        let parse = |s, locale| Money::<d128>::parse(s, eur, &Locale::new(locale));
+
+        assert_eq!(parse("12,50", "de_DE"), Ok(money("12.50", "EUR")));
+        assert_eq!(
+            parse("1.234.567,5 €", "de_DE"),
+            Ok(money("1234567.5", "EUR"))
+        );
+        // the group separator can't be mistaken for a decimal separator
+        assert!(parse("12.50", "de_DE").is_err());
+        assert!(parse("1.2345", "de_DE").is_err());
+        assert!(parse("1,234.567", "de_DE").is_err());
+
+        assert_eq!(parse("€1,234.5", "en"), Ok(money("1234.5", "EUR")));
+        assert!(parse("1,2,3", "en").is_err());
+        assert!(parse("1234,567", "en").is_err());
+        assert!(parse(",123", "en").is_err());
+        assert!(parse("1.234,5", "en").is_err());
+
+        assert_eq!(parse("1 234,50 €", "fr"), Ok(money("1234.50", "EUR")));
+        assert!(parse("12 50", "fr").is_err());
+    }
+
+    #[test]
+    fn compare() {
+        assert!(money("1", "USD") < money("2", "USD"));
+        assert_eq!(money("1", "USD").partial_cmp(&money("2", "GBP")), None);
+        assert_eq!(money("1.005", "USD").round(), money("1.00", "USD"));
+    }
+
+    #[test]
+    fn currency_code() {
+        assert!("US".parse::<CurrencyCode>().is_err());
+        assert_eq!("abc".parse::<CurrencyCode>().unwrap().symbol(), "ABC");
+    }
+}
@@ -1,28 +1,173 @@
-use chrono::Datelike;
+use chrono::{Datelike, NaiveDate};
 use serde_derive::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize, Debug, Clone)]
-pub(crate) struct CalendarMonth {
+/// A month of a particular year, such as March 2026.
+///
+/// Calendar months are ordered by date, and are written as 'YYYY-MM'.
+///
+/// # Example
+/// ```
+/// use budget_lib::{CalendarMonth, Month};
+///
+/// let m: CalendarMonth = "2026-12".parse().unwrap();
+///
+/// assert_eq!(m.succ(), CalendarMonth::new(2027, Month::Jan));
+/// assert_eq!(m.to_string(), "2026-12");
+/// ```
+#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct CalendarMonth {
     year: i32,
     month: Month,
 }
 
 impl CalendarMonth {
+    /// Create a new calendar month.
     pub fn new(year: i32, month: Month) -> CalendarMonth {
         CalendarMonth { year, month }
     }
 
+    /// Create a new calendar month, or return `None` if its dates, or those of the
+    /// months either side of it, are out of the range of [`NaiveDate`].
+    pub fn new_opt(year: i32, month: Month) -> Option<CalendarMonth> {
+        let in_range = |year: i32| NaiveDate::from_ymd_opt(year, 1, 1).is_some();
+        if in_range(year.checked_sub(1)?) && in_range(year.checked_add(1)?) {
+            Some(CalendarMonth::new(year, month))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month of the year.
+    pub fn month(&self) -> Month {
+        self.month
+    }
+
+    /// Advance to the following month.
     pub fn increment(&mut self) {
-        match self.month {
-            Month::Dec => {
-                self.year += 1;
-                self.month.increment();
+        if self.month == Month::Dec {
+            self.year += 1;
+        }
+        self.month.increment();
+    }
+
+    /// Go back to the previous month.
+    pub fn decrement(&mut self) {
+        if self.month == Month::Jan {
+            self.year -= 1;
+        }
+        self.month.decrement();
+    }
+
+    /// Returns the following month.
+    pub fn succ(mut self) -> CalendarMonth {
+        self.increment();
+        self
+    }
+
+    /// Returns the previous month.
+    pub fn pred(mut self) -> CalendarMonth {
+        self.decrement();
+        self
+    }
+
+    /// Returns the first day of the month.
+    pub fn first_day(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month as u32, 1).unwrap()
+    }
+
+    /// Returns the last day of the month.
+    pub fn last_day(&self) -> NaiveDate {
+        self.succ().first_day().pred_opt().unwrap()
+    }
+
+    /// Returns the budget month containing `date`, for budget months which start
+    /// on the day `first` of each calendar month, from 1 to 28. A budget month is
+    /// named after the calendar month that it starts in.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::{CalendarMonth, Month};
+    ///
+    /// let date = chrono::NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+    ///
+    /// assert_eq!(CalendarMonth::containing(date, 1), CalendarMonth::new(2026, Month::Mar));
+    /// assert_eq!(CalendarMonth::containing(date, 25), CalendarMonth::new(2026, Month::Feb));
+    /// ```
+    pub fn containing<T: Datelike>(date: T, first: u32) -> CalendarMonth {
+        let month = CalendarMonth::new(date.year(), date.month().into());
+        if date.day() < first.clamp(1, 28) {
+            month.pred()
+        } else {
+            month
+        }
+    }
+
+    /// Returns the first day of the budget month, for budget months which start on
+    /// the day `first` of each calendar month. See [`CalendarMonth::containing`].
+    pub fn start(&self, first: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month as u32, first.clamp(1, 28)).unwrap()
+    }
+
+    /// Returns the last day of the budget month, for budget months which start on
+    /// the day `first` of each calendar month. See [`CalendarMonth::containing`].
+    pub fn end(&self, first: u32) -> NaiveDate {
+        self.succ().start(first).pred_opt().unwrap()
+    }
+
+    /// Returns an iterator over the months from `from` to `to`, inclusive.
+    ///
+    /// The iterator is empty if `to` is before `from`.
+    pub fn range(from: CalendarMonth, to: CalendarMonth) -> impl Iterator<Item = CalendarMonth> {
+        std::iter::successors(Some(from), |m| Some(m.succ())).take_while(move |m| *m <= to)
+    }
+}
+
+impl fmt::Display for CalendarMonth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month as u32)
+    }
+}
+
+impl FromStr for CalendarMonth {
+    type Err = ParseMonthError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseMonthError {
+            input: s.to_string(),
+        };
+        let (year, month) = s.trim().split_once('-').ok_or_else(err)?;
+        let year = year.parse().map_err(|_| err())?;
+        match month.parse::<u32>() {
+            Ok(m) if (1..=12).contains(&m) => {
+                CalendarMonth::new_opt(year, m.into()).ok_or_else(err)
             }
-            _ => self.month.increment(),
+            _ => Err(err()),
         }
     }
 }
 
+/// The error returned when a string can't be parsed as a [`CalendarMonth`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseMonthError {
+    input: String,
+}
+
+impl fmt::Display for ParseMonthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a month, such as '2026-03'", self.input)
+    }
+}
+
+impl Error for ParseMonthError {}
+
 impl<T: Datelike> From<T> for CalendarMonth {
     fn from(date: T) -> Self {
         CalendarMonth {
@@ -32,7 +177,9 @@ impl<T: Datelike> From<T> for CalendarMonth {
     }
 }
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize, Debug, Clone)]
+/// A month of the year.
+#[allow(missing_docs)]
+#[derive(PartialEq, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize, Debug, Clone, Copy)]
 pub enum Month {
     Jan = 1,
     Feb,
@@ -49,8 +196,14 @@ pub enum Month {
 }
 
 impl Month {
+    /// Advance to the following month, wrapping around from December to January.
     pub fn increment(&mut self) {
-        *self = ((self.clone() as u32 + 1) % 12).into()
+        *self = (*self as u32 % 12 + 1).into()
+    }
+
+    /// Go back to the previous month, wrapping around from January to December.
+    pub fn decrement(&mut self) {
+        *self = ((*self as u32 + 10) % 12 + 1).into()
     }
 }
 
@@ -105,8 +258,42 @@ mod tests {
         let mut m = Month::Jan;
         m.increment();
         assert_eq!(m, Month::Feb);
-        m = Month::Dec;
+        m = Month::Nov;
+        m.increment();
+        assert_eq!(m, Month::Dec);
         m.increment();
         assert_eq!(m, Month::Jan);
+        m.decrement();
+        assert_eq!(m, Month::Dec);
+    }
+
+    #[test]
+    fn range() {
+        let from: CalendarMonth = "2025-11".parse().unwrap();
+        let to: CalendarMonth = "2026-02".parse().unwrap();
+        let months: Vec<_> = CalendarMonth::range(from, to)
+            .map(|m| m.to_string())
+            .collect();
+        assert_eq!(months, vec!["2025-11", "2025-12", "2026-01", "2026-02"]);
+        assert_eq!(CalendarMonth::range(to, from).count(), 0);
+        assert_eq!(to.last_day(), NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+        assert!("2026-13".parse::<CalendarMonth>().is_err());
+        assert!("99999999-01".parse::<CalendarMonth>().is_err());
+        assert!("-99999999-01".parse::<CalendarMonth>().is_err());
+    }
+
+    #[test]
+    fn budget_months() {
+        let date = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let feb: CalendarMonth = "2026-02".parse().unwrap();
+        assert_eq!(CalendarMonth::containing(date("2026-03-24"), 25), feb);
+        assert_eq!(
+            CalendarMonth::containing(date("2026-03-25"), 25),
+            feb.succ()
+        );
+        assert_eq!(CalendarMonth::containing(date("2026-03-24"), 1), feb.succ());
+        assert_eq!(feb.start(25), date("2026-02-25"));
+        assert_eq!(feb.end(25), date("2026-03-24"));
+        assert_eq!(feb.end(1), feb.last_day());
     }
 }
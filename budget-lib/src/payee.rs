@@ -0,0 +1,300 @@
+//! # Payees.
+
+use crate::{Currency, Pattern};
+use chrono::NaiveDate;
+use decimal::d128;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+/// A payee, with the other names it appears under and the category its
+/// transactions usually belong to.
+///
+/// # Example
+/// ```
+/// use budget_lib::Payee;
+///
+/// let amazon = Payee::new("Amazon").with_alias("amzn|amazon\\.com".parse().unwrap());
+///
+/// assert!(amazon.matches("AMZN Mktp UK"));
+/// assert!(amazon.matches("amazon"));
+/// assert!(!amazon.matches("Tesco"));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payee {
+    name: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<Pattern>,
+
+    /// The category of the payee's transactions when none is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+}
+
+impl Payee {
+    /// Create a new payee with the given canonical name.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Payee {
+            name: name.into(),
+            aliases: Vec::new(),
+            category: None,
+        }
+    }
+
+    /// Inline method for adding an alias to the payee.
+    pub fn with_alias(mut self, alias: Pattern) -> Self {
+        self.aliases.push(alias);
+        self
+    }
+
+    /// Inline method for setting the default category of the payee.
+    pub fn with_category<S: Into<String>>(mut self, category: S) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Returns the canonical name of the payee.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the patterns of the other names that the payee appears under.
+    pub fn aliases(&self) -> &[Pattern] {
+        &self.aliases
+    }
+
+    /// Returns the category of the payee's transactions when none is given.
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    /// Returns true if the name is the canonical name of the payee, ignoring case,
+    /// or matches one of its aliases.
+    pub fn matches(&self, name: &str) -> bool {
+        same_name(&self.name, name) || self.aliases.iter().any(|a| a.is_match(name))
+    }
+
+    pub(crate) fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub(crate) fn add_alias(&mut self, alias: Pattern) {
+        self.aliases.push(alias);
+    }
+
+    pub(crate) fn set_category(&mut self, category: Option<String>) {
+        self.category = category;
+    }
+}
+
+/// Returns a payee name folded to one case, so that names which differ only in
+/// case, such as "Café" and "CAFÉ", are the same payee.
+pub(crate) fn fold_case(name: &str) -> String {
+    name.to_lowercase()
+}
+
+/// Returns true if two payee names are the same, ignoring case.
+pub(crate) fn same_name(a: &str, b: &str) -> bool {
+    a == b || fold_case(a) == fold_case(b)
+}
+
+/// An error which can be returned when renaming or merging payees.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayeeError {
+    /// There is no payee with the name, registered or on a transaction
+    NoPayee(String),
+    /// There is already a payee registered with the new name
+    Exists(String),
+}
+
+impl fmt::Display for PayeeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PayeeError::NoPayee(name) => write!(f, "no payee named '{}'", name),
+            PayeeError::Exists(name) => write!(f, "there is already a payee named '{}'", name),
+        }
+    }
+}
+
+impl std::error::Error for PayeeError {}
+
+/// The transactions with a payee.
+#[derive(Debug, Clone)]
+pub struct PayeeStats<C = d128>
+where
+    C: Currency,
+{
+    /// The name of the payee
+    pub payee: String,
+    /// The number of transactions
+    pub count: usize,
+    /// The total of the transactions, in the currency of the budget
+    pub total: C,
+    /// The number of transactions left out of the total, because there is no
+    /// exchange rate for their currency
+    pub unconverted: usize,
+    /// The date of the latest transaction
+    pub last: Option<NaiveDate>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, Budget, Transaction};
+
+    fn payee(name: &str) -> Transaction<f64> {
+        Transaction::with_currency(-10.0).with_payee(Some(name))
+    }
+
+    #[test]
+    fn matching_aliases() {
+        let tesco = Payee::new("Tesco")
+            .with_alias("^tesco stores".parse().unwrap())
+            .with_alias("tesco express".parse().unwrap());
+
+        assert!(tesco.matches("TESCO"));
+        assert!(tesco.matches("Tesco Stores 2041"));
+        assert!(tesco.matches("tesco express"));
+        assert!(!tesco.matches("Tesco Mobile"));
+        assert!(!tesco.matches("Sainsbury's"));
+    }
+
+    #[test]
+    fn default_category() {
+        let mut budget = Budget::<f64>::with_categories(vec!["groceries", "fuel"]);
+        budget.set_payee_category("Tesco", Some("groceries".to_string()));
+        budget.import(payee("tesco")).unwrap();
+        budget
+            .import(payee("Tesco").with_category("fuel").with_amount(-20.0))
+            .unwrap();
+        // transfers have no category
+        let transfer = payee("Tesco").with_transfer(uuid::Uuid::new_v4());
+        budget.import(transfer.with_amount(-30.0)).unwrap();
+
+        let mut categories: Vec<_> = budget
+            .ledger()
+            .into_iter()
+            .map(|t| (-*t.amount() as i32, budget.category_name(t)))
+            .collect();
+        categories.sort();
+        assert_eq!(
+            categories,
+            vec![
+                (10, Some("groceries".to_string())),
+                (20, Some("fuel".to_string())),
+                (30, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn renaming() {
+        let mut budget = Budget::<f64>::default();
+        budget.add(payee("tesco")).unwrap();
+        budget.add(payee("TESCO")).unwrap();
+        budget.add(payee("Aldi")).unwrap();
+
+        assert_eq!(budget.rename_payee("Tesco", "Tesco Stores"), Ok(2));
+        assert_eq!(
+            budget.rename_payee("Lidl", "Aldi"),
+            Err(PayeeError::NoPayee("Lidl".to_string()))
+        );
+        budget.set_payee_category("Aldi", None);
+        assert_eq!(
+            budget.rename_payee("Tesco Stores", "Aldi"),
+            Err(PayeeError::Exists("Aldi".to_string()))
+        );
+        let names: Vec<_> = budget.payee_stats().into_iter().map(|s| s.payee).collect();
+        assert_eq!(names, vec!["Aldi", "Tesco Stores"]);
+    }
+
+    #[test]
+    fn registered_payees_ignore_case() {
+        let mut budget = Budget::<f64>::with_categories(vec!["coffee"]);
+        budget.add(payee("Starbucks")).unwrap();
+        budget.set_payee_category("Starbucks", Some("coffee".to_string()));
+        budget.add_payee_alias("Starbucks", "sbx".parse().unwrap());
+
+        // the registered payee is renamed along with its transactions
+        assert_eq!(budget.rename_payee("starbucks", "Sbux"), Ok(1));
+        assert!(budget.payees().all(|p| p.name() != "Starbucks"));
+        let sbux = budget.payee("Sbux").unwrap();
+        assert_eq!(sbux.category(), Some("coffee"));
+        assert!(sbux.matches("SBX 1234"));
+        // a payee can be renamed to the same name in another case
+        assert_eq!(budget.rename_payee("sbux", "SBUX"), Ok(1));
+        assert_eq!(budget.payees().count(), 1);
+
+        budget.add(payee("Costa")).unwrap();
+        budget.set_payee_category("Costa", None);
+        assert_eq!(
+            budget.rename_payee("SBUX", "costa"),
+            Err(PayeeError::Exists("costa".to_string()))
+        );
+        assert_eq!(budget.merge_payee("sbux", "costa"), Ok(1));
+        let names: Vec<_> = budget.payees().map(|p| p.name().to_string()).collect();
+        assert_eq!(names, vec!["Costa"]);
+        assert_eq!(budget.payee("Costa").unwrap().category(), Some("coffee"));
+    }
+
+    #[test]
+    fn non_ascii_names_ignore_case() {
+        let mut budget = Budget::<f64>::with_categories(vec!["coffee"]);
+        budget.add(payee("Café")).unwrap();
+        budget.add(payee("CAFÉ")).unwrap();
+        budget.set_payee_category("Café", Some("coffee".to_string()));
+        assert!(budget.payee("café").unwrap().matches("CAFÉ"));
+
+        // the payee counted once in the stats is renamed as one
+        assert_eq!(budget.payee_stats().len(), 1);
+        assert_eq!(budget.rename_payee("CAFÉ", "Le Café"), Ok(2));
+        assert_eq!(budget.payee("le café").unwrap().category(), Some("coffee"));
+        budget.add(payee("Ünterhaus")).unwrap();
+        assert_eq!(budget.merge_payee("ÜNTERHAUS", "LE CAFÉ"), Ok(1));
+        let stats = budget.payee_stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!((stats[0].payee.as_str(), stats[0].count), ("Le Café", 3));
+    }
+
+    #[test]
+    fn merging() {
+        let mut budget = Budget::<f64>::with_categories(vec!["shopping"]);
+        budget.add(payee("Amazon")).unwrap();
+        budget.add(payee("Amazon.com")).unwrap();
+        budget.set_payee_category("Amazon.com", Some("shopping".to_string()));
+        budget.add_payee_alias("Amazon.com", "amzn".parse().unwrap());
+
+        assert_eq!(budget.merge_payee("Amazon.com", "Amazon"), Ok(1));
+        // the merged payee is gone, and its name, aliases and category are kept
+        assert!(budget.payees().all(|p| p.name() != "Amazon.com"));
+        let amazon = budget.payee("amazon.com").unwrap();
+        assert_eq!(amazon.name(), "Amazon");
+        assert!(amazon.matches("AMZN Mktp"));
+        assert_eq!(amazon.category(), Some("shopping"));
+
+        // a new transaction under an alias is given the payee's name
+        budget.import(payee("AMZN Mktp")).unwrap();
+        let stats = budget.payee_stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].count, 3);
+        assert_eq!(
+            budget.merge_payee("Amazon", "eBay"),
+            Err(PayeeError::NoPayee("eBay".to_string()))
+        );
+    }
+
+    #[test]
+    fn stats_leave_out_unconverted_transactions() {
+        let mut budget = Budget::<f64>::default();
+        budget.set_currency("GBP".parse().unwrap());
+        let euro = Account::new("euro").with_currency("EUR".parse().unwrap());
+        budget.add_account(euro).unwrap();
+        budget.add(payee("Tesco")).unwrap();
+        budget.add(payee("Tesco").with_account("euro")).unwrap();
+
+        let stats = budget.payee_stats();
+        assert_eq!(stats[0].count, 2);
+        assert_eq!(stats[0].total, -10.0);
+        assert_eq!(stats[0].unconverted, 1);
+    }
+}
@@ -0,0 +1,878 @@
+//! # Reports.
+//!
+//! Reports aggregate the transactions of a [`Budget`] over a range of
+//! [`CalendarMonth`]s. All amounts are in the currency of the budget.
+
+use crate::transaction::{tag_ancestors, TAG_SEPARATOR};
+use crate::{Budget, CalendarMonth, Currency, Transaction};
+use decimal::d128;
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// The ways that transactions can be grouped in a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// Group by the master category of the transaction's category
+    MasterCategory,
+    /// Group by category. Spending in a subcategory is counted in each of its
+    /// parents too, so the parents show the total of their subcategories.
+    Category,
+    /// Group by payee
+    Payee,
+    /// Group by tag. A transaction with several tags is counted once for each tag,
+    /// and a transaction with a subtag, such as "holiday/2026", is counted in its
+    /// parent tags too.
+    Tag,
+}
+
+impl Grouping {
+    /// Returns the names of the groups that a transaction belongs to.
+    fn groups<C: Currency>(self, budget: &Budget<C>, t: &Transaction<C>) -> Vec<String> {
+        let none = |what| vec![format!("(no {})", what)];
+        match self {
+            Grouping::MasterCategory => match budget.category_name(t) {
+                Some(c) => match budget.master_category_of(&c) {
+                    Some(m) => vec![m.name().to_string()],
+                    None => none("master category"),
+                },
+                None => none("category"),
+            },
+            Grouping::Category => match budget.category_paths(t) {
+                paths if paths.is_empty() => none("category"),
+                paths => paths,
+            },
+            Grouping::Payee => match t.payee() {
+                Some(p) => vec![p.clone()],
+                None => none("payee"),
+            },
+            Grouping::Tag => {
+                let mut tags: Vec<String> = t
+                    .tags()
+                    .flat_map(|t| tag_ancestors(t))
+                    .map(String::from)
+                    .collect();
+                tags.sort_unstable();
+                tags.dedup();
+                if tags.is_empty() {
+                    none("tag")
+                } else {
+                    tags
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Grouping {
+    type Err = ParseGroupingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "master" => Ok(Grouping::MasterCategory),
+            "category" => Ok(Grouping::Category),
+            "payee" => Ok(Grouping::Payee),
+            "tag" => Ok(Grouping::Tag),
+            _ => Err(ParseGroupingError),
+        }
+    }
+}
+
+/// The error returned when a string can't be parsed as a [`Grouping`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseGroupingError;
+
+impl fmt::Display for ParseGroupingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected one of master, category, payee or tag")
+    }
+}
+
+impl std::error::Error for ParseGroupingError {}
+
+/// The spending of a budget over a range of months, broken down into groups.
+///
+/// Spending is the money flowing out of the budget, so only transactions in
+/// on-budget accounts are counted, and transfers between on-budget accounts are
+/// left out. Money coming back into a category, such as a refund, reduces the
+/// spending in it. Income is left out: the categories marked as income, such as
+/// salary, and money coming in without a category. Spending is positive.
+///
+/// # Example
+/// ```
+/// use budget_lib::{Budget, Grouping, SpendingReport, Transaction};
+///
+/// let mut budget: Budget = Budget::with_categories(vec!["groceries", "transport", "salary"]);
+/// let date = "2026-01-15T12:00:00Z".parse().unwrap();
+/// budget.add(Transaction::new(-30).with_category("groceries").with_date_transaction(date)).unwrap();
+/// budget.add(Transaction::new(-10).with_category("transport").with_date_transaction(date)).unwrap();
+/// budget.add(Transaction::new(500).with_category("salary").with_date_transaction(date)).unwrap();
+/// // a refund
+/// budget.add(Transaction::new(5).with_category("groceries").with_date_transaction(date)).unwrap();
+/// budget.set_category_income("salary", true);
+///
+/// let month = "2026-01".parse().unwrap();
+/// let report = SpendingReport::new(&budget, month, month, Grouping::Category);
+///
+/// assert_eq!(report.total(), 35.into());
+/// assert_eq!(report.rows()[0].group(), "groceries");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpendingReport<C = d128>
+where
+    C: Currency,
+{
+    months: Vec<CalendarMonth>,
+    rows: Vec<SpendingRow<C>>,
+    total: C,
+    unconverted: usize,
+}
+
+/// The spending of one group in a [`SpendingReport`].
+#[derive(Debug, Clone)]
+pub struct SpendingRow<C = d128>
+where
+    C: Currency,
+{
+    group: String,
+    master_category: Option<String>,
+    depth: usize,
+    name_start: usize,
+    by_month: Vec<C>,
+    total: C,
+}
+
+impl<C> SpendingReport<C>
+where
+    C: Currency,
+{
+    /// Create a report of the spending in a budget, from the start of month `from`
+    /// to the end of month `to`.
+    pub fn new(
+        budget: &Budget<C>,
+        from: CalendarMonth,
+        to: CalendarMonth,
+        grouping: Grouping,
+    ) -> Self {
+        Self::filtered(budget, from, to, grouping, |_| true)
+    }
+
+    /// Create a report of the spending in the transactions which `filter` selects,
+    /// such as those with a tag.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::{Budget, Grouping, SpendingReport, Transaction};
+    ///
+    /// let mut budget: Budget = Budget::default();
+    /// let date = "2026-07-15T12:00:00Z".parse().unwrap();
+    /// budget.add(Transaction::new(-300).with_category("transport").with_tag("holiday/2026").with_date_transaction(date)).unwrap();
+    /// budget.add(Transaction::new(-80).with_category("restaurants").with_tag("holiday/2026").with_date_transaction(date)).unwrap();
+    /// budget.add(Transaction::new(-30).with_category("groceries").with_date_transaction(date)).unwrap();
+    ///
+    /// let month = "2026-07".parse().unwrap();
+    /// let report = SpendingReport::filtered(&budget, month, month, Grouping::Category, |t| {
+    ///     t.has_tag("holiday")
+    /// });
+    ///
+    /// assert_eq!(report.total(), 380.into());
+    /// assert_eq!(report.rows().len(), 2);
+    /// ```
+    pub fn filtered<F>(
+        budget: &Budget<C>,
+        from: CalendarMonth,
+        to: CalendarMonth,
+        grouping: Grouping,
+        filter: F,
+    ) -> Self
+    where
+        F: Fn(&Transaction<C>) -> bool,
+    {
+        let months: Vec<CalendarMonth> = CalendarMonth::range(from, to).collect();
+        let mut groups: BTreeMap<String, Vec<C>> = BTreeMap::new();
+        let mut total = C::zero();
+
+        let mut unconverted = 0;
+
+        let transfers = budget.transfers();
+        let transactions: Vec<(usize, &Transaction<C>, C)> = budget
+            .ledger()
+            .into_iter()
+            .filter(|t| filter(t))
+            .filter_map(|t| match months.binary_search(&budget.month_of(t.date())) {
+                Ok(i) if budget.is_on_budget(t) && !budget.is_internal_transfer(t, &transfers) => {
+                    match budget.to_budget_currency(t) {
+                        Some(amount) => Some((i, t, amount)),
+                        None => {
+                            unconverted += 1;
+                            None
+                        }
+                    }
+                }
+                _ => None,
+            })
+            .collect();
+        for (index, t, amount) in transactions {
+            let income = match budget.category_name(t) {
+                Some(category) => budget.is_income(&category),
+                None => !amount.is_negative(),
+            };
+            if income {
+                continue;
+            }
+
+            total -= amount;
+            for group in grouping.groups(budget, t) {
+                let by_month = groups
+                    .entry(group)
+                    .or_insert_with(|| vec![C::zero(); months.len()]);
+                by_month[index] -= amount;
+            }
+        }
+
+        let mut rows: Vec<SpendingRow<C>> = groups
+            .into_iter()
+            .map(|(group, by_month)| {
+                let mut total = C::zero();
+                for x in &by_month {
+                    total += *x;
+                }
+                let (master_category, depth) = match grouping {
+                    Grouping::Category => (
+                        budget
+                            .master_category_of(&group)
+                            .map(|m| m.name().to_string()),
+                        budget
+                            .category(&group)
+                            .map_or(0, |c| budget.category_depth(c)),
+                    ),
+                    Grouping::Tag => (None, group.matches(TAG_SEPARATOR).count()),
+                    _ => (None, 0),
+                };
+                let separator = match grouping {
+                    Grouping::Category => ':',
+                    _ => TAG_SEPARATOR,
+                };
+                let name_start = match depth {
+                    0 => 0,
+                    _ => group.rfind(separator).map_or(0, |i| i + 1),
+                };
+                SpendingRow {
+                    group,
+                    master_category,
+                    depth,
+                    name_start,
+                    by_month,
+                    total,
+                }
+            })
+            .collect();
+
+        let grouped = budget.grouped_categories();
+        let order: Vec<String> = match grouping {
+            Grouping::Category => grouped
+                .iter()
+                .flat_map(|(_, categories)| categories.iter().map(|c| budget.category_path(c)))
+                .collect(),
+            Grouping::MasterCategory => grouped
+                .iter()
+                .filter_map(|(m, _)| m.map(|m| m.name().to_string()))
+                .collect(),
+            Grouping::Payee | Grouping::Tag => Vec::new(),
+        };
+        if grouping == Grouping::Tag {
+            // in order of name, with subtags after their parents
+            rows.sort_by(|a, b| {
+                a.group
+                    .split(TAG_SEPARATOR)
+                    .cmp(b.group.split(TAG_SEPARATOR))
+            });
+        } else if order.is_empty() {
+            // largest first. the sort is stable, so equal totals stay in order of name
+            rows.sort_by(|a, b| {
+                b.total
+                    .partial_cmp(&a.total)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        } else {
+            // in the order of the budget, with the groups it doesn't know about last
+            rows.sort_by_key(|r| {
+                order
+                    .iter()
+                    .position(|g| *g == r.group)
+                    .unwrap_or(order.len())
+            });
+        }
+
+        SpendingReport {
+            months,
+            rows,
+            total,
+            unconverted,
+        }
+    }
+
+    /// Returns the months covered by the report, in order.
+    pub fn months(&self) -> &[CalendarMonth] {
+        &self.months
+    }
+
+    /// Returns the groups of the report.
+    ///
+    /// Categories and master categories are in the order of the budget (see
+    /// [`Budget::grouped_categories`]). Tags are in order of name, with subtags
+    /// after their parents. Payees are in order of decreasing spending.
+    pub fn rows(&self) -> &[SpendingRow<C>] {
+        &self.rows
+    }
+
+    /// Returns the total spending over the whole report.
+    ///
+    /// When grouping by tag this can be less than the sum of the rows, since a
+    /// transaction with several tags is included in several rows.
+    pub fn total(&self) -> C {
+        self.total
+    }
+
+    /// Returns the average spending per month.
+    pub fn average(&self) -> C {
+        average(self.total, self.months.len())
+    }
+
+    /// Returns the number of transactions which are left out of the report,
+    /// because there is no exchange rate for their currency.
+    pub fn unconverted(&self) -> usize {
+        self.unconverted
+    }
+
+    /// Returns the share of the total spending that a row makes up, as a fraction
+    /// between zero and one.
+    pub fn share(&self, row: &SpendingRow<C>) -> C {
+        if self.total == C::zero() {
+            C::zero()
+        } else {
+            row.total / self.total
+        }
+    }
+}
+
+impl<C> SpendingRow<C>
+where
+    C: Currency,
+{
+    /// Returns the name of the group.
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Returns the master category of the group, when grouping by category.
+    pub fn master_category(&self) -> Option<&str> {
+        self.master_category.as_deref()
+    }
+
+    /// Returns the name of the group without the names of its parents, such as
+    /// "fuel" for the category "transport:car:fuel".
+    pub fn name(&self) -> &str {
+        &self.group[self.name_start..]
+    }
+
+    /// Returns the number of parents of the group's category or tag, when
+    /// grouping by category or tag. When grouping by category, rows with a depth
+    /// of zero add up to the total of the report.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the spending in each month of the report, in order.
+    pub fn by_month(&self) -> &[C] {
+        &self.by_month
+    }
+
+    /// Returns the total spending of the group.
+    pub fn total(&self) -> C {
+        self.total
+    }
+
+    /// Returns the average spending of the group per month.
+    pub fn average(&self) -> C {
+        average(self.total, self.by_month.len())
+    }
+}
+
+/// The money flowing into and out of a budget, month by month and account by account.
+///
+/// Only on-budget accounts are included. Transfers between them are left out of
+/// the income and spending, since the money doesn't enter or leave the budget.
+/// They are shown separately for each account.
+///
+/// # Example
+/// ```
+/// use budget_lib::{Account, Budget, CashFlowReport, Transaction};
+///
+/// let mut budget: Budget = Budget::default();
+/// budget.add_account(Account::new("current")).unwrap();
+/// budget.add_account(Account::new("savings")).unwrap();
+/// let date = "2026-01-15T12:00:00Z".parse().unwrap();
+/// budget.add(Transaction::new(1000).with_account("current").with_date_transaction(date)).unwrap();
+/// budget.add(Transaction::new(-600).with_account("current").with_date_transaction(date)).unwrap();
+/// budget.transfer_between_accounts(300.into(), "current", "savings", date).unwrap();
+///
+/// let month = "2026-01".parse().unwrap();
+/// let report = CashFlowReport::new(&budget, month, month);
+///
+/// assert_eq!(report.months()[0].net(), 400.into());
+/// assert_eq!(report.months()[0].savings_rate(), Some("0.4".parse().unwrap()));
+/// assert_eq!(report.accounts()[1].transfers, 300.into());
+/// ```
+#[derive(Debug, Clone)]
+pub struct CashFlowReport<C = d128>
+where
+    C: Currency,
+{
+    from: CalendarMonth,
+    months: Vec<MonthFlow<C>>,
+    accounts: Vec<AccountFlow<C>>,
+    unconverted: usize,
+}
+
+/// The income and spending of one month in a [`CashFlowReport`].
+#[derive(Debug, Clone)]
+pub struct MonthFlow<C = d128>
+where
+    C: Currency,
+{
+    /// The month
+    pub month: CalendarMonth,
+    /// The money flowing into the budget
+    pub inflow: C,
+    /// The money flowing out of the budget, as a positive amount
+    pub outflow: C,
+}
+
+/// The cash flow of one account over the whole of a [`CashFlowReport`].
+#[derive(Debug, Clone)]
+pub struct AccountFlow<C = d128>
+where
+    C: Currency,
+{
+    /// The name of the account, or `None` for transactions without an account
+    pub account: Option<String>,
+    /// The money flowing into the account from outside the budget
+    pub inflow: C,
+    /// The money flowing out of the account to outside the budget, as a positive amount
+    pub outflow: C,
+    /// The money transferred into the account from other accounts, less the money
+    /// transferred out
+    pub transfers: C,
+}
+
+impl<C> CashFlowReport<C>
+where
+    C: Currency,
+{
+    /// Create a report of the cash flow of a budget, from the start of month `from`
+    /// to the end of month `to`.
+    pub fn new(budget: &Budget<C>, from: CalendarMonth, to: CalendarMonth) -> Self {
+        let mut months: Vec<MonthFlow<C>> = CalendarMonth::range(from, to)
+            .map(|month| MonthFlow {
+                month,
+                inflow: C::zero(),
+                outflow: C::zero(),
+            })
+            .collect();
+        let mut accounts: BTreeMap<Option<String>, AccountFlow<C>> = BTreeMap::new();
+        let mut unconverted = 0;
+
+        let transfers = budget.transfers();
+        for t in budget.ledger() {
+            let index = match months.binary_search_by_key(&budget.month_of(t.date()), |m| m.month) {
+                Ok(i) if budget.is_on_budget(t) => i,
+                _ => continue,
+            };
+
+            let amount = match budget.to_budget_currency(t) {
+                Some(amount) => amount,
+                None => {
+                    unconverted += 1;
+                    continue;
+                }
+            };
+            let account = accounts
+                .entry(t.account().clone())
+                .or_insert_with(|| AccountFlow {
+                    account: t.account().clone(),
+                    inflow: C::zero(),
+                    outflow: C::zero(),
+                    transfers: C::zero(),
+                });
+
+            if budget.is_internal_transfer(t, &transfers) {
+                account.transfers += amount;
+            } else if amount.is_negative() {
+                account.outflow -= amount;
+                months[index].outflow -= amount;
+            } else {
+                account.inflow += amount;
+                months[index].inflow += amount;
+            }
+        }
+
+        CashFlowReport {
+            from,
+            months,
+            accounts: accounts.into_values().collect(),
+            unconverted,
+        }
+    }
+
+    /// Returns the cash flow of each month of the report, in order.
+    pub fn months(&self) -> &[MonthFlow<C>] {
+        &self.months
+    }
+
+    /// Returns the cash flow of each account, in order of name. Transactions
+    /// without an account come first.
+    pub fn accounts(&self) -> &[AccountFlow<C>] {
+        &self.accounts
+    }
+
+    /// Returns the number of transactions which are left out of the report,
+    /// because there is no exchange rate for their currency.
+    pub fn unconverted(&self) -> usize {
+        self.unconverted
+    }
+
+    /// Returns the total cash flow over the whole report, as if it were the first month.
+    pub fn total(&self) -> MonthFlow<C> {
+        let mut total = MonthFlow {
+            month: self.from,
+            inflow: C::zero(),
+            outflow: C::zero(),
+        };
+        for m in &self.months {
+            total.inflow += m.inflow;
+            total.outflow += m.outflow;
+        }
+        total
+    }
+}
+
+impl<C> MonthFlow<C>
+where
+    C: Currency,
+{
+    /// Returns the money saved, which is the inflow less the outflow.
+    pub fn net(&self) -> C {
+        let mut net = self.inflow;
+        net -= self.outflow;
+        net
+    }
+
+    /// Returns the fraction of the inflow which was saved, or `None` if there
+    /// was no inflow.
+    pub fn savings_rate(&self) -> Option<C> {
+        if self.inflow == C::zero() {
+            None
+        } else {
+            Some(self.net() / self.inflow)
+        }
+    }
+}
+
+impl<C> AccountFlow<C>
+where
+    C: Currency,
+{
+    /// Returns the change in the balance of the account.
+    pub fn net(&self) -> C {
+        let mut net = self.inflow;
+        net -= self.outflow;
+        net += self.transfers;
+        net
+    }
+}
+
+/// The net worth of a budget at the end of each month, broken down by account.
+///
+/// Both on-budget and off-budget accounts are included. Balances are converted
+/// into the currency of the budget at the exchange rate on the last day of each
+/// month. The balance of an account with no exchange rate by then is left out
+/// of the total.
+///
+/// # Example
+/// ```
+/// use budget_lib::{Account, Budget, NetWorthReport, Transaction};
+///
+/// let mut budget: Budget = Budget::default();
+/// budget.add_account(Account::new("current")).unwrap();
+/// budget.add_account(Account::new("house").off_budget()).unwrap();
+///
+/// let date = "2026-01-15T12:00:00Z".parse().unwrap();
+/// budget.add(Transaction::new(1000).with_account("current").with_date_transaction(date)).unwrap();
+/// budget.add_valuation("house", "2026-02-01".parse().unwrap(), 250_000.into());
+///
+/// let report = NetWorthReport::new(&budget, "2026-01".parse().unwrap(), "2026-02".parse().unwrap());
+///
+/// assert_eq!(report.rows()[0].total, 1000.into());
+/// assert_eq!(report.rows()[1].total, 251_000.into());
+/// ```
+#[derive(Debug, Clone)]
+pub struct NetWorthReport<C = d128>
+where
+    C: Currency,
+{
+    accounts: Vec<Option<String>>,
+    rows: Vec<NetWorthRow<C>>,
+}
+
+/// The net worth at the end of one month of a [`NetWorthReport`].
+#[derive(Debug, Clone)]
+pub struct NetWorthRow<C = d128>
+where
+    C: Currency,
+{
+    /// The month
+    pub month: CalendarMonth,
+    /// The balance of each account, in the same order as the accounts of the
+    /// report, or `None` if there is no exchange rate for the account's currency
+    pub balances: Vec<Option<C>>,
+    /// The total of the balances
+    pub total: C,
+}
+
+impl<C> NetWorthReport<C>
+where
+    C: Currency,
+{
+    /// Create a report of the net worth of a budget at the end of each month from
+    /// `from` to `to`.
+    pub fn new(budget: &Budget<C>, from: CalendarMonth, to: CalendarMonth) -> Self {
+        let accounts = account_names(budget);
+
+        let rows = CalendarMonth::range(from, to)
+            .map(|month| {
+                let date = month.end(budget.first_day_of_month());
+                let mut total = C::zero();
+                let balances = accounts
+                    .iter()
+                    .map(|a| {
+                        let a = a.as_deref();
+                        let balance = budget.balance(a, date);
+                        // an empty account is empty in any currency
+                        let balance = match balance == C::zero() {
+                            true => Some(balance),
+                            false => budget.rates().convert(
+                                balance,
+                                budget.account_currency(a),
+                                budget.currency(),
+                                date,
+                            ),
+                        };
+                        if let Some(balance) = balance {
+                            total += balance;
+                        }
+                        balance
+                    })
+                    .collect();
+                NetWorthRow {
+                    month,
+                    balances,
+                    total,
+                }
+            })
+            .collect();
+
+        NetWorthReport { accounts, rows }
+    }
+
+    /// Returns the accounts of the report, in order of name. `None` is for the
+    /// transactions without an account, and comes first.
+    pub fn accounts(&self) -> &[Option<String>] {
+        &self.accounts
+    }
+
+    /// Returns the net worth at the end of each month of the report, in order.
+    pub fn rows(&self) -> &[NetWorthRow<C>] {
+        &self.rows
+    }
+
+    /// Returns the number of balances which are left out of the totals, because
+    /// there is no exchange rate for their account's currency.
+    pub fn unconverted(&self) -> usize {
+        self.rows
+            .iter()
+            .flat_map(|r| &r.balances)
+            .filter(|b| b.is_none())
+            .count()
+    }
+}
+
+/// Returns the names of the registered accounts, and any other accounts which
+/// transactions refer to, in order of name. `None` is for the transactions without
+/// an account, and comes first.
+pub(crate) fn account_names<C: Currency>(budget: &Budget<C>) -> Vec<Option<String>> {
+    let mut accounts: BTreeSet<Option<String>> = budget
+        .accounts()
+        .map(|a| Some(a.name().to_string()))
+        .collect();
+    for t in budget.ledger() {
+        if !accounts.contains(t.account()) {
+            accounts.insert(t.account().clone());
+        }
+    }
+    accounts.into_iter().collect()
+}
+
+fn average<C: Currency>(total: C, months: usize) -> C {
+    match u32::try_from(months) {
+        Ok(0) => C::zero(),
+        Ok(n) => total / C::from_count(n),
+        Err(_) => total / C::from_count(u32::MAX),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn spending_by_tag() {
+        let mut budget = Budget::<f64>::default();
+        let date = |m| Utc.with_ymd_and_hms(2026, m, 1, 0, 0, 0).unwrap();
+        budget
+            .add(
+                Transaction::with_currency(-10.0)
+                    .with_tag("holiday")
+                    .with_tag("food")
+                    .with_date_transaction(date(1)),
+            )
+            .unwrap();
+        budget
+            .add(Transaction::with_currency(-30.0).with_date_transaction(date(2)))
+            .unwrap();
+        budget
+            .add(Transaction::with_currency(-99.0).with_date_transaction(date(5)))
+            .unwrap();
+
+        let from = "2026-01".parse().unwrap();
+        let to = "2026-04".parse().unwrap();
+        let report = SpendingReport::new(&budget, from, to, Grouping::Tag);
+
+        assert_eq!(report.total(), 40.0);
+        assert_eq!(report.average(), 10.0);
+
+        let groups: Vec<_> = report.rows().iter().map(SpendingRow::group).collect();
+        assert_eq!(groups, vec!["(no tag)", "food", "holiday"]);
+        assert_eq!(report.rows()[0].by_month(), &[0.0, 30.0, 0.0, 0.0]);
+        assert_eq!(report.share(&report.rows()[1]), 0.25);
+    }
+
+    #[test]
+    fn refunds_reduce_spending() {
+        let mut budget = Budget::<f64>::with_categories(vec!["clothes", "salary"]);
+        let date = |m| Utc.with_ymd_and_hms(2026, m, 1, 0, 0, 0).unwrap();
+        let add = |budget: &mut Budget<f64>, amount, category: Option<&str>, month| {
+            let mut t = Transaction::with_currency(amount).with_date_transaction(date(month));
+            t.set_category(category);
+            budget.add(t)
+        };
+        add(&mut budget, -80.0, Some("clothes"), 1).unwrap();
+        add(&mut budget, 30.0, Some("clothes"), 2).unwrap();
+        add(&mut budget, 1000.0, Some("salary"), 1).unwrap();
+        add(&mut budget, -50.0, Some("salary"), 2).unwrap();
+        add(&mut budget, 200.0, None, 1).unwrap();
+        assert!(budget.set_category_income("salary", true));
+
+        let from = "2026-01".parse().unwrap();
+        let to = "2026-02".parse().unwrap();
+        let report = SpendingReport::new(&budget, from, to, Grouping::Category);
+
+        // the salary and the uncategorised money are income
+        assert_eq!(report.total(), 50.0);
+        assert_eq!(report.rows().len(), 1);
+        assert_eq!(report.rows()[0].group(), "clothes");
+        assert_eq!(report.rows()[0].by_month(), &[80.0, -30.0]);
+
+        // a month with only the refund still shows the category
+        let report = SpendingReport::new(&budget, to, to, Grouping::Category);
+        assert_eq!(report.total(), -30.0);
+        assert_eq!(report.rows()[0].group(), "clothes");
+    }
+
+    #[test]
+    fn transfer_to_tracking_account() {
+        let mut budget = Budget::<f64>::default();
+        budget.add_account(crate::Account::new("current")).unwrap();
+        budget.add_account(crate::Account::new("savings")).unwrap();
+        budget
+            .add_account(crate::Account::new("mortgage").off_budget())
+            .unwrap();
+
+        let date = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        budget
+            .transfer_between_accounts(100.0, "current", "savings", date)
+            .unwrap();
+        budget
+            .transfer_between_accounts(700.0, "current", "mortgage", date)
+            .unwrap();
+
+        let month = "2026-01".parse().unwrap();
+        let spending = SpendingReport::new(&budget, month, month, Grouping::Category);
+        assert_eq!(spending.total(), 700.0);
+
+        let cashflow = CashFlowReport::new(&budget, month, month);
+        assert_eq!(cashflow.months()[0].outflow, 700.0);
+        assert_eq!(cashflow.accounts().len(), 2);
+
+        let networth = NetWorthReport::new(&budget, month, month);
+        assert_eq!(
+            networth.rows()[0].balances,
+            vec![Some(-800.0), Some(700.0), Some(100.0)]
+        );
+    }
+
+    #[test]
+    fn unconverted_transactions_are_left_out() {
+        let gbp: crate::CurrencyCode = "GBP".parse().unwrap();
+        let eur: crate::CurrencyCode = "EUR".parse().unwrap();
+        let mut budget = Budget::<f64>::with_categories(vec!["food"]);
+        budget.set_currency(gbp);
+        budget.add_account(crate::Account::new("current")).unwrap();
+        budget
+            .add_account(crate::Account::new("euro").with_currency(eur))
+            .unwrap();
+
+        let date = Utc.with_ymd_and_hms(2026, 1, 10, 12, 0, 0).unwrap();
+        for (amount, account) in [(-30.0, "current"), (-20.0, "euro")] {
+            budget
+                .add(
+                    Transaction::with_currency(amount)
+                        .with_account(account)
+                        .with_category("food")
+                        .with_date_transaction(date),
+                )
+                .unwrap();
+        }
+
+        let month = "2026-01".parse().unwrap();
+        let spending = SpendingReport::new(&budget, month, month, Grouping::Category);
+        assert_eq!(spending.total(), 30.0);
+        assert_eq!(spending.unconverted(), 1);
+
+        let cashflow = CashFlowReport::new(&budget, month, month);
+        assert_eq!(cashflow.months()[0].outflow, 30.0);
+        assert_eq!(cashflow.unconverted(), 1);
+
+        let networth = NetWorthReport::new(&budget, month, month);
+        assert_eq!(networth.rows()[0].balances, vec![Some(-30.0), None]);
+        assert_eq!(networth.rows()[0].total, -30.0);
+        assert_eq!(networth.unconverted(), 1);
+
+        budget.add_rate(eur, gbp, month.first_day(), 0.5);
+        let spending = SpendingReport::new(&budget, month, month, Grouping::Category);
+        assert_eq!(spending.total(), 40.0);
+        assert_eq!(spending.unconverted(), 0);
+    }
+}
@@ -0,0 +1,476 @@
+//! # Rules for categorising transactions.
+
+use crate::{Currency, Transaction};
+use decimal::d128;
+use regex::{Regex, RegexBuilder};
+use serde_derive::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// A case-insensitive regular expression, for matching the text of a transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// Returns true if the pattern matches anywhere in the text.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = ParsePatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RegexBuilder::new(s)
+            .case_insensitive(true)
+            .build()
+            .map(Pattern)
+            .map_err(|e| ParsePatternError(e.to_string()))
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = ParsePatternError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(p: Pattern) -> String {
+        p.0.as_str().to_string()
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "/{}/", self.0.as_str())
+    }
+}
+
+/// An error which can be returned when parsing a [`Pattern`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsePatternError(String);
+
+impl fmt::Display for ParsePatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid pattern: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParsePatternError {}
+
+/// A category's share of a split transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Share<C = d128>
+where
+    C: Currency,
+{
+    /// The category of this part of the transaction
+    pub category: String,
+    /// The percentage of the amount of the transaction
+    pub percent: C,
+}
+
+/// A change that a [`Rule`] makes to the transactions it matches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Action<C = d128>
+where
+    C: Currency,
+{
+    /// Set the category of the transaction
+    SetCategory(String),
+    /// Set the payee of the transaction
+    SetPayee(String),
+    /// Add tags to the transaction
+    AddTags(Vec<String>),
+    /// Split the transaction into a transaction for each share. The last share
+    /// takes whatever is left after rounding, so that the parts always add up to
+    /// the original amount. Transactions which are already in one of the
+    /// categories aren't split again.
+    Split(Vec<Share<C>>),
+}
+
+impl<C> Action<C>
+where
+    C: Currency,
+{
+    /// Create an action which splits transactions between categories.
+    ///
+    /// Returns an error unless the percentages of the shares add up to 100.
+    pub fn split(shares: Vec<Share<C>>) -> Result<Self, InvalidSplitError> {
+        check_shares(&shares)?;
+        Ok(Action::Split(shares))
+    }
+}
+
+/// Returns an error unless the percentages of the shares add up to 100.
+fn check_shares<C: Currency>(shares: &[Share<C>]) -> Result<(), InvalidSplitError> {
+    let mut total = C::zero();
+    for s in shares {
+        total += s.percent;
+    }
+    if C::parse("100").is_ok_and(|hundred| total == hundred) {
+        Ok(())
+    } else {
+        let total = total.format(2);
+        let total = total.trim_end_matches('0').trim_end_matches('.');
+        Err(InvalidSplitError(total.to_string()))
+    }
+}
+
+/// The error returned when the percentages of a split don't add up to 100.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidSplitError(String);
+
+impl fmt::Display for InvalidSplitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the split percentages add up to {}, not 100", self.0)
+    }
+}
+
+impl std::error::Error for InvalidSplitError {}
+
+/// A rule which matches transactions by their payee, description, amount or
+/// account, and changes them, such as to set their category.
+///
+/// A rule with no conditions matches every transaction.
+///
+/// # Example
+/// ```
+/// use budget_lib::{Action, Rule, Transaction};
+///
+/// let rule = Rule::new()
+///     .with_payee("tesco|sainsbury".parse().unwrap())
+///     .with_action(Action::SetCategory("groceries".to_string()));
+///
+/// let t = Transaction::new(-42).with_payee(Some("TESCO STORES 1234"));
+/// let t = rule.apply(t, 2).remove(0);
+///
+/// assert_eq!(t.category_name(), Some("groceries"));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule<C = d128>
+where
+    C: Currency,
+{
+    uuid: Uuid,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payee: Option<Pattern>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<Pattern>,
+
+    /// The smallest amount to match, ignoring whether money flows in or out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min: Option<C>,
+
+    /// The largest amount to match, ignoring whether money flows in or out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max: Option<C>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account: Option<String>,
+
+    actions: Vec<Action<C>>,
+}
+
+impl<C> Default for Rule<C>
+where
+    C: Currency,
+{
+    fn default() -> Self {
+        Rule {
+            uuid: Uuid::new_v4(),
+            payee: None,
+            description: None,
+            min: None,
+            max: None,
+            account: None,
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl<C> Rule<C>
+where
+    C: Currency,
+{
+    /// Create a new rule which matches every transaction and does nothing.
+    pub fn new() -> Self {
+        Rule::default()
+    }
+
+    /// Inline method for matching transactions with a payee which matches the pattern.
+    pub fn with_payee(mut self, pattern: Pattern) -> Self {
+        self.payee = Some(pattern);
+        self
+    }
+
+    /// Inline method for matching transactions with a description which matches the pattern.
+    pub fn with_description(mut self, pattern: Pattern) -> Self {
+        self.description = Some(pattern);
+        self
+    }
+
+    /// Inline method for matching transactions with an amount in the given range.
+    ///
+    /// The range applies to the size of the amount, so a range of 10 to 100
+    /// matches both -50 and 50.
+    pub fn with_amount(mut self, min: Option<C>, max: Option<C>) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Inline method for matching transactions in the given account.
+    pub fn with_account<S: Into<String>>(mut self, account: S) -> Self {
+        self.account = Some(account.into());
+        self
+    }
+
+    /// Inline method for adding an action to the rule. Actions are carried out in
+    /// the order they are added.
+    pub fn with_action(mut self, action: Action<C>) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    /// Returns the globally unique id of the rule.
+    pub fn uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    /// Returns the pattern that the payee must match, if any.
+    pub fn payee(&self) -> Option<&Pattern> {
+        self.payee.as_ref()
+    }
+
+    /// Returns the pattern that the description must match, if any.
+    pub fn description(&self) -> Option<&Pattern> {
+        self.description.as_ref()
+    }
+
+    /// Returns the smallest and largest amounts to match, if set.
+    pub fn amount(&self) -> (Option<C>, Option<C>) {
+        (self.min, self.max)
+    }
+
+    /// Returns the account that transactions must be in, if any.
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    /// Returns the actions of the rule.
+    pub fn actions(&self) -> &[Action<C>] {
+        &self.actions
+    }
+
+    /// Returns an error if the rule splits transactions into shares which don't
+    /// add up to 100 percent.
+    pub(crate) fn check(&self) -> Result<(), InvalidSplitError> {
+        for action in &self.actions {
+            if let Action::Split(shares) = action {
+                check_shares(shares)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Call `f` on the name of each category that the actions of the rule refer to.
+    pub(crate) fn map_categories<F: FnMut(&mut String)>(&mut self, mut f: F) {
+        for action in &mut self.actions {
+            match action {
+                Action::SetCategory(category) => f(category),
+                Action::Split(shares) => shares.iter_mut().for_each(|s| f(&mut s.category)),
+                _ => (),
+            }
+        }
+    }
+
+    /// Remove the actions which refer to a category for which `f` returns true. A
+    /// split with a share in such a category is removed as a whole, since the
+    /// rest of its shares no longer add up to 100 percent.
+    pub(crate) fn remove_categories<F: FnMut(&str) -> bool>(&mut self, mut f: F) {
+        self.actions.retain(|action| match action {
+            Action::SetCategory(category) => !f(category),
+            Action::Split(shares) => !shares.iter().any(|s| f(&s.category)),
+            _ => true,
+        });
+    }
+
+    /// Replace each tag that the actions of the rule add with the result of `f`,
+    /// removing those for which it returns None.
+    pub(crate) fn map_tags<F: FnMut(&str) -> Option<String>>(&mut self, mut f: F) {
+        for action in &mut self.actions {
+            if let Action::AddTags(tags) = action {
+                *tags = tags.iter().filter_map(|t| f(t)).collect();
+            }
+        }
+        // an action which no longer adds any tags does nothing
+        self.actions
+            .retain(|a| !matches!(a, Action::AddTags(tags) if tags.is_empty()));
+    }
+
+    /// Returns true if the transaction meets all the conditions of the rule.
+    pub fn matches(&self, t: &Transaction<C>) -> bool {
+        let text = |pattern: &Option<Pattern>, text: &Option<String>| match pattern {
+            Some(p) => text.as_deref().is_some_and(|s| p.is_match(s)),
+            None => true,
+        };
+        let amount = t.amount().abs();
+
+        text(&self.payee, t.payee())
+            && text(&self.description, t.description())
+            && self.min.is_none_or(|min| amount >= min)
+            && self.max.is_none_or(|max| amount <= max)
+            && self
+                .account
+                .as_ref()
+                .is_none_or(|a| t.account().as_ref() == Some(a))
+    }
+
+    /// Carry out the actions of the rule on a transaction, if it matches.
+    ///
+    /// Returns the transaction, or the parts of the transaction if it is split.
+    /// The amounts of the parts are rounded to `decimal_places`.
+    pub fn apply(&self, t: Transaction<C>, decimal_places: u32) -> Vec<Transaction<C>> {
+        if !self.matches(&t) {
+            return vec![t];
+        }
+
+        let mut transactions = vec![t];
+        for action in &self.actions {
+            transactions = transactions
+                .into_iter()
+                .flat_map(|mut t| match action {
+                    Action::SetCategory(category) => {
+                        t.set_category(Some(category.as_str()));
+                        vec![t]
+                    }
+                    Action::SetPayee(payee) => {
+                        t.set_payee(Some(payee.as_str()));
+                        vec![t]
+                    }
+                    Action::AddTags(tags) => {
+                        for tag in tags {
+                            t.tag(tag.as_str());
+                        }
+                        vec![t]
+                    }
+                    Action::Split(shares) => split(t, shares, decimal_places),
+                })
+                .collect();
+        }
+        transactions
+    }
+}
+
+/// Split a transaction into a transaction for each share.
+fn split<C: Currency>(
+    t: Transaction<C>,
+    shares: &[Share<C>],
+    decimal_places: u32,
+) -> Vec<Transaction<C>> {
+    // a transaction already in one of the categories is a part of an earlier split
+    let already_split = t
+        .category_name()
+        .is_some_and(|c| shares.iter().any(|s| s.category == c));
+    if shares.is_empty() || already_split {
+        return vec![t];
+    }
+
+    let hundred = C::parse("100").unwrap_or_else(|_| C::zero());
+    let total = *t.amount();
+    let mut remaining = total;
+    shares
+        .iter()
+        .enumerate()
+        .map(|(i, share)| {
+            let amount = if i == shares.len() - 1 {
+                remaining
+            } else {
+                let amount = total * share.percent / hundred;
+                C::parse(&amount.format(decimal_places)).unwrap_or(amount)
+            };
+            remaining -= amount;
+
+            let mut part = t.clone().with_uuid(Uuid::new_v4());
+            part.set_amount(amount);
+            part.set_category(Some(share.category.as_str()));
+            part
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditions() {
+        let rule = Rule::<f64>::new()
+            .with_description("^card ".parse().unwrap())
+            .with_amount(Some(10.0), Some(100.0))
+            .with_account("current");
+        let t = |amount, description: &str| {
+            Transaction::with_currency(amount)
+                .with_description(Some(description))
+                .with_account("current")
+        };
+
+        assert!(rule.matches(&t(-50.0, "CARD payment")));
+        assert!(rule.matches(&t(50.0, "card refund")));
+        assert!(!rule.matches(&t(-500.0, "card payment")));
+        assert!(!rule.matches(&t(-50.0, "direct debit")));
+        assert!(!rule.matches(&t(-50.0, "card payment").with_account("savings")));
+    }
+
+    #[test]
+    fn split_by_percentage() {
+        let rule = Rule::new().with_action(Action::Split(vec![
+            Share {
+                category: "groceries".to_string(),
+                percent: 70.into(),
+            },
+            Share {
+                category: "household".to_string(),
+                percent: 30.into(),
+            },
+        ]));
+        let amount = |s: &str| s.parse::<d128>().unwrap();
+        let parts = rule.apply(Transaction::new(amount("-33.33")).with_tag("shop"), 2);
+
+        let amounts: Vec<d128> = parts.iter().map(|t| *t.amount()).collect();
+        assert_eq!(amounts, vec![amount("-23.33"), amount("-10")]);
+        assert_eq!(parts[1].category_name(), Some("household"));
+        assert!(parts.iter().all(|t| t.tags().any(|t| t == "shop")));
+        assert_ne!(parts[0].uuid(), parts[1].uuid());
+        assert_eq!(rule.apply(parts[0].clone(), 2).len(), 1);
+    }
+
+    #[test]
+    fn split_must_add_up() {
+        let share = |category: &str, percent: f64| Share {
+            category: category.to_string(),
+            percent,
+        };
+        let e = Action::split(vec![share("groceries", 70.0), share("household", 20.5)]);
+        assert_eq!(
+            e.unwrap_err().to_string(),
+            "the split percentages add up to 90.5, not 100"
+        );
+        assert!(Action::<f64>::split(Vec::new()).is_err());
+
+        let rule = Rule::new().with_action(Action::Split(vec![share("groceries", 70.0)]));
+        assert!(rule.check().is_err());
+        let split = Action::split(vec![share("groceries", 70.0), share("household", 30.0)]);
+        assert!(Rule::new().with_action(split.unwrap()).check().is_ok());
+    }
+}
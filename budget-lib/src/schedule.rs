@@ -0,0 +1,388 @@
+//! # Scheduled transactions.
+
+use crate::transaction::{midday, Source};
+use crate::{CalendarMonth, Currency, Transaction};
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc, Weekday};
+use decimal::d128;
+use serde::{de, Deserialize as _, Deserializer};
+use serde_derive::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+use uuid::Uuid;
+
+/// How often a scheduled transaction recurs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Recurrence {
+    /// Every `n` days
+    Days(u32),
+    /// Every `n` weeks
+    Weeks(u32),
+    /// Every `every` months, on the given day of the month. If the month is too
+    /// short, the last day of the month is used.
+    Months {
+        /// The number of months between occurrences
+        every: u32,
+        /// The day of the month
+        day: u32,
+    },
+    /// Every `n` months, on the last weekday of the month
+    LastBusinessDay(u32),
+}
+
+impl Recurrence {
+    /// Returns an error if the recurrence never moves on from its first
+    /// occurrence, because it is every 0 days, weeks or months, or if it is on
+    /// day 0 of the month.
+    fn validate(self) -> Result<Self, InvalidRecurrenceError> {
+        let valid = match self {
+            Recurrence::Days(every) | Recurrence::Weeks(every) => every > 0,
+            Recurrence::Months { every, day } => every > 0 && day > 0,
+            Recurrence::LastBusinessDay(every) => every > 0,
+        };
+        if valid {
+            Ok(self)
+        } else {
+            Err(InvalidRecurrenceError(self))
+        }
+    }
+
+    /// Returns the date of an occurrence, counting from zero for the first
+    /// occurrence on or after `start`, or `None` if it is past the last date
+    /// which can be represented.
+    fn occurrence(self, start: NaiveDate, n: u32) -> Option<NaiveDate> {
+        match self {
+            Recurrence::Days(every) => {
+                start.checked_add_signed(Duration::days(i64::from(every.checked_mul(n)?)))
+            }
+            Recurrence::Weeks(every) => {
+                start.checked_add_signed(Duration::weeks(i64::from(every.checked_mul(n)?)))
+            }
+            Recurrence::Months { every, day } => {
+                // the first occurrence is the next time the day comes around
+                let first = if start.day() <= day { 0 } else { 1 };
+                let month = add_months(start.into(), every.checked_mul(n)?.checked_add(first)?)?;
+                let day = day.min(month.last_day().day());
+                NaiveDate::from_ymd_opt(month.year(), month.month() as u32, day)
+            }
+            Recurrence::LastBusinessDay(every) => {
+                let last = |m: CalendarMonth| {
+                    let mut date = m.last_day();
+                    while date.weekday() == Weekday::Sat || date.weekday() == Weekday::Sun {
+                        date = date.pred_opt().unwrap();
+                    }
+                    date
+                };
+                let first = if start <= last(start.into()) { 0 } else { 1 };
+                let month = add_months(start.into(), every.checked_mul(n)?.checked_add(first)?)?;
+                Some(last(month))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let every = |f: &mut fmt::Formatter, n: u32, unit: &str| {
+            if n == 1 {
+                write!(f, "every {}", unit)
+            } else {
+                write!(f, "every {} {}s", n, unit)
+            }
+        };
+        match *self {
+            Recurrence::Days(n) => every(f, n, "day"),
+            Recurrence::Weeks(n) => every(f, n, "week"),
+            Recurrence::Months { every: n, day } => {
+                every(f, n, "month")?;
+                write!(f, " on day {}", day)
+            }
+            Recurrence::LastBusinessDay(n) => {
+                every(f, n, "month")?;
+                write!(f, " on the last business day")
+            }
+        }
+    }
+}
+
+/// The error returned when a schedule is given a [`Recurrence`] of every 0 days,
+/// weeks or months, which would never move on from its first occurrence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidRecurrenceError(Recurrence);
+
+impl fmt::Display for InvalidRecurrenceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a schedule can't recur {}", self.0)
+    }
+}
+
+impl Error for InvalidRecurrenceError {}
+
+/// Deserialize a recurrence, refusing one which would never move on.
+fn valid_recurrence<'de, D>(deserializer: D) -> Result<Recurrence, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Recurrence::deserialize(deserializer)?
+        .validate()
+        .map_err(de::Error::custom)
+}
+
+/// Returns the month `n` months after `month`, or `None` if it is past the last
+/// date which can be represented.
+fn add_months(month: CalendarMonth, n: u32) -> Option<CalendarMonth> {
+    month
+        .first_day()
+        .checked_add_months(Months::new(n))
+        .map(CalendarMonth::from)
+}
+
+/// A transaction which recurs on a schedule, such as rent or a salary.
+///
+/// The scheduled transaction holds a template, which is copied into the
+/// ledger on each date that it falls due.
+///
+/// # Example
+/// ```
+/// use budget_lib::{Recurrence, ScheduledTransaction, Transaction};
+/// use chrono::NaiveDate;
+///
+/// let start = NaiveDate::from_ymd_opt(2026, 1, 31).unwrap();
+/// let rent = Transaction::new(-800).with_category("rent");
+/// let mut s = ScheduledTransaction::new(rent, start, Recurrence::Months { every: 1, day: 31 })
+///     .unwrap();
+///
+/// let due = s.due(NaiveDate::from_ymd_opt(2026, 3, 1).unwrap());
+/// assert_eq!(due.len(), 2);
+/// assert_eq!(s.next_date(), NaiveDate::from_ymd_opt(2026, 3, 31));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTransaction<C = d128>
+where
+    C: Currency,
+{
+    uuid: Uuid,
+    template: Transaction<C>,
+    start: NaiveDate,
+    #[serde(deserialize_with = "valid_recurrence")]
+    recurrence: Recurrence,
+
+    /// The last date that the transaction may occur on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until: Option<NaiveDate>,
+
+    /// The total number of occurrences
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<u32>,
+
+    /// The number of occurrences which have been added to the ledger or skipped
+    next: u32,
+}
+
+impl<C> ScheduledTransaction<C>
+where
+    C: Currency,
+{
+    /// Create a new scheduled transaction, with the first occurrence on or after `start`.
+    ///
+    /// Returns an error if the recurrence is every 0 days, weeks or months, or
+    /// on day 0 of the month.
+    pub fn new(
+        template: Transaction<C>,
+        start: NaiveDate,
+        recurrence: Recurrence,
+    ) -> Result<Self, InvalidRecurrenceError> {
+        Ok(ScheduledTransaction {
+            uuid: Uuid::new_v4(),
+            template,
+            start,
+            recurrence: recurrence.validate()?,
+            until: None,
+            count: None,
+            next: 0,
+        })
+    }
+
+    /// Inline method for ending the schedule after the given date.
+    pub fn with_until(mut self, until: NaiveDate) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Inline method for ending the schedule after a number of occurrences.
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Returns the globally unique id of the scheduled transaction.
+    pub fn uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    /// Returns the transaction which is copied on each occurrence.
+    pub fn template(&self) -> &Transaction<C> {
+        &self.template
+    }
+
+    pub(crate) fn template_mut(&mut self) -> &mut Transaction<C> {
+        &mut self.template
+    }
+
+    /// Returns how often the transaction recurs.
+    pub fn recurrence(&self) -> Recurrence {
+        self.recurrence
+    }
+
+    /// Returns the last date that the transaction may occur on, if set.
+    pub fn until(&self) -> Option<NaiveDate> {
+        self.until
+    }
+
+    /// Returns the total number of occurrences, if set.
+    pub fn count(&self) -> Option<u32> {
+        self.count
+    }
+
+    /// Returns the date of an occurrence, or `None` if the schedule has ended by then.
+    fn occurrence(&self, n: u32) -> Option<NaiveDate> {
+        if self.count.is_some_and(|c| n >= c) {
+            return None;
+        }
+        self.recurrence
+            .occurrence(self.start, n)
+            .filter(|d| self.until.is_none_or(|u| *d <= u))
+    }
+
+    /// Returns the date of the next occurrence, or `None` if the schedule has ended.
+    pub fn next_date(&self) -> Option<NaiveDate> {
+        self.occurrence(self.next)
+    }
+
+    /// Returns the dates of the upcoming occurrences, up to and including `until`.
+    pub fn upcoming(&self, until: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        (self.next..)
+            .map(move |n| self.occurrence(n))
+            .take_while(move |d| d.is_some_and(|d| d <= until))
+            .flatten()
+    }
+
+    /// Returns a transaction for an occurrence on the given date.
+    pub fn instance(&self, date: NaiveDate) -> Transaction<C> {
+        let date = midday(date);
+        let mut t = self
+            .template
+            .clone()
+            .with_uuid(Uuid::new_v4())
+            .with_date_transaction(date)
+            .with_source(Source::Scheduled);
+        t.set_date_created(Utc::now());
+        t
+    }
+
+    /// Returns transactions for all the occurrences up to and including `today`
+    /// which haven't already been returned or skipped.
+    pub fn due(&mut self, today: NaiveDate) -> Vec<Transaction<C>> {
+        let dates: Vec<NaiveDate> = self.upcoming(today).collect();
+        self.next += dates.len() as u32;
+        dates.into_iter().map(|d| self.instance(d)).collect()
+    }
+
+    /// Skip the next occurrence. Returns the date that was skipped, or `None` if
+    /// the schedule has ended.
+    pub fn skip(&mut self) -> Option<NaiveDate> {
+        let date = self.next_date()?;
+        self.next += 1;
+        Some(date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn recurrences() {
+        let start = date(2026, 1, 20);
+        let dates = |r: Recurrence| -> Vec<NaiveDate> {
+            (0..3).map(|n| r.occurrence(start, n).unwrap()).collect()
+        };
+
+        assert_eq!(
+            dates(Recurrence::Weeks(2)),
+            vec![start, date(2026, 2, 3), date(2026, 2, 17)]
+        );
+        assert_eq!(
+            dates(Recurrence::Months { every: 1, day: 15 }),
+            vec![date(2026, 2, 15), date(2026, 3, 15), date(2026, 4, 15)]
+        );
+        assert_eq!(
+            dates(Recurrence::Months { every: 6, day: 31 }),
+            vec![date(2026, 1, 31), date(2026, 7, 31), date(2027, 1, 31)]
+        );
+        // january and february 2026 both end on a saturday
+        assert_eq!(
+            dates(Recurrence::LastBusinessDay(1)),
+            vec![date(2026, 1, 30), date(2026, 2, 27), date(2026, 3, 31)]
+        );
+    }
+
+    #[test]
+    fn end_and_skip() {
+        let t = Transaction::<f64>::with_currency(-10.0);
+        let mut s = ScheduledTransaction::new(t, date(2026, 1, 1), Recurrence::Days(1))
+            .unwrap()
+            .with_count(5)
+            .with_until(date(2026, 1, 4));
+
+        assert_eq!(s.skip(), Some(date(2026, 1, 1)));
+        assert_eq!(s.due(date(2026, 1, 2)).len(), 1);
+        assert_eq!(s.upcoming(date(2026, 12, 31)).count(), 2);
+        assert_eq!(s.due(date(2026, 12, 31)).len(), 2);
+        assert_eq!(s.next_date(), None);
+        assert_eq!(s.skip(), None);
+    }
+
+    #[test]
+    fn zero_recurrence() {
+        let t = Transaction::<f64>::with_currency(-10.0);
+        for r in [
+            Recurrence::Days(0),
+            Recurrence::Weeks(0),
+            Recurrence::Months { every: 0, day: 1 },
+            Recurrence::Months { every: 1, day: 0 },
+            Recurrence::LastBusinessDay(0),
+        ] {
+            let e = ScheduledTransaction::new(t.clone(), date(2026, 1, 1), r).unwrap_err();
+            assert_eq!(e, InvalidRecurrenceError(r));
+        }
+
+        let s = ScheduledTransaction::new(t, date(2026, 1, 1), Recurrence::Days(1)).unwrap();
+        let yaml = serde_yaml::to_string(&s)
+            .unwrap()
+            .replace("Days: 1", "Days: 0");
+        assert!(serde_yaml::from_str::<ScheduledTransaction<f64>>(&yaml).is_err());
+    }
+
+    #[test]
+    fn ends_past_the_last_date() {
+        let t = Transaction::<f64>::with_currency(-10.0);
+        for r in [
+            Recurrence::Days(3_000_000_000),
+            Recurrence::Weeks(u32::MAX),
+            Recurrence::Months {
+                every: u32::MAX,
+                day: 1,
+            },
+            Recurrence::LastBusinessDay(30_000_000),
+        ] {
+            let mut s = ScheduledTransaction::new(t.clone(), date(2020, 1, 1), r).unwrap();
+            assert!(s.skip().is_some());
+            assert_eq!(s.next_date(), None);
+            assert_eq!(s.due(date(2026, 1, 1)).len(), 0);
+        }
+    }
+}
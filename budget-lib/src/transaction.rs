@@ -1,11 +1,17 @@
 //! # Transaction.
 
 use crate::Currency;
-use chrono::{Date, DateTime, Utc};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use decimal::d128;
 use serde_derive::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Returns midday UTC on a date, for the time of a transaction which only has a
+/// date. Midday is on the same date in every timezone.
+pub fn midday(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap())
+}
+
 /// A struct which represents a financial transaction.
 /// Most of the fields are optional, and will only be
 /// serialised if present.
@@ -43,7 +49,7 @@ where
 
     /// An optional category for the transaction
     #[serde(skip_serializing_if = "Option::is_none")]
-    category: Option<String>,
+    category: Option<CategoryRef>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     account: Option<String>,
@@ -64,6 +70,10 @@ where
     /// The source of this transaction. This enum may be used for differentiating between transactions
     /// in a single ledger that came from different sources
     source: Source,
+
+    /// If this transaction is one half of a transfer between two accounts, the uuid of the other half
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transfer: Option<Uuid>,
 }
 
 impl<C> Default for Transaction<C>
@@ -84,6 +94,7 @@ where
             uuid: Uuid::new_v4(),
             reconciled: false,
             source: Source::Manual,
+            transfer: None,
         }
     }
 }
@@ -269,34 +280,63 @@ where
         &self.payee
     }
 
+    /// Set the payee of the transaction.
     pub fn set_payee<S: Into<String>>(&mut self, payee: Option<S>) {
         self.payee = payee.map(S::into);
     }
 
+    /// Inline method for setting the payee of a Transaction.
     pub fn with_payee<S: Into<String>>(mut self, payee: Option<S>) -> Self {
         self.payee = payee.map(S::into);
         self
     }
 
-    pub fn category(&self) -> &Option<String> {
-        &self.category
+    /// Returns the category of the transaction, if set.
+    ///
+    /// Once a transaction is added to a [`Budget`](crate::Budget) it refers to its
+    /// category by id. Use [`Budget::category_name`](crate::Budget::category_name)
+    /// to find the name.
+    pub fn category(&self) -> Option<&CategoryRef> {
+        self.category.as_ref()
+    }
+
+    /// Returns the name of the category, if the transaction refers to its category by name.
+    pub fn category_name(&self) -> Option<&str> {
+        match &self.category {
+            Some(CategoryRef::Name(name)) => Some(name),
+            _ => None,
+        }
     }
 
+    /// Set the category of the transaction by name.
     pub fn set_category<S: Into<String>>(&mut self, category: Option<S>) {
-        self.category = category.map(S::into);
+        self.category = category.map(|c| CategoryRef::Name(c.into()));
     }
 
+    /// Inline method for setting the category of a Transaction by name.
     pub fn with_category<S: Into<String>>(mut self, category: S) -> Self {
-        self.category = Some(category.into());
+        self.set_category(Some(category));
         self
     }
 
+    pub(crate) fn set_category_ref(&mut self, category: Option<CategoryRef>) {
+        self.category = category;
+    }
+
+    /// Returns the account of the transaction, if set.
     pub fn account(&self) -> &Option<String> {
         &self.account
     }
 
-    pub fn set_account<S: Into<String>>(&self) -> &Option<String> {
-        &self.account
+    /// Set the account of the transaction.
+    pub fn set_account<S: Into<String>>(&mut self, account: Option<S>) {
+        self.account = account.map(S::into);
+    }
+
+    /// Inline method for setting the account of the transaction.
+    pub fn with_account<S: Into<String>>(mut self, account: S) -> Self {
+        self.account = Some(account.into());
+        self
     }
 
     /// add tag to transaction, if its not already present
@@ -307,6 +347,7 @@ where
         }
     }
 
+    /// Inline method for adding a tag to a Transaction.
     pub fn with_tag<S: Into<String>>(mut self, tag: S) -> Self {
         self.tag(tag);
         self
@@ -317,6 +358,7 @@ where
         self.tags.retain(|x| x != tag.as_ref());
     }
 
+    /// Inline method for removing a tag from a Transaction.
     pub fn without_tag<S: AsRef<String>>(mut self, tag: S) -> Self {
         self.untag(tag);
         self
@@ -330,43 +372,114 @@ where
         self.tags = t;
     }
 
-    pub fn tags(&self) -> std::slice::Iter<String> {
+    /// Returns an iterator over the tags of the transaction.
+    pub fn tags(&self) -> std::slice::Iter<'_, String> {
         self.tags.iter()
     }
 
+    /// Returns true if the transaction has the tag, or one of its subtags. Tags
+    /// are nested with a '/', so a transaction tagged "holiday/2026" has the tag
+    /// "holiday" too.
+    ///
+    /// # Example
+    /// ```
+    /// use budget_lib::Transaction;
+    ///
+    /// let t = Transaction::new(-20).with_tag("holiday/2026");
+    /// assert!(t.has_tag("holiday"));
+    /// assert!(t.has_tag("holiday/2026"));
+    /// assert!(!t.has_tag("holiday/2025"));
+    /// ```
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| is_within_tag(t, tag))
+    }
+
+    /// Replace each tag with the result of `f`, removing those for which it
+    /// returns None. Returns true if the tags changed.
+    pub(crate) fn map_tags<F: FnMut(&str) -> Option<String>>(&mut self, f: F) -> bool {
+        let tags: Vec<String> = self.tags.iter().map(String::as_str).filter_map(f).collect();
+        if tags == self.tags {
+            return false;
+        }
+        self.set_tags(tags);
+        true
+    }
+
+    /// Returns the non-unique id of the transaction, if set.
     pub fn id(&self) -> Option<u16> {
         self.id
     }
 
+    /// Set the non-unique id of the transaction.
     pub fn set_id<T: Into<u16>>(&mut self, id: Option<T>) {
         self.id = id.map(T::into);
     }
 
+    /// Inline method for setting the non-unique id of a Transaction.
     pub fn with_id<T: Into<u16>>(mut self, id: Option<T>) -> Self {
         self.set_id(id);
         self
     }
 
+    /// Returns the globally unique id of the transaction.
     pub fn uuid(&self) -> &Uuid {
         &self.uuid
     }
 
+    /// Returns the uuid of the other half of a transfer between two accounts, if this
+    /// transaction is part of one.
+    pub fn transfer(&self) -> Option<&Uuid> {
+        self.transfer.as_ref()
+    }
+
+    /// Returns true if the transaction is one half of a transfer between two accounts.
+    ///
+    /// Transfers move money without it entering or leaving the budget, so they
+    /// are left out of income and spending.
+    pub fn is_transfer(&self) -> bool {
+        self.transfer.is_some()
+    }
+
+    pub(crate) fn set_date_created(&mut self, date: DateTime<Utc>) {
+        self.date_created = date;
+    }
+
+    pub(crate) fn with_uuid(mut self, uuid: Uuid) -> Self {
+        self.uuid = uuid;
+        self
+    }
+
+    pub(crate) fn with_transfer(mut self, other: Uuid) -> Self {
+        self.transfer = Some(other);
+        self
+    }
+
+    /// Returns true if the transaction was added from a scheduled transaction.
+    pub fn is_scheduled(&self) -> bool {
+        matches!(self.source, Source::Scheduled)
+    }
+
+    /// Returns true if the transaction has been reconciled.
     pub fn reconciled(&self) -> bool {
         self.reconciled
     }
 
+    /// Set the reconciled state of the transaction.
     pub fn set_reconciled(&mut self, b: bool) {
         self.reconciled = b;
     }
 
+    /// Returns the source of the transaction.
     pub fn source(&self) -> &Source {
         &self.source
     }
 
+    /// Set the source of the transaction.
     pub fn set_source(&mut self, s: Source) {
         self.source = s;
     }
 
+    /// Inline method for setting the source of a Transaction.
     pub fn with_source(mut self, s: Source) -> Self {
         self.set_source(s);
         self
@@ -383,19 +496,107 @@ where
     }
 }
 
+/// The separator between the parts of a nested tag.
+pub(crate) const TAG_SEPARATOR: char = '/';
+
+/// Returns true if `tag` is `parent`, or one of its subtags.
+pub(crate) fn is_within_tag(tag: &str, parent: &str) -> bool {
+    tag.strip_prefix(parent)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(TAG_SEPARATOR))
+}
+
+/// Returns the tag with `old`, or the part of it which is a parent tag, replaced
+/// by `new`.
+pub(crate) fn rename_tag(tag: &str, old: &str, new: &str) -> String {
+    if is_within_tag(tag, old) {
+        format!("{}{}", new, &tag[old.len()..])
+    } else {
+        tag.to_string()
+    }
+}
+
+/// Returns the tag followed by each of its parents, so "a/b/c" gives "a/b/c",
+/// "a/b" and "a".
+pub(crate) fn tag_ancestors(tag: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(tag), |t| t.rfind(TAG_SEPARATOR).map(|i| &t[..i]))
+}
+
+/// The category of a transaction.
+///
+/// A transaction refers to its category by name until it is added to a
+/// [`Budget`](crate::Budget), which gives each category a stable id so that it
+/// can be renamed without changing its transactions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "StoredCategoryRef")]
+pub enum CategoryRef {
+    /// The id of a category in a budget
+    Id(Uuid),
+    /// The name of a category
+    Name(String),
+}
+
+/// A category as it is saved. Earlier versions saved just the name, so a plain
+/// string is always a name, even if it looks like an id.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredCategoryRef {
+    Tagged(TaggedCategoryRef),
+    Name(String),
+}
+
+#[derive(Deserialize)]
+enum TaggedCategoryRef {
+    Id(Uuid),
+    Name(String),
+}
+
+impl From<StoredCategoryRef> for CategoryRef {
+    fn from(stored: StoredCategoryRef) -> Self {
+        match stored {
+            StoredCategoryRef::Tagged(TaggedCategoryRef::Id(id)) => CategoryRef::Id(id),
+            StoredCategoryRef::Tagged(TaggedCategoryRef::Name(name))
+            | StoredCategoryRef::Name(name) => CategoryRef::Name(name),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Source {
     Manual,
     Reconciliation,
+    /// Added from a scheduled transaction
+    Scheduled,
+    /// Imported from a file
+    Import,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{d128, Transaction};
+    use super::{d128, CategoryRef, Transaction, Uuid};
 
     #[test]
     fn constructors() {
         Transaction::<d128>::default();
         Transaction::<f64>::default();
     }
+
+    #[test]
+    fn category_refs() {
+        let id = CategoryRef::Id(Uuid::new_v4());
+        let name = CategoryRef::Name("groceries".to_string());
+        for category in [&id, &name] {
+            let yaml = serde_yaml::to_string(category).unwrap();
+            assert_eq!(
+                &serde_yaml::from_str::<CategoryRef>(&yaml).unwrap(),
+                category
+            );
+        }
+
+        // earlier versions saved the name alone, which may look like an id
+        let legacy = "4f1c2d3e-5a6b-4c7d-8e9f-0a1b2c3d4e5f";
+        assert_eq!(
+            serde_yaml::from_str::<CategoryRef>(legacy).unwrap(),
+            CategoryRef::Name(legacy.to_string())
+        );
+    }
 }